@@ -0,0 +1,379 @@
+// tiff-core/src/async_reader.rs
+//! Asynchronous reading for remote and object-storage sources
+//!
+//! This mirrors the synchronous `TiffDataSource`/`TiffReader` split:
+//! - AsyncTiffDataSource: Trait for anything that can fetch bytes without blocking
+//! - AsyncFileSource: Files read through tokio
+//! - AsyncTiffReader: Reads headers, IFDs and tag values from an async source
+//!
+//! Each operation fetches the bytes it needs up front (one read for the
+//! header, two per IFD, at most one per tag value) and hands them to the
+//! synchronous parser, so both paths decode exactly the same way. Image
+//! data is read with `read_bytes_at` and decoded like any other buffer.
+
+use core::future::Future;
+
+use crate::{
+    error::{Result, TiffError},
+    header::{Endian, TiffHeader},
+    ifd::{FieldType, IfdEntry, ImageFileDirectory, TagValue},
+    reader::{check_read_limit, InMemorySource, TiffDataSource, TiffReader},
+    IfdChainGuard, TiffFile,
+};
+use crate::prelude::*;
+
+/// Trait for data sources that read asynchronously
+///
+/// Implementations can write `async fn read_bytes_at` directly; the
+/// returned future must be `Send` so readers can be used from spawned
+/// tasks.
+pub trait AsyncTiffDataSource {
+    /// Get the total size of the data source
+    fn len(&self) -> usize;
+
+    /// Check if the data source is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read bytes at a specific offset
+    ///
+    /// # Arguments
+    /// * `offset` - Byte offset to read from
+    /// * `count` - Number of bytes to read
+    ///
+    /// # Errors
+    /// Returns error if offset + count exceeds data bounds or overflows
+    fn read_bytes_at(&self, offset: usize, count: usize) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+/// In-memory data completes every read immediately
+impl AsyncTiffDataSource for InMemorySource {
+    fn len(&self) -> usize {
+        TiffDataSource::len(self)
+    }
+
+    async fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        TiffDataSource::read_bytes_at(self, offset, count)
+    }
+}
+
+/// File-backed data source that reads through tokio
+///
+/// Like `FileSource`, the file sits behind a lock because every read is a
+/// seek followed by a read; concurrent reads take turns.
+#[derive(Debug)]
+pub struct AsyncFileSource {
+    file: tokio::sync::Mutex<tokio::fs::File>,
+    len: usize,
+}
+
+impl AsyncFileSource {
+    /// Open a file for reading
+    ///
+    /// # Errors
+    /// Returns `Io` if the file can't be opened or its size read
+    pub async fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len() as usize;
+        Ok(Self {
+            file: tokio::sync::Mutex::new(file),
+            len,
+        })
+    }
+}
+
+impl AsyncTiffDataSource for AsyncFileSource {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    async fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let end = offset.checked_add(count).ok_or(TiffError::OutOfBounds {
+            index: usize::MAX,
+            max: self.len,
+        })?;
+        if end > self.len {
+            return Err(TiffError::OutOfBounds { index: end, max: self.len });
+        }
+        let mut file = self.file.lock().await;
+        file.seek(std::io::SeekFrom::Start(offset as u64)).await?;
+        let mut buf = vec![0; count];
+        file.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+}
+
+/// Reader that parses TIFF structures from an async data source
+///
+/// The async counterpart of `TiffReader`. Call `read_header` first so
+/// BigTIFF files are recognized, then `read_ifds` or `read_ifd_at`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tiff_core::InMemorySource;
+/// use tiff_core::async_reader::AsyncTiffReader;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// // Any AsyncTiffDataSource works here, e.g. one backed by HTTP range requests
+/// let mut reader = AsyncTiffReader::new(InMemorySource::new(std::fs::read("cog.tif")?));
+/// let header = reader.read_header().await?;
+/// let ifds = reader.read_ifds(&header).await?;
+/// let width = ifds[0].find_entry(256).expect("ImageWidth");
+/// println!("{:?}", reader.parse_tag_value(width, header.endianness()).await?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncTiffReader<S: AsyncTiffDataSource> {
+    source: S,
+    /// Whether `read_header` found a BigTIFF header
+    bigtiff: bool,
+    /// Whether non-UTF-8 ASCII text is an error, as in `TiffReader`
+    strict_ascii: bool,
+}
+
+impl<S: AsyncTiffDataSource> AsyncTiffReader<S> {
+    /// Create a new reader with the given data source
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            bigtiff: false,
+            strict_ascii: false,
+        }
+    }
+
+    /// Reject ASCII text that isn't valid UTF-8
+    ///
+    /// Same as `TiffReader::with_strict_ascii`: by default non-UTF-8 text
+    /// is decoded as Latin-1, in strict mode it fails with `InvalidString`.
+    pub fn with_strict_ascii(mut self, strict: bool) -> Self {
+        self.strict_ascii = strict;
+        self
+    }
+
+    /// Get the total size of the data
+    pub fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    /// Check if the data source is empty
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+
+    /// Check if this reader parses IFDs in BigTIFF layout
+    ///
+    /// Set by `read_header`; a reader that hasn't read a header assumes
+    /// classic TIFF.
+    pub fn is_bigtiff(&self) -> bool {
+        self.bigtiff
+    }
+
+    /// Read bytes at a specific offset
+    pub async fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        self.source.read_bytes_at(offset, count).await
+    }
+
+    /// A synchronous reader over bytes fetched from this source
+    fn parser(&self, bytes: Vec<u8>) -> TiffReader<InMemorySource> {
+        TiffReader::with_bigtiff(InMemorySource::new(bytes), self.bigtiff).with_strict_ascii(self.strict_ascii)
+    }
+
+    /// Read the TIFF header at the start of the source
+    ///
+    /// A BigTIFF header switches this reader into BigTIFF mode, so later
+    /// IFDs are parsed with 8-byte counts and offsets.
+    pub async fn read_header(&mut self) -> Result<TiffHeader> {
+        let available = self.len().min(TiffHeader::BIGTIFF_SIZE);
+        let header = TiffHeader::parse(&self.read_bytes_at(0, available).await?)?;
+        self.bigtiff = header.is_bigtiff();
+        Ok(header)
+    }
+
+    /// Read an IFD at the given offset
+    ///
+    /// Takes two reads: the entry count, then the entries and next-IFD
+    /// link together.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the offset points inside the header, or
+    /// `OutOfBounds` if the IFD runs past the end of the source
+    pub async fn read_ifd_at(&self, offset: usize, endian: Endian) -> Result<ImageFileDirectory> {
+        let header_size = if self.bigtiff { TiffHeader::BIGTIFF_SIZE } else { TiffHeader::SIZE };
+        if offset < header_size {
+            return Err(TiffError::MalformedFile {
+                reason: format!("IFD offset {offset} points inside the file header"),
+            });
+        }
+        let layout = self.parser(Vec::new());
+        let count_bytes = self.read_bytes_at(offset, layout.entry_count_size()).await?;
+        let entries = if self.bigtiff {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&count_bytes);
+            usize::try_from(endian.read_u64(bytes)).unwrap_or(usize::MAX)
+        } else {
+            endian.read_u16([count_bytes[0], count_bytes[1]]) as usize
+        };
+
+        // A BigTIFF count can claim far more entries than the source holds
+        let size = entries
+            .checked_mul(layout.ifd_entry_size())
+            .and_then(|size| size.checked_add(layout.entry_count_size() + layout.offset_size()))
+            .filter(|&size| size <= self.len())
+            .ok_or(TiffError::OutOfBounds { index: offset, max: self.len() })?;
+        // The fetched IFD starts at 0 in the parser's source
+        self.parser(self.read_bytes_at(offset, size).await?).read_ifd(0, endian)
+    }
+
+    /// Read every IFD in the main chain
+    ///
+    /// Applies the same loop and length checks as `TiffFile::from_reader`.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the chain loops or is longer than
+    /// `TiffFile::DEFAULT_MAX_IFDS`, or any error from reading an IFD
+    pub async fn read_ifds(&self, header: &TiffHeader) -> Result<Vec<ImageFileDirectory>> {
+        let mut ifds = Vec::new();
+        let mut guard = IfdChainGuard::new(TiffFile::<InMemorySource>::DEFAULT_MAX_IFDS);
+        let mut ifd_offset = header.ifd_offset as usize;
+
+        while ifd_offset != 0 {
+            guard.visit(ifd_offset)?;
+            let ifd = self.read_ifd_at(ifd_offset, header.endianness()).await?;
+            ifd_offset = ifd.next_ifd_offset;
+            ifds.push(ifd);
+        }
+        Ok(ifds)
+    }
+
+    /// Parse the value of an IFD entry
+    ///
+    /// Values that fit in the entry itself need no read; larger ones are
+    /// fetched in a single read.
+    ///
+    /// # Errors
+    /// Same as `TiffReader::parse_tag_value`
+    pub async fn parse_tag_value(&self, entry: &IfdEntry, endian: Endian) -> Result<TagValue> {
+        let field_type = FieldType::from_u16(entry.field_type)?;
+        let total_bytes = usize::try_from(entry.count)
+            .ok()
+            .and_then(|count| count.checked_mul(field_type.byte_size()))
+            .ok_or_else(|| TiffError::InvalidTag {
+                tag: entry.tag,
+                reason: format!("{} {:?} values overflow the value size", entry.count, field_type),
+            })?;
+        check_read_limit(total_bytes, TiffReader::<InMemorySource>::MAX_TAG_VALUE_BYTES)?;
+
+        let layout = self.parser(Vec::new());
+        if total_bytes <= layout.offset_size() {
+            return layout.parse_tag_value(entry, endian);
+        }
+        let data_start = usize::try_from(entry.value_offset).unwrap_or(usize::MAX);
+        let data = self.read_bytes_at(data_start, total_bytes).await?;
+        // The fetched bytes start at 0 in the parser's source
+        let relocated = IfdEntry { value_offset: 0, ..entry.clone() };
+        self.parser(data).parse_tag_value(&relocated, endian)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TestTiff;
+    use crate::tags::tags;
+
+    fn multi_page_file(endian: Endian) -> Vec<u8> {
+        let mut tiff = TestTiff::new(endian);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[640])
+            .ascii(tags::SOFTWARE, "async reader test")
+            .rational(tags::X_RESOLUTION, &[(300, 1)]);
+        tiff.add_ifd().long(tags::IMAGE_WIDTH, &[320]);
+        tiff.build()
+    }
+
+    #[tokio::test]
+    async fn test_matches_sync_parsing() {
+        for endian in [Endian::Little, Endian::Big] {
+            let bytes = multi_page_file(endian);
+            let sync = TiffFile::from_bytes(bytes.clone()).unwrap();
+
+            let mut reader = AsyncTiffReader::new(InMemorySource::new(bytes));
+            let header = reader.read_header().await.unwrap();
+            assert_eq!(header.endianness(), endian);
+            let ifds = reader.read_ifds(&header).await.unwrap();
+            assert_eq!(ifds.len(), sync.image_count());
+
+            for (ifd, sync_ifd) in ifds.iter().zip(&sync.ifds) {
+                assert_eq!(ifd.entry_descriptors(), sync_ifd.entry_descriptors());
+                for entry in &ifd.entries {
+                    let value = reader.parse_tag_value(entry, endian).await.unwrap();
+                    let expected = sync.reader.parse_tag_value(entry, endian).unwrap();
+                    assert_eq!(value.to_display_string(64), expected.to_display_string(64));
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strict_ascii() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().raw(tags::SOFTWARE, 2, 4, b"caf\xE9".to_vec());
+        let bytes = tiff.build();
+
+        for strict in [false, true] {
+            let mut reader = AsyncTiffReader::new(InMemorySource::new(bytes.clone())).with_strict_ascii(strict);
+            let header = reader.read_header().await.unwrap();
+            let ifds = reader.read_ifds(&header).await.unwrap();
+            let value = reader.parse_tag_value(&ifds[0].entries[0], header.endianness()).await;
+            if strict {
+                assert!(matches!(value, Err(TiffError::InvalidString { .. })));
+            } else {
+                assert_eq!(value.unwrap().as_string(), Some("café"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncated_and_looping_chains() {
+        let bytes = multi_page_file(Endian::Little);
+        let mut reader = AsyncTiffReader::new(InMemorySource::new(bytes[..bytes.len() - 20].to_vec()));
+        let header = reader.read_header().await.unwrap();
+        assert!(reader.read_ifds(&header).await.is_err());
+        assert!(matches!(
+            reader.read_ifd_at(usize::MAX - 1, Endian::Little).await,
+            Err(TiffError::OutOfBounds { .. })
+        ));
+
+        // Point the second IFD's next link back at the first
+        let mut bytes = multi_page_file(Endian::Little);
+        let first = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        let sync = TiffFile::from_bytes(bytes.clone()).unwrap();
+        let link = sync.ifds[0].next_ifd_offset + 2 + 12;
+        bytes[link..link + 4].copy_from_slice(&first.to_le_bytes());
+        let mut reader = AsyncTiffReader::new(InMemorySource::new(bytes));
+        let header = reader.read_header().await.unwrap();
+        match reader.read_ifds(&header).await {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.contains("loops"), "{reason}"),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_file_source() {
+        let path = std::env::temp_dir().join(format!("tiff-core-async-{}.tif", std::process::id()));
+        std::fs::write(&path, multi_page_file(Endian::Big)).unwrap();
+
+        let source = AsyncFileSource::open(&path).await.unwrap();
+        assert!(source.read_bytes_at(source.len() - 1, 2).await.is_err());
+        let mut reader = AsyncTiffReader::new(source);
+        let header = reader.read_header().await.unwrap();
+        assert_eq!(reader.read_ifds(&header).await.unwrap().len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
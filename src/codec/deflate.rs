@@ -0,0 +1,516 @@
+// tiff-core/src/codec/deflate.rs
+//! DEFLATE (RFC 1951) decompression
+//!
+//! A small, dependency-free inflater. It decodes Huffman codes one bit at
+//! a time using canonical code counts, which keeps the tables tiny at
+//! some cost in speed. `decompress_deflate` handles the zlib (RFC 1950)
+//! wrapper that TIFF's Deflate compression uses; gzip lives in its own
+//! module.
+//!
+//! Compression = 8 (Deflate) and 32946 (the older Adobe code for the same
+//! thing) are both zlib streams. The inflater is built in rather than
+//! taken from a crate so the library keeps its no-dependency promise.
+
+use crate::error::{Result, TiffError};
+use crate::prelude::*;
+
+/// Longest Huffman code allowed by DEFLATE
+const MAX_CODE_BITS: usize = 15;
+
+/// Base lengths for length symbols 257..=285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// Extra bits for length symbols 257..=285
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances for distance symbols 0..=29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits for distance symbols 0..=29
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// Order in which code length code lengths are stored in a dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+fn malformed(reason: &str) -> TiffError {
+    TiffError::MalformedFile {
+        reason: format!("deflate: {reason}"),
+    }
+}
+
+/// LSB-first bit reader over a byte slice
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0, bit_buf: 0, bit_count: 0 }
+    }
+
+    /// Read `n` (at most 16) bits, least significant first
+    fn bits(&mut self, n: u32) -> Result<u32> {
+        while self.bit_count < n {
+            let byte = *self.data.get(self.pos).ok_or_else(|| malformed("stream ended early"))?;
+            self.pos += 1;
+            self.bit_buf |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buf & ((1u32 << n) - 1);
+        self.bit_buf >>= n;
+        self.bit_count -= n;
+        Ok(value)
+    }
+
+    /// Discard bits up to the next byte boundary
+    fn align(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+
+    /// Number of input bytes consumed, counting a partly used byte as consumed
+    fn consumed(&self) -> usize {
+        self.pos
+    }
+}
+
+/// Canonical Huffman code described by its per-length counts
+struct Huffman {
+    counts: [u16; MAX_CODE_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Build a code from the bit length of each symbol (0 = unused)
+    fn new(lengths: &[u8]) -> Result<Self> {
+        let mut counts = [0u16; MAX_CODE_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+
+        // Reject over-subscribed codes; incomplete ones are allowed (a
+        // single distance code is common)
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(malformed("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0u16; MAX_CODE_BITS + 2];
+        for length in 1..=MAX_CODE_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    /// Decode one symbol
+    fn decode(&self, bits: &mut BitReader<'_>) -> Result<u16> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for &count in &self.counts[1..] {
+            code |= bits.bits(1)? as i32;
+            let count = count as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(malformed("invalid Huffman code"))
+    }
+}
+
+/// Inflate a raw DEFLATE stream
+///
+/// # Arguments
+/// * `data` - The compressed stream, with no zlib or gzip wrapper
+/// * `max_len` - Largest output to produce; longer streams are rejected
+///
+/// # Errors
+/// Returns `MalformedFile` if the stream is truncated or invalid, or if it
+/// inflates to more than `max_len` bytes
+pub fn inflate(data: &[u8], max_len: usize) -> Result<Vec<u8>> {
+    inflate_stream(data, max_len).map(|(out, _)| out)
+}
+
+/// Inflate a raw DEFLATE stream, also returning how many input bytes it used
+///
+/// Wrappers need the consumed length to find the trailer that follows the
+/// final block.
+pub(crate) fn inflate_stream(data: &[u8], max_len: usize) -> Result<(Vec<u8>, usize)> {
+    let (out, consumed) = inflate_prefix(data, max_len)?;
+    Ok((out, consumed.ok_or_else(|| too_long(max_len))?))
+}
+
+/// Inflate a raw DEFLATE stream, stopping once `max_len` bytes are produced
+///
+/// # Returns
+/// The output, and how many input bytes the stream used, or `None` if
+/// decoding stopped at `max_len` before the stream's final block ended
+fn inflate_prefix(data: &[u8], max_len: usize) -> Result<(Vec<u8>, Option<usize>)> {
+    let mut bits = BitReader::new(data);
+    let mut out = Vec::with_capacity(super::output_capacity(data.len(), max_len));
+
+    loop {
+        let last = bits.bits(1)? == 1;
+        let filled = match bits.bits(2)? {
+            0 => stored_block(&mut bits, &mut out, max_len)?,
+            1 => {
+                let (literal, distance) = fixed_codes()?;
+                codes_block(&mut bits, &mut out, max_len, &literal, &distance)?
+            }
+            2 => {
+                let (literal, distance) = dynamic_codes(&mut bits)?;
+                codes_block(&mut bits, &mut out, max_len, &literal, &distance)?
+            }
+            _ => return Err(malformed("invalid block type 3")),
+        };
+        if filled {
+            return Ok((out, None));
+        }
+        if last {
+            break;
+        }
+    }
+    Ok((out, Some(bits.consumed())))
+}
+
+/// Decompress a zlib-wrapped DEFLATE stream (Compression = 8 or 32946)
+///
+/// Checks the two-byte zlib header and the Adler-32 checksum of the
+/// output. Preset dictionaries are not supported.
+///
+/// Like PackBits and LZW, decoding stops once `expected_len` bytes are
+/// produced and any further output is dropped. The checksum covers the
+/// whole stream, so it can only be checked when the stream ends exactly
+/// at `expected_len`.
+///
+/// # Arguments
+/// * `data` - The compressed strip or tile
+/// * `expected_len` - Decoded size of the strip or tile
+///
+/// # Errors
+/// Returns `MalformedFile` for a bad header or checksum, a corrupt
+/// stream, or one that ends before `expected_len` bytes
+pub fn decompress_deflate(data: &[u8], expected_len: usize) -> Result<Vec<u8>> {
+    let zlib = |reason: &str| TiffError::MalformedFile {
+        reason: format!("zlib: {reason}"),
+    };
+    let [cmf, flg, ..] = *data else {
+        return Err(zlib("stream ended early"));
+    };
+    if cmf & 0x0F != 8 || cmf >> 4 > 7 {
+        return Err(zlib("header does not describe a DEFLATE stream"));
+    }
+    if !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) {
+        return Err(zlib("header check bits are wrong"));
+    }
+    if flg & 0x20 != 0 {
+        return Err(zlib("preset dictionaries are not supported"));
+    }
+
+    let (out, consumed) = inflate_prefix(&data[2..], expected_len)?;
+    let Some(consumed) = consumed else {
+        return Ok(out);
+    };
+    if out.len() < expected_len {
+        return Err(zlib(&format!("data ended after {} of {expected_len} bytes", out.len())));
+    }
+    let checksum = data
+        .get(2 + consumed..2 + consumed + 4)
+        .ok_or_else(|| zlib("missing Adler-32 checksum"))?;
+    if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]) != adler32(&out) {
+        return Err(zlib("Adler-32 checksum mismatch"));
+    }
+    Ok(out)
+}
+
+/// Compute the Adler-32 checksum used by zlib
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before `b` could overflow
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// Copy an uncompressed block
+///
+/// Returns `true` if the block didn't fit in `max_len` and was cut short.
+fn stored_block(bits: &mut BitReader<'_>, out: &mut Vec<u8>, max_len: usize) -> Result<bool> {
+    bits.align();
+    let header = bits.data.get(bits.pos..bits.pos + 4).ok_or_else(|| malformed("stream ended early"))?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err(malformed("stored block length check failed"));
+    }
+    bits.pos += 4;
+    let block = bits
+        .data
+        .get(bits.pos..bits.pos + len as usize)
+        .ok_or_else(|| malformed("stream ended early"))?;
+    if out.len() + block.len() > max_len {
+        out.extend_from_slice(&block[..max_len - out.len()]);
+        return Ok(true);
+    }
+    out.extend_from_slice(block);
+    bits.pos += len as usize;
+    Ok(false)
+}
+
+fn too_long(max_len: usize) -> TiffError {
+    malformed(&format!("output exceeds {max_len} bytes"))
+}
+
+/// Build the fixed literal/length and distance codes
+fn fixed_codes() -> Result<(Huffman, Huffman)> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+/// Read the code descriptions at the start of a dynamic block
+fn dynamic_codes(bits: &mut BitReader<'_>) -> Result<(Huffman, Huffman)> {
+    let literal_count = bits.bits(5)? as usize + 257;
+    let distance_count = bits.bits(5)? as usize + 1;
+    let code_length_count = bits.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(malformed("too many codes in dynamic block"));
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = bits.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_code.decode(bits)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *index
+                    .checked_sub(1)
+                    .and_then(|i| lengths.get(i))
+                    .ok_or_else(|| malformed("repeat with no previous length"))?;
+                (previous, 3 + bits.bits(2)? as usize)
+            }
+            17 => (0, 3 + bits.bits(3)? as usize),
+            _ => (0, 11 + bits.bits(7)? as usize),
+        };
+        let run = lengths
+            .get_mut(index..index + repeat)
+            .ok_or_else(|| malformed("code lengths overrun the table"))?;
+        run.fill(value);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(malformed("missing end-of-block code"));
+    }
+
+    let (literal, distance) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literal)?, Huffman::new(distance)?))
+}
+
+/// Decode a Huffman-compressed block until its end-of-block symbol
+///
+/// Returns `true` if the block needed more than `max_len` bytes of output
+/// and was cut short.
+fn codes_block(
+    bits: &mut BitReader<'_>,
+    out: &mut Vec<u8>,
+    max_len: usize,
+    literal: &Huffman,
+    distance: &Huffman,
+) -> Result<bool> {
+    loop {
+        let symbol = literal.decode(bits)? as usize;
+        match symbol {
+            0..=255 => {
+                if out.len() >= max_len {
+                    return Ok(true);
+                }
+                out.push(symbol as u8);
+            }
+            256 => return Ok(false),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(malformed("invalid length symbol"));
+                }
+                let length = LENGTH_BASE[index] as usize + bits.bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+                let index = distance.decode(bits)? as usize;
+                if index >= DIST_BASE.len() {
+                    return Err(malformed("invalid distance symbol"));
+                }
+                let back = DIST_BASE[index] as usize + bits.bits(DIST_EXTRA[index] as u32)? as usize;
+                if back > out.len() {
+                    return Err(malformed("distance reaches before the start of the output"));
+                }
+                // Copies may overlap their own output, so go byte by byte
+                let start = out.len() - back;
+                let copy = length.min(max_len - out.len());
+                for i in 0..copy {
+                    out.push(out[start + i]);
+                }
+                if copy < length {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_block() {
+        let data = [0x01, 0x05, 0x00, 0xFA, 0xFF, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(inflate(&data, 100).unwrap(), b"hello");
+        assert!(inflate(&data, 4).is_err());
+
+        // LEN and NLEN must be complements
+        let bad = [0x01, 0x05, 0x00, 0xFA, 0xFE, b'h', b'e', b'l', b'l', b'o'];
+        assert!(matches!(inflate(&bad, 100), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_fixed_huffman_with_back_references() {
+        // "abcabcabcabc" at level 9, produced by zlib with wbits=-15
+        let data = [0x4B, 0x4C, 0x4A, 0x4E, 0x84, 0x21, 0x00];
+        assert_eq!(inflate(&data, 100).unwrap(), b"abcabcabcabc");
+    }
+
+    #[test]
+    fn test_dynamic_huffman() {
+        // 64 lines of "row N\n" at level 9, produced by zlib with wbits=-15
+        let expected: Vec<u8> = (0..64).flat_map(|i| format!("row {i}\n").into_bytes()).collect();
+        let data = [
+            0x2D, 0xD0, 0xBB, 0x0D, 0x42, 0x31, 0x10, 0x45, 0xC1, 0x9C, 0x2A, 0x28, 0x81, 0xFD, 0x19,
+            0xA8, 0x0A, 0xE9, 0x25, 0xB4, 0x0F, 0xF2, 0x38, 0x39, 0xD9, 0x1D, 0xD9, 0x7B, 0x7D, 0xBE,
+            0xF7, 0xC7, 0xED, 0xFA, 0x37, 0x76, 0x73, 0xB7, 0x76, 0x7B, 0x77, 0x76, 0xD7, 0xEE, 0x73,
+            0xF7, 0xB5, 0xFB, 0xB6, 0x3A, 0x63, 0xEB, 0x30, 0x0F, 0xFB, 0x00, 0x04, 0x21, 0x10, 0xC1,
+            0x08, 0x48, 0x50, 0x92, 0x92, 0xE7, 0x0D, 0x94, 0xA4, 0x24, 0x25, 0x29, 0x49, 0x49, 0x4A,
+            0x52, 0x92, 0x52, 0x94, 0xA2, 0xD4, 0xF9, 0x0A, 0xA5, 0x28, 0x45, 0x29, 0x4A, 0x51, 0x8A,
+            0x52, 0x94, 0xA6, 0x34, 0xA5, 0x29, 0x7D, 0x2E, 0x42, 0x69, 0x4A, 0x53, 0x9A, 0xD2, 0x94,
+            0xA6, 0x0C, 0x65, 0x28, 0x43, 0x19, 0xCA, 0x9C, 0xC3, 0x52, 0x86, 0x32, 0x94, 0xA1, 0x0C,
+            0x65, 0x51, 0x16, 0x65, 0x51, 0x56, 0xDD, 0x7E,
+        ];
+        assert_eq!(inflate(&data, expected.len()).unwrap(), expected);
+    }
+
+    /// `zlib.compress(b"hello hello hello hello", 9)` from Python
+    const ZLIB_HELLO: [u8; 16] = [
+        0x78, 0xDA, 0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x27, 0x01, 0x68, 0x03, 0x08, 0xB1,
+    ];
+
+    #[test]
+    fn test_adler32() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        // Long input exercises the deferred modulo
+        assert_eq!(adler32(&[0xFF; 100_000]), 0x149A_302C);
+    }
+
+    #[test]
+    fn test_decompress_deflate() {
+        assert_eq!(decompress_deflate(&ZLIB_HELLO, 23).unwrap(), b"hello hello hello hello");
+        // Shorter than the layout expects
+        assert!(matches!(decompress_deflate(&ZLIB_HELLO, 30), Err(TiffError::MalformedFile { .. })));
+        // A huge claimed size must not be allocated up front
+        assert!(matches!(decompress_deflate(&ZLIB_HELLO, usize::MAX / 2), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_stops_at_expected_len() {
+        assert_eq!(decompress_deflate(&ZLIB_HELLO, 10).unwrap(), b"hello hell");
+        assert_eq!(decompress_deflate(&ZLIB_HELLO, 0).unwrap(), b"");
+        // Stored blocks are cut the same way
+        let stored = [0x78, 0x01, 0x01, 0x05, 0x00, 0xFA, 0xFF, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(decompress_deflate(&stored, 3).unwrap(), b"hel");
+        // The raw inflater still rejects output past its limit
+        assert!(matches!(inflate(&ZLIB_HELLO[2..], 10), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_decompress_deflate_rejects_bad_wrapper() {
+        let reason = |data: &[u8]| match decompress_deflate(data, 23) {
+            Err(TiffError::MalformedFile { reason }) => reason,
+            other => panic!("expected MalformedFile, got {other:?}"),
+        };
+
+        let mut bad_checksum = ZLIB_HELLO;
+        bad_checksum[15] ^= 1;
+        assert!(reason(&bad_checksum).contains("Adler-32"));
+
+        let mut bad_method = ZLIB_HELLO;
+        bad_method[0] = 0x79;
+        assert!(reason(&bad_method).contains("DEFLATE"));
+
+        let mut bad_check_bits = ZLIB_HELLO;
+        bad_check_bits[1] = 0xDB;
+        assert!(reason(&bad_check_bits).contains("check bits"));
+
+        assert!(reason(&ZLIB_HELLO[..14]).contains("checksum"));
+        assert!(reason(&[0x78]).contains("ended early"));
+    }
+
+    #[test]
+    fn test_truncated_and_invalid_streams() {
+        let data = [0x4B, 0x4C, 0x4A, 0x4E, 0x84, 0x21, 0x00];
+        assert!(matches!(inflate(&data[..3], 100), Err(TiffError::MalformedFile { .. })));
+        // Block type 3 is reserved
+        assert!(matches!(inflate(&[0x07], 100), Err(TiffError::MalformedFile { .. })));
+        // A back-reference into nothing
+        assert!(matches!(inflate(&[0x03, 0x02, 0x00], 100), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_inflate_stream_reports_consumed_bytes() {
+        let mut data = vec![0x4B, 0x4C, 0x4A, 0x4E, 0x84, 0x21, 0x00];
+        data.extend_from_slice(&[0xDE, 0xAD]);
+        let (out, consumed) = inflate_stream(&data, 100).unwrap();
+        assert_eq!(out, b"abcabcabcabc");
+        assert_eq!(consumed, 7);
+    }
+}
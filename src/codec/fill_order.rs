@@ -0,0 +1,37 @@
+// tiff-core/src/codec/fill_order.rs
+//! Bit order normalization (FillOrder = 2)
+//!
+//! FillOrder 2 stores the first pixel of each byte in its least
+//! significant bit. Bit-level decoders such as CCITT fax expect the
+//! default most-significant-first order, so the data has to be reversed
+//! byte by byte before they see it. `TiffImageReader` handles FillOrder
+//! itself when unpacking uncompressed sub-byte samples.
+
+use crate::tags::FillOrder;
+
+/// Reverse the bits of every byte in `buf` if `fill_order` is `LsbToMsb`
+///
+/// Apply this before any bit-level unpacking or decompression; data with
+/// the default `MsbToLsb` order is left untouched.
+pub fn reverse_bits_if_needed(buf: &mut [u8], fill_order: FillOrder) {
+    if fill_order == FillOrder::LsbToMsb {
+        for byte in buf {
+            *byte = byte.reverse_bits();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_bits_if_needed() {
+        let mut data = [0b0000_0001, 0b1100_1010, 0xFF, 0x00];
+        reverse_bits_if_needed(&mut data, FillOrder::MsbToLsb);
+        assert_eq!(data, [0b0000_0001, 0b1100_1010, 0xFF, 0x00]);
+
+        reverse_bits_if_needed(&mut data, FillOrder::LsbToMsb);
+        assert_eq!(data, [0b1000_0000, 0b0101_0011, 0xFF, 0x00]);
+    }
+}
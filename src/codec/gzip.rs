@@ -0,0 +1,157 @@
+// tiff-core/src/codec/gzip.rs
+//! gzip (RFC 1952) unwrapping
+//!
+//! Used for TIFF files that were gzipped as a whole (`.tif.gz`), not for
+//! any compression scheme inside TIFF. Only the first member of a
+//! multi-member gzip file is read.
+
+use crate::codec::deflate::inflate_stream;
+use crate::error::{Result, TiffError};
+use crate::prelude::*;
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+fn malformed(reason: &str) -> TiffError {
+    TiffError::MalformedFile {
+        reason: format!("gzip: {reason}"),
+    }
+}
+
+/// Compute the CRC-32 (IEEE 802.3) checksum used by gzip
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Decompress a gzip file
+///
+/// The header's optional fields are skipped, and the CRC-32 and length
+/// in the trailer are checked against the inflated data.
+///
+/// # Errors
+/// Returns `MalformedFile` for a bad header, a corrupt DEFLATE stream or
+/// a trailer that doesn't match the data
+pub fn decompress_gzip(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < 18 || data[0] != 0x1F || data[1] != 0x8B {
+        return Err(malformed("missing gzip signature"));
+    }
+    if data[2] != 8 {
+        return Err(malformed("unsupported compression method"));
+    }
+    let flags = data[3];
+    let mut pos = 10;
+
+    if flags & FLAG_EXTRA != 0 {
+        let len = data.get(pos..pos + 2).ok_or_else(|| malformed("header ended early"))?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            let end = data
+                .get(pos..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0))
+                .ok_or_else(|| malformed("unterminated header string"))?;
+            pos += end + 1;
+        }
+    }
+    if flags & FLAG_HCRC != 0 {
+        pos += 2;
+    }
+
+    // The trailer's length field bounds the output, so a corrupt stream
+    // can't inflate without limit
+    let size_field = &data[data.len() - 4..];
+    let expected_len = u32::from_le_bytes([size_field[0], size_field[1], size_field[2], size_field[3]]);
+    let body = data.get(pos..).ok_or_else(|| malformed("header ended early"))?;
+    let (out, consumed) = inflate_stream(body, expected_len as usize)?;
+
+    let trailer = body
+        .get(consumed..consumed + 8)
+        .ok_or_else(|| malformed("missing trailer"))?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let len = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&out) {
+        return Err(malformed("CRC-32 mismatch"));
+    }
+    if len != out.len() as u32 {
+        return Err(malformed("length mismatch"));
+    }
+    Ok(out)
+}
+
+/// Wrap data in a gzip container using uncompressed DEFLATE blocks
+#[cfg(test)]
+pub(crate) fn gzip_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF];
+    let mut chunks = data.chunks(0xFFFF).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `gzip.compress(b"hello gzip", mtime=0)` from Python
+    const HELLO: [u8; 30] = [
+        0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xCB, 0x48, 0xCD, 0xC9, 0xC9,
+        0x57, 0x48, 0xAF, 0xCA, 0x2C, 0x00, 0x00, 0x19, 0x6A, 0xD2, 0xDF, 0x0A, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_decompress_gzip() {
+        assert_eq!(decompress_gzip(&HELLO).unwrap(), b"hello gzip");
+        let data = vec![7u8; 70_000];
+        assert_eq!(decompress_gzip(&gzip_stored(&data)).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decompress_gzip_skips_file_name() {
+        let mut data = HELLO[..10].to_vec();
+        data[3] = FLAG_NAME;
+        data.extend_from_slice(b"image.tif\0");
+        data.extend_from_slice(&HELLO[10..]);
+        assert_eq!(decompress_gzip(&data).unwrap(), b"hello gzip");
+    }
+
+    #[test]
+    fn test_decompress_gzip_rejects_corruption() {
+        let mut bad_crc = HELLO;
+        bad_crc[22] ^= 1;
+        assert!(matches!(decompress_gzip(&bad_crc), Err(TiffError::MalformedFile { .. })));
+
+        let mut bad_magic = HELLO;
+        bad_magic[0] = 0;
+        assert!(matches!(decompress_gzip(&bad_magic), Err(TiffError::MalformedFile { .. })));
+
+        assert!(decompress_gzip(&HELLO[..20]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+// tiff-core/src/codec/jpeg.rs
+//! JPEG-in-TIFF stream assembly (Compression = 7)
+//!
+//! This crate doesn't decode JPEG. It does put together the stream an
+//! external decoder expects. Strips and tiles are usually abbreviated
+//! JPEG streams that omit the quantization and Huffman tables. Those
+//! tables are stored once, in the JPEGTables tag (347), as their own
+//! SOI...EOI stream. Splicing puts the tables in front of each block's
+//! scan data so that the result is one complete interchange stream.
+
+use crate::error::{Result, TiffError};
+use crate::prelude::*;
+
+/// JPEG start-of-image marker
+pub const SOI: [u8; 2] = [0xFF, 0xD8];
+/// JPEG end-of-image marker
+pub const EOI: [u8; 2] = [0xFF, 0xD9];
+
+/// Combine the JPEGTables stream with one strip or tile into a full JPEG stream
+///
+/// The tables' trailing EOI and the block's leading SOI are dropped, giving
+/// `SOI, tables, block contents..., EOI`. Without tables, the block is
+/// returned as-is.
+///
+/// # Errors
+/// Returns `MalformedFile` if the block doesn't start with SOI, or the
+/// tables aren't an SOI...EOI stream
+pub fn splice_jpeg_tables(tables: Option<&[u8]>, block: &[u8]) -> Result<Vec<u8>> {
+    if !block.starts_with(&SOI) {
+        return Err(TiffError::MalformedFile {
+            reason: "JPEG strip or tile does not start with an SOI marker".to_string(),
+        });
+    }
+    let Some(tables) = tables else {
+        return Ok(block.to_vec());
+    };
+    if tables.len() < 4 || !tables.starts_with(&SOI) || !tables.ends_with(&EOI) {
+        return Err(TiffError::MalformedFile {
+            reason: "JPEGTables is not an SOI...EOI stream".to_string(),
+        });
+    }
+
+    let mut stream = Vec::with_capacity(tables.len() + block.len() - 4);
+    stream.extend_from_slice(&tables[..tables.len() - 2]);
+    stream.extend_from_slice(&block[2..]);
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLES: [u8; 9] = [0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x03, 0x07, 0xFF, 0xD9];
+    const BLOCK: [u8; 8] = [0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9];
+
+    #[test]
+    fn test_splice_with_tables() {
+        let stream = splice_jpeg_tables(Some(&TABLES), &BLOCK).unwrap();
+        assert_eq!(stream, vec![0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x03, 0x07, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+    }
+
+    #[test]
+    fn test_splice_without_tables() {
+        assert_eq!(splice_jpeg_tables(None, &BLOCK).unwrap(), BLOCK.to_vec());
+    }
+
+    #[test]
+    fn test_splice_rejects_missing_markers() {
+        assert!(matches!(splice_jpeg_tables(None, &BLOCK[2..]), Err(TiffError::MalformedFile { .. })));
+        assert!(matches!(splice_jpeg_tables(Some(&TABLES[..7]), &BLOCK), Err(TiffError::MalformedFile { .. })));
+    }
+}
@@ -0,0 +1,276 @@
+// tiff-core/src/codec/lzw.rs
+//! LZW decompression (Compression = 5)
+//!
+//! TIFF's LZW differs from GIF's in two ways: codes are packed
+//! most-significant bit first, and the code width grows one code early
+//! (to 10 bits once the table holds 511 entries rather than 512). Codes
+//! start at 9 bits and never exceed 12. Code 256 (ClearCode) resets the
+//! table and 257 (EndOfInformation) ends the strip.
+//!
+//! Old-style LZW from pre-6.0 writers (LSB-first packing) is not handled.
+
+use crate::error::{Result, TiffError};
+use crate::prelude::*;
+
+/// Code that resets the table to its 258 initial entries
+const CLEAR_CODE: u16 = 256;
+/// Code that marks the end of the strip or tile
+const END_OF_INFORMATION: u16 = 257;
+/// First code assigned to a multi-byte string
+const FIRST_CODE: usize = 258;
+/// Largest table a 12-bit code can address
+const MAX_ENTRIES: usize = 4096;
+/// Widest code allowed
+const MAX_CODE_WIDTH: u32 = 12;
+
+fn malformed(reason: String) -> TiffError {
+    TiffError::MalformedFile { reason }
+}
+
+/// A table entry: the string for `prefix` followed by `byte`
+#[derive(Clone, Copy)]
+struct Entry {
+    prefix: u16,
+    byte: u8,
+    first: u8,
+    len: u16,
+}
+
+/// Decompress TIFF LZW data until `expected_len` bytes have been produced
+///
+/// Decoding stops at EndOfInformation or once `expected_len` bytes are
+/// produced, whichever comes first; extra output is dropped.
+///
+/// # Errors
+/// Returns `MalformedFile` if the data runs out before `expected_len`
+/// bytes, uses a code that isn't in the table yet, or fills the table
+/// without a ClearCode
+pub fn decompress_lzw(data: &[u8], expected_len: usize) -> Result<Vec<u8>> {
+    let mut table: Vec<Entry> = (0..FIRST_CODE)
+        .map(|i| Entry { prefix: u16::MAX, byte: i as u8, first: i as u8, len: 1 })
+        .collect();
+    let mut out = Vec::with_capacity(super::output_capacity(data.len(), expected_len));
+    let mut width = 9;
+    let mut previous: Option<u16> = None;
+    let mut bit_pos = 0usize;
+
+    while out.len() < expected_len {
+        let Some(code) = read_code(data, bit_pos, width) else {
+            break;
+        };
+        bit_pos += width as usize;
+
+        if code == CLEAR_CODE {
+            table.truncate(FIRST_CODE);
+            width = 9;
+            previous = None;
+            continue;
+        }
+        if code == END_OF_INFORMATION {
+            break;
+        }
+
+        let index = code as usize;
+        match previous {
+            None => {
+                if index >= CLEAR_CODE as usize {
+                    return Err(malformed(format!("LZW code {code} is not in the table yet")));
+                }
+            }
+            Some(prev) => {
+                let first = match table.get(index) {
+                    Some(entry) if index < table.len() => entry.first,
+                    // The code being defined by this very step (the KwKwK case)
+                    _ if index == table.len() => table[prev as usize].first,
+                    _ => return Err(malformed(format!("LZW code {code} is not in the table yet"))),
+                };
+                if table.len() >= MAX_ENTRIES {
+                    return Err(malformed("LZW table overflowed 12-bit codes without a ClearCode".to_string()));
+                }
+                let prefix = table[prev as usize];
+                table.push(Entry { prefix: prev, byte: first, first: prefix.first, len: prefix.len + 1 });
+            }
+        }
+        write_string(&table, index, &mut out);
+        previous = Some(code);
+
+        // Early change: widen as soon as the next entry would need it
+        if table.len() + 1 >= 1 << width && width < MAX_CODE_WIDTH {
+            width += 1;
+        }
+    }
+
+    if out.len() < expected_len {
+        return Err(malformed(format!("LZW data ended after {} of {expected_len} bytes", out.len())));
+    }
+    out.truncate(expected_len);
+    Ok(out)
+}
+
+/// Read a `width`-bit code starting at `bit_pos`, MSB first
+fn read_code(data: &[u8], bit_pos: usize, width: u32) -> Option<u16> {
+    let first_byte = bit_pos / 8;
+    let mut buf = 0u32;
+    for i in 0..3 {
+        buf = (buf << 8) | *data.get(first_byte + i).unwrap_or(&0) as u32;
+    }
+    if bit_pos + width as usize > data.len() * 8 {
+        return None;
+    }
+    let shift = 24 - (bit_pos % 8) as u32 - width;
+    Some(((buf >> shift) & ((1 << width) - 1)) as u16)
+}
+
+/// Append the string for `code` to `out`
+fn write_string(table: &[Entry], code: usize, out: &mut Vec<u8>) {
+    let len = table[code].len as usize;
+    let start = out.len();
+    out.resize(start + len, 0);
+    let mut code = code;
+    for slot in out[start..].iter_mut().rev() {
+        let entry = table[code];
+        *slot = entry.byte;
+        code = entry.prefix as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pack codes MSB-first at the widths a TIFF LZW decoder expects
+    fn pack(codes: &[u16], widths: &[u32]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let (mut buf, mut bits) = (0u64, 0u32);
+        for (&code, &width) in codes.iter().zip(widths) {
+            buf = (buf << width) | code as u64;
+            bits += width;
+            while bits >= 8 {
+                out.push((buf >> (bits - 8)) as u8);
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            out.push((buf << (8 - bits)) as u8);
+        }
+        out
+    }
+
+    /// A straightforward TIFF LZW encoder, mirroring libtiff's
+    fn compress(data: &[u8]) -> Vec<u8> {
+        use std::collections::HashMap;
+        let mut codes = Vec::new();
+        let mut widths = Vec::new();
+        let mut table: HashMap<Vec<u8>, u16> = HashMap::new();
+        let mut next = FIRST_CODE;
+        let mut width = 9;
+        let mut emit = |code: u16, next: usize, width: &mut u32| {
+            if next >= 1 << *width && *width < MAX_CODE_WIDTH {
+                *width += 1;
+            }
+            codes.push(code);
+            widths.push(*width);
+        };
+
+        emit(CLEAR_CODE, next, &mut width);
+        let mut current: Vec<u8> = Vec::new();
+        for &byte in data {
+            let mut extended = current.clone();
+            extended.push(byte);
+            if extended.len() == 1 || table.contains_key(&extended) {
+                current = extended;
+                continue;
+            }
+            let code = if current.len() == 1 { current[0] as u16 } else { table[&current] };
+            emit(code, next, &mut width);
+            table.insert(extended, next as u16);
+            next += 1;
+            if next == MAX_ENTRIES - 2 {
+                emit(CLEAR_CODE, next, &mut width);
+                table.clear();
+                next = FIRST_CODE;
+                width = 9;
+            }
+            current = vec![byte];
+        }
+        if !current.is_empty() {
+            let code = if current.len() == 1 { current[0] as u16 } else { table[&current] };
+            emit(code, next, &mut width);
+            next += 1;
+        }
+        emit(END_OF_INFORMATION, next, &mut width);
+        pack(&codes, &widths)
+    }
+
+    #[test]
+    fn test_tiff6_spec_example() {
+        // The example from section 13 of the TIFF 6.0 specification
+        let data = pack(&[256, 7, 258, 8, 8, 258, 6, 6, 257], &[9; 9]);
+        assert_eq!(decompress_lzw(&data, 9).unwrap(), vec![7, 7, 7, 8, 8, 7, 7, 6, 6]);
+    }
+
+    #[test]
+    fn test_round_trip_through_all_code_widths() {
+        // Pseudo-random bytes fill the table quickly, forcing 10-, 11- and
+        // 12-bit codes and several ClearCodes
+        let mut state = 0x1234_5678u32;
+        let data: Vec<u8> = (0..20_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8 % 64
+            })
+            .collect();
+        let packed = compress(&data);
+        assert_eq!(decompress_lzw(&packed, data.len()).unwrap(), data);
+
+        let runs: Vec<u8> = (0..5000u32).map(|i| (i / 37) as u8).collect();
+        assert_eq!(decompress_lzw(&compress(&runs), runs.len()).unwrap(), runs);
+    }
+
+    #[test]
+    fn test_stops_at_expected_len() {
+        let data = pack(&[256, 7, 258, 8, 8, 258, 6, 6, 257], &[9; 9]);
+        assert_eq!(decompress_lzw(&data, 4).unwrap(), vec![7, 7, 7, 8]);
+    }
+
+    #[test]
+    fn test_malformed_streams() {
+        // Code 300 is far past the 258-entry table
+        let data = pack(&[256, 7, 300, 257], &[9; 4]);
+        match decompress_lzw(&data, 10) {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.contains("300"), "{reason}"),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+
+        // A multi-byte code right after a clear has nothing to extend
+        let data = pack(&[256, 258, 257], &[9; 3]);
+        assert!(matches!(decompress_lzw(&data, 2), Err(TiffError::MalformedFile { .. })));
+
+        // Running out before the expected length
+        let data = pack(&[256, 7, 8, 257], &[9; 4]);
+        assert!(matches!(decompress_lzw(&data, 3), Err(TiffError::MalformedFile { .. })));
+        // A huge claimed size must not be allocated up front
+        assert!(matches!(decompress_lzw(&data, usize::MAX / 2), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_table_overflow_without_clear() {
+        // 3838 new entries take the table to 4096, and one more code overflows it
+        let mut codes = vec![CLEAR_CODE];
+        let mut widths = vec![9];
+        let mut width = 9;
+        for i in 0..3840u32 {
+            codes.push((i % 200) as u16);
+            widths.push(width);
+            let len = FIRST_CODE + i as usize;
+            if len + 1 >= 1 << width && width < MAX_CODE_WIDTH {
+                width += 1;
+            }
+        }
+        let data = pack(&codes, &widths);
+        match decompress_lzw(&data, 10_000) {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.contains("overflowed"), "{reason}"),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+    }
+}
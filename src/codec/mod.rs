@@ -0,0 +1,85 @@
+// tiff-core/src/codec/mod.rs
+//! Decoding of compressed and predicted image data
+//!
+//! Strip and tile bytes go through two stages before they are pixels:
+//! decompression (Compression tag, 259) and then reversal of any
+//! differencing predictor (Predictor tag, 317). This module holds the
+//! implementations of both stages; `TiffImageReader` decides which to run.
+//! The `gzip` module, behind the `gzip` feature, reuses the inflater to
+//! unwrap whole files that were gzipped outside of TIFF, and `fill_order`
+//! normalizes the bit order of LSB-first data for bit-level decoders.
+//! Schemes without a built-in decoder can be plugged in through the
+//! `CompressionCodec` trait.
+
+pub mod deflate;
+pub mod fill_order;
+#[cfg(feature = "gzip")]
+pub mod gzip;
+pub mod jpeg;
+pub mod lzw;
+pub mod packbits;
+pub mod predictor;
+
+pub use deflate::{decompress_deflate, inflate};
+pub use fill_order::reverse_bits_if_needed;
+#[cfg(feature = "gzip")]
+pub use gzip::decompress_gzip;
+pub use jpeg::splice_jpeg_tables;
+pub use lzw::decompress_lzw;
+pub use packbits::decompress_packbits;
+pub use predictor::{apply_horizontal_predictor, unpredict_float, unpredict_horizontal};
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::error::Result;
+use crate::prelude::*;
+
+/// Initial output capacity for decoding `input_len` bytes into `expected_len`
+///
+/// `expected_len` comes from the file's layout tags, so a tiny file can
+/// claim gigabytes. Pre-allocate no more than a generous expansion of the
+/// input and let the output grow past that only as data actually decodes.
+pub(crate) fn output_capacity(input_len: usize, expected_len: usize) -> usize {
+    expected_len.min(input_len.saturating_mul(128))
+}
+
+/// A decompressor for one Compression tag value
+///
+/// Register an implementation on `TiffImageReader::with_codec` to decode
+/// schemes this crate doesn't ship, such as ZSTD or LERC, without the
+/// crate depending on those libraries. A registered codec takes priority
+/// over the built-in one for the same value.
+pub trait CompressionCodec: Send + Sync {
+    /// Decompress one strip or tile
+    ///
+    /// # Arguments
+    /// * `data` - The strip or tile bytes as stored in the file
+    /// * `expected_len` - The decoded size of the strip or tile
+    ///
+    /// # Returns
+    /// The decoded bytes, before any predictor is reversed
+    fn decompress(&self, data: &[u8], expected_len: usize) -> Result<Vec<u8>>;
+}
+
+/// User-supplied codecs keyed by Compression tag value
+#[derive(Default)]
+pub(crate) struct CodecRegistry {
+    codecs: BTreeMap<u32, Box<dyn CompressionCodec>>,
+}
+
+impl CodecRegistry {
+    pub(crate) fn insert(&mut self, compression: u32, codec: Box<dyn CompressionCodec>) {
+        self.codecs.insert(compression, codec);
+    }
+
+    pub(crate) fn get(&self, compression: u32) -> Option<&dyn CompressionCodec> {
+        self.codecs.get(&compression).map(|codec| codec.as_ref())
+    }
+}
+
+impl fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.codecs.keys()).finish()
+    }
+}
@@ -0,0 +1,89 @@
+// tiff-core/src/codec/packbits.rs
+//! PackBits decompression (Compression = 32773)
+//!
+//! PackBits is Apple's byte-oriented run-length encoding. Each run starts
+//! with a header byte `n`, read as a signed byte:
+//! - 0 to 127: copy the next `n + 1` bytes literally
+//! - -1 to -127: repeat the next byte `1 - n` times
+//! - -128: no-op
+
+use crate::error::{Result, TiffError};
+use crate::prelude::*;
+
+/// Decompress PackBits data until `expected_len` bytes have been produced
+///
+/// Any input left after `expected_len` is reached is ignored, and a run
+/// that overshoots is truncated, since each TIFF strip or tile is packed
+/// independently and its decoded size is known from the layout.
+///
+/// # Errors
+/// Returns `MalformedFile` if the input runs out first
+pub fn decompress_packbits(data: &[u8], expected_len: usize) -> Result<Vec<u8>> {
+    let truncated = |produced: usize| TiffError::MalformedFile {
+        reason: format!("PackBits data ended after {produced} of {expected_len} bytes"),
+    };
+
+    let mut out = Vec::with_capacity(super::output_capacity(data.len(), expected_len));
+    let mut input = data.iter().copied();
+    while out.len() < expected_len {
+        let header = input.next().ok_or_else(|| truncated(out.len()))? as i8;
+        match header {
+            0..=127 => {
+                for _ in 0..=header {
+                    out.push(input.next().ok_or_else(|| truncated(out.len()))?);
+                }
+            }
+            -127..=-1 => {
+                let value = input.next().ok_or_else(|| truncated(out.len()))?;
+                let run = 1 - header as isize;
+                out.resize(out.len() + run as usize, value);
+            }
+            -128 => {}
+        }
+    }
+    out.truncate(expected_len);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_and_repeat_runs() {
+        let data = [0x02, 1, 2, 3, 0xFE, 9, 0x80, 0x00, 4];
+        assert_eq!(decompress_packbits(&data, 7).unwrap(), vec![1, 2, 3, 9, 9, 9, 4]);
+    }
+
+    #[test]
+    fn test_tiff6_spec_example() {
+        // The worked example from section 9 of the TIFF 6.0 specification
+        let packed = [
+            0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA, 0x03, 0x80, 0x00, 0x2A, 0x22, 0xF7, 0xAA,
+        ];
+        let unpacked = [
+            0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0x22, 0xAA,
+            0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA,
+        ];
+        assert_eq!(decompress_packbits(&packed, unpacked.len()).unwrap(), unpacked);
+        // Asking for more than the example encodes runs out of input
+        assert!(matches!(
+            decompress_packbits(&packed, unpacked.len() + 1),
+            Err(TiffError::MalformedFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stops_at_expected_len() {
+        assert_eq!(decompress_packbits(&[0xF9, 5, 0x00, 1], 4).unwrap(), vec![5; 4]);
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        assert!(matches!(decompress_packbits(&[0x03, 1, 2], 4), Err(TiffError::MalformedFile { .. })));
+        assert!(matches!(decompress_packbits(&[0xFE], 3), Err(TiffError::MalformedFile { .. })));
+        assert!(matches!(decompress_packbits(&[], 1), Err(TiffError::MalformedFile { .. })));
+        // A huge claimed size must not be allocated up front
+        assert!(matches!(decompress_packbits(&[0x00, 1], usize::MAX / 2), Err(TiffError::MalformedFile { .. })));
+    }
+}
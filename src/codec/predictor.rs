@@ -0,0 +1,280 @@
+// tiff-core/src/codec/predictor.rs
+//! Predictor reversal (tag 317)
+//!
+//! Predictors store each sample as the difference from the sample to its
+//! left in the same row, which makes smooth images compress better.
+//! Decoding is a running sum along each row.
+//!
+//! Prediction only ever runs within one row of one plane. For chunky data
+//! (PlanarConfiguration = 1) the "sample to the left" is the same channel
+//! one pixel over, so the stride is `samples_per_pixel`. For planar data
+//! (PlanarConfiguration = 2) each plane holds a single channel, so callers
+//! must pass `samples_per_pixel = 1` when reversing a plane; passing the
+//! image's real sample count would mix up unrelated values.
+
+use crate::error::{Result, TiffError};
+use crate::header::Endian;
+use crate::prelude::*;
+
+/// Reverse horizontal differencing (Predictor = 2) in place
+///
+/// # Arguments
+/// * `buf` - Decompressed rows; any trailing partial row is left untouched
+/// * `width` - Pixels per row
+/// * `samples_per_pixel` - Interleaved samples per pixel (1 for a planar plane)
+/// * `bits_per_sample` - 8, 16 or 32
+/// * `endian` - Byte order of multi-byte samples in `buf`
+///
+/// # Errors
+/// Returns `UnsupportedFeature` for any other bit depth
+pub fn unpredict_horizontal(
+    buf: &mut [u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bits_per_sample: u32,
+    endian: Endian,
+) -> Result<()> {
+    let stride = samples_per_pixel;
+    let samples_per_row = width * samples_per_pixel;
+    if samples_per_row == 0 {
+        return Ok(());
+    }
+
+    match bits_per_sample {
+        8 => {
+            for row in buf.chunks_exact_mut(samples_per_row) {
+                for i in stride..row.len() {
+                    row[i] = row[i].wrapping_add(row[i - stride]);
+                }
+            }
+        }
+        16 => {
+            for row in buf.chunks_exact_mut(samples_per_row * 2) {
+                for i in stride..samples_per_row {
+                    let left = endian.read_u16([row[(i - stride) * 2], row[(i - stride) * 2 + 1]]);
+                    let value = endian.read_u16([row[i * 2], row[i * 2 + 1]]).wrapping_add(left);
+                    let bytes = match endian {
+                        Endian::Little => value.to_le_bytes(),
+                        Endian::Big => value.to_be_bytes(),
+                    };
+                    row[i * 2..i * 2 + 2].copy_from_slice(&bytes);
+                }
+            }
+        }
+        32 => {
+            for row in buf.chunks_exact_mut(samples_per_row * 4) {
+                for i in stride..samples_per_row {
+                    let at = |j: usize| [row[j * 4], row[j * 4 + 1], row[j * 4 + 2], row[j * 4 + 3]];
+                    let value = endian.read_u32(at(i)).wrapping_add(endian.read_u32(at(i - stride)));
+                    let bytes = match endian {
+                        Endian::Little => value.to_le_bytes(),
+                        Endian::Big => value.to_be_bytes(),
+                    };
+                    row[i * 4..i * 4 + 4].copy_from_slice(&bytes);
+                }
+            }
+        }
+        other => {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!("horizontal predictor with {other}-bit samples"),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reverse horizontal differencing (Predictor = 2) in native byte order
+///
+/// Convenience form of `unpredict_horizontal` for buffers whose 16-bit
+/// samples are already in the machine's byte order, such as a `[u16]`
+/// viewed as bytes. Data straight from a strip or tile is in the file's
+/// byte order and should go through `unpredict_horizontal` instead.
+///
+/// # Arguments
+/// * `buf` - Decompressed rows; any trailing partial row is left untouched
+/// * `width` - Pixels per row
+/// * `samples_per_pixel` - Interleaved samples per pixel (1 for a planar plane)
+/// * `bits_per_sample` - 8 or 16
+///
+/// # Errors
+/// Returns `UnsupportedFeature` for any other bit depth
+pub fn apply_horizontal_predictor(buf: &mut [u8], width: u32, samples_per_pixel: u32, bits_per_sample: u8) -> Result<()> {
+    if !matches!(bits_per_sample, 8 | 16) {
+        return Err(TiffError::UnsupportedFeature {
+            feature: format!("horizontal predictor with {bits_per_sample}-bit samples"),
+        });
+    }
+    let endian = if cfg!(target_endian = "big") { Endian::Big } else { Endian::Little };
+    unpredict_horizontal(buf, width as usize, samples_per_pixel as usize, bits_per_sample as u32, endian)
+}
+
+/// Reverse floating point prediction (Predictor = 3) in place
+///
+/// Each row is stored as byte planes (most significant byte of every
+/// sample first), byte-wise differenced with a stride of
+/// `samples_per_pixel`. This undoes the differencing and re-interleaves
+/// the bytes into samples in the given byte order.
+///
+/// # Arguments
+/// * `buf` - Decompressed rows; any trailing partial row is left untouched
+/// * `width` - Pixels per row
+/// * `samples_per_pixel` - Interleaved samples per pixel (1 for a planar plane)
+/// * `bytes_per_sample` - 2, 4 or 8
+/// * `endian` - Byte order to write the reassembled samples in
+///
+/// # Errors
+/// Returns `UnsupportedFeature` for any other sample size
+pub fn unpredict_float(
+    buf: &mut [u8],
+    width: usize,
+    samples_per_pixel: usize,
+    bytes_per_sample: usize,
+    endian: Endian,
+) -> Result<()> {
+    if !matches!(bytes_per_sample, 2 | 4 | 8) {
+        return Err(TiffError::UnsupportedFeature {
+            feature: format!("floating point predictor with {}-bit samples", bytes_per_sample * 8),
+        });
+    }
+    let samples_per_row = width * samples_per_pixel;
+    if samples_per_row == 0 {
+        return Ok(());
+    }
+
+    let mut planes = vec![0; samples_per_row * bytes_per_sample];
+    for row in buf.chunks_exact_mut(samples_per_row * bytes_per_sample) {
+        for i in samples_per_pixel..row.len() {
+            row[i] = row[i].wrapping_add(row[i - samples_per_pixel]);
+        }
+        planes.copy_from_slice(row);
+        for sample in 0..samples_per_row {
+            for byte in 0..bytes_per_sample {
+                let position = match endian {
+                    Endian::Big => byte,
+                    Endian::Little => bytes_per_sample - 1 - byte,
+                };
+                row[sample * bytes_per_sample + position] = planes[byte * samples_per_row + sample];
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply 8-bit horizontal differencing, the inverse of `unpredict_horizontal`
+    fn predict_u8(row: &[u8], stride: usize) -> Vec<u8> {
+        (0..row.len())
+            .map(|i| if i < stride { row[i] } else { row[i].wrapping_sub(row[i - stride]) })
+            .collect()
+    }
+
+    #[test]
+    fn test_horizontal_u8_chunky() {
+        let original = [10, 20, 30, 15, 25, 35, 12, 200, 40];
+        let mut buf = predict_u8(&original, 3);
+        assert_eq!(buf[3..6], [5, 5, 5]);
+        unpredict_horizontal(&mut buf, 3, 3, 8, Endian::Little).unwrap();
+        assert_eq!(buf, original);
+    }
+
+    #[test]
+    fn test_horizontal_chunky_vs_planar() {
+        // The same 3-pixel RGB row, stored interleaved and as three planes
+        let chunky = [10, 20, 30, 15, 25, 35, 12, 200, 40];
+        let planes: Vec<Vec<u8>> = (0..3).map(|c| chunky.iter().skip(c).step_by(3).copied().collect()).collect();
+
+        let mut chunky_buf = predict_u8(&chunky, 3);
+        unpredict_horizontal(&mut chunky_buf, 3, 3, 8, Endian::Little).unwrap();
+
+        let mut planar_result = Vec::new();
+        for plane in &planes {
+            let mut plane_buf = predict_u8(plane, 1);
+            unpredict_horizontal(&mut plane_buf, 3, 1, 8, Endian::Little).unwrap();
+            assert_eq!(&plane_buf, plane);
+            planar_result.push(plane_buf);
+        }
+
+        let reinterleaved: Vec<u8> = (0..3).flat_map(|x| planar_result.iter().map(move |p| p[x])).collect();
+        assert_eq!(chunky_buf, reinterleaved);
+
+        // Reversing the concatenated planes with the chunky stride corrupts them
+        let mut wrong: Vec<u8> = planes.iter().flat_map(|plane| predict_u8(plane, 1)).collect();
+        unpredict_horizontal(&mut wrong, 3, 3, 8, Endian::Little).unwrap();
+        assert_ne!(wrong, planes.concat());
+    }
+
+    #[test]
+    fn test_horizontal_u16_both_byte_orders() {
+        let original = [1000u16, 65535, 3];
+        for endian in [Endian::Little, Endian::Big] {
+            let encode = |v: u16| match endian {
+                Endian::Little => v.to_le_bytes(),
+                Endian::Big => v.to_be_bytes(),
+            };
+            let diffs = [original[0], original[1].wrapping_sub(original[0]), original[2].wrapping_sub(original[1])];
+            let mut buf: Vec<u8> = diffs.iter().flat_map(|&v| encode(v)).collect();
+            unpredict_horizontal(&mut buf, 3, 1, 16, endian).unwrap();
+            let expected: Vec<u8> = original.iter().flat_map(|&v| encode(v)).collect();
+            assert_eq!(buf, expected);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_rejects_odd_bit_depth() {
+        let mut buf = [0; 4];
+        assert!(matches!(
+            unpredict_horizontal(&mut buf, 4, 1, 4, Endian::Little),
+            Err(TiffError::UnsupportedFeature { .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_horizontal_predictor_rgb() {
+        let original = [10, 20, 30, 15, 25, 35, 12, 200, 40];
+        let mut buf = predict_u8(&original, 3);
+        apply_horizontal_predictor(&mut buf, 3, 3, 8).unwrap();
+        assert_eq!(buf, original);
+
+        let original = [1000u16, 2000, 3000, 1500, 65535, 0];
+        let diffs: Vec<u16> = (0..6)
+            .map(|i| if i < 3 { original[i] } else { original[i].wrapping_sub(original[i - 3]) })
+            .collect();
+        let mut buf: Vec<u8> = diffs.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        apply_horizontal_predictor(&mut buf, 2, 3, 16).unwrap();
+        let expected: Vec<u8> = original.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        assert_eq!(buf, expected);
+
+        for bits in [4, 12, 32] {
+            assert!(matches!(
+                apply_horizontal_predictor(&mut buf, 2, 3, bits),
+                Err(TiffError::UnsupportedFeature { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_float_predictor_round_trip() {
+        let values = [1.5f32, -2.25, 100.0, 0.0, 3.75, -0.5];
+        for (endian, samples_per_pixel) in [(Endian::Little, 2), (Endian::Big, 2), (Endian::Little, 1)] {
+            // Encode: split into MSB-first byte planes, then difference with the sample stride
+            let count = values.len();
+            let mut planes = vec![0u8; count * 4];
+            for (i, value) in values.iter().enumerate() {
+                for (byte, b) in value.to_be_bytes().iter().enumerate() {
+                    planes[byte * count + i] = *b;
+                }
+            }
+            let mut buf = predict_u8(&planes, samples_per_pixel);
+
+            unpredict_float(&mut buf, count / samples_per_pixel, samples_per_pixel, 4, endian).unwrap();
+            let decoded: Vec<f32> = buf
+                .chunks_exact(4)
+                .map(|b| f32::from_bits(endian.read_u32([b[0], b[1], b[2], b[3]])))
+                .collect();
+            assert_eq!(decoded, values);
+        }
+    }
+}
@@ -0,0 +1,341 @@
+// tiff-core/src/color.rs
+//! Color space conversion
+//!
+//! TIFF stores YCbCr images (PhotometricInterpretation 6) the way JPEG
+//! does: a luma sample plus two chroma samples, with the chroma often
+//! subsampled. Conversion to RGB follows section 21 of the TIFF 6.0
+//! specification, using the YCbCrCoefficients, ReferenceBlackWhite and
+//! YCbCrSubSampling tags when present.
+//!
+//! CMYK (PhotometricInterpretation 5, InkSet 1) is converted with the
+//! naive device formula. That ignores any embedded ICC profile, so the
+//! colors are only approximate.
+
+use crate::{
+    error::{Result, TiffError},
+    header::Endian,
+    ifd::ImageFileDirectory,
+    reader::{TiffDataSource, TiffReader},
+    tags::tags,
+};
+use crate::prelude::*;
+
+/// Parameters for converting YCbCr samples to RGB
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YCbCrParams {
+    /// LumaRed, LumaGreen and LumaBlue
+    pub coefficients: [f64; 3],
+    /// Black and white codes for Y, Cb and Cr, in that order
+    pub reference_black_white: [f64; 6],
+    /// Chroma subsampling as (horizontal, vertical)
+    pub sub_sampling: (u16, u16),
+}
+
+impl Default for YCbCrParams {
+    /// CCIR 601 coefficients, full-range references and the TIFF default
+    /// (2, 2) subsampling
+    fn default() -> Self {
+        Self {
+            coefficients: [0.299, 0.587, 0.114],
+            reference_black_white: [0.0, 255.0, 128.0, 255.0, 128.0, 255.0],
+            sub_sampling: (2, 2),
+        }
+    }
+}
+
+impl YCbCrParams {
+    /// Read the conversion parameters of an IFD, defaulting any missing tag
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if a tag is present with the wrong number of values
+    pub fn from_ifd<T: TiffDataSource>(
+        ifd: &ImageFileDirectory,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            coefficients: ifd.ycbcr_coefficients(reader, endian)?.unwrap_or(defaults.coefficients),
+            reference_black_white: ifd
+                .reference_black_white(reader, endian)?
+                .unwrap_or(defaults.reference_black_white),
+            sub_sampling: ifd.ycbcr_sub_sampling(reader, endian)?.unwrap_or(defaults.sub_sampling),
+        })
+    }
+
+    /// Convert one YCbCr sample to RGB
+    pub fn to_rgb(&self, y: u8, cb: u8, cr: u8) -> (u8, u8, u8) {
+        let [luma_red, luma_green, luma_blue] = self.coefficients;
+        let rbw = &self.reference_black_white;
+        // Scale each component from its reference range: Y to 0..=255 and
+        // the chroma to -127..=127 around zero
+        let y = (y as f64 - rbw[0]) * 255.0 / (rbw[1] - rbw[0]);
+        let cb = (cb as f64 - rbw[2]) * 127.0 / (rbw[3] - rbw[2]);
+        let cr = (cr as f64 - rbw[4]) * 127.0 / (rbw[5] - rbw[4]);
+
+        let red = cr * (2.0 - 2.0 * luma_red) + y;
+        let blue = cb * (2.0 - 2.0 * luma_blue) + y;
+        let green = (y - luma_blue * blue - luma_red * red) / luma_green;
+        // Adding 0.5 before truncating rounds without needing std's f64::round
+        let clamp = |v: f64| (v.clamp(0.0, 255.0) + 0.5) as u8;
+        (clamp(red), clamp(green), clamp(blue))
+    }
+}
+
+/// Convert one YCbCr sample to RGB with the default CCIR 601 parameters
+pub fn ycbcr_to_rgb(y: u8, cb: u8, cr: u8) -> (u8, u8, u8) {
+    YCbCrParams::default().to_rgb(y, cb, cr)
+}
+
+/// Convert 8-bit chunky YCbCr image data to interleaved RGB
+///
+/// `data` is laid out as TIFF stores it: with (h, v) subsampling, each
+/// block of h x v pixels is h * v luma samples in row-major order
+/// followed by one Cb and one Cr sample, and blocks run left to right,
+/// top to bottom. The chroma of a block is replicated across its pixels.
+/// Blocks that hang over the right or bottom edge are cropped.
+///
+/// # Returns
+/// `width * height * 3` bytes of RGB
+///
+/// # Errors
+/// Returns `InvalidTag` if the subsampling factors aren't 1, 2 or 4 with
+/// vertical no greater than horizontal, or `InsufficientData` if `data`
+/// is shorter than the image needs
+pub fn convert_ycbcr_buffer(data: &[u8], width: u32, height: u32, params: &YCbCrParams) -> Result<Vec<u8>> {
+    let (h, v) = params.sub_sampling;
+    if !matches!(h, 1 | 2 | 4) || !matches!(v, 1 | 2 | 4) || v > h {
+        return Err(TiffError::InvalidTag {
+            tag: tags::YCBCR_SUB_SAMPLING,
+            reason: format!("unsupported subsampling ({h}, {v})"),
+        });
+    }
+    let (h, v) = (h as usize, v as usize);
+    let (width, height) = (width as usize, height as usize);
+    let blocks_across = width.div_ceil(h);
+    let block_size = h * v + 2;
+    let needed = blocks_across * height.div_ceil(v) * block_size;
+    if data.len() < needed {
+        return Err(TiffError::InsufficientData {
+            operation: "converting YCbCr data",
+            needed,
+            available: data.len(),
+        });
+    }
+
+    let mut rgb = vec![0; width * height * 3];
+    for (index, block) in data[..needed].chunks_exact(block_size).enumerate() {
+        let (block_x, block_y) = (index % blocks_across * h, index / blocks_across * v);
+        let (cb, cr) = (block[h * v], block[h * v + 1]);
+        for dy in 0..v {
+            for dx in 0..h {
+                let (x, y) = (block_x + dx, block_y + dy);
+                if x < width && y < height {
+                    let (r, g, b) = params.to_rgb(block[dy * h + dx], cb, cr);
+                    let out = (y * width + x) * 3;
+                    rgb[out..out + 3].copy_from_slice(&[r, g, b]);
+                }
+            }
+        }
+    }
+    Ok(rgb)
+}
+
+/// Convert one CMYK sample to RGB
+///
+/// This is the naive device conversion `R = (255 - C) * (255 - K) / 255`
+/// (likewise for G from M and B from Y), not an ICC-accurate one.
+pub fn cmyk_to_rgb(c: u8, m: u8, y: u8, k: u8) -> (u8, u8, u8) {
+    let channel = |ink: u8| (((255 - ink) as u32 * (255 - k) as u32 + 127) / 255) as u8;
+    (channel(c), channel(m), channel(y))
+}
+
+/// Convert chunky 8-bit CMYK data to RGB
+///
+/// Each pixel's first four samples are converted with [`cmyk_to_rgb`];
+/// any further samples (alpha or other extra samples) are copied after
+/// the RGB unchanged, so pixels come out `samples_per_pixel - 1` bytes
+/// wide.
+///
+/// # Errors
+/// Returns `InvalidTag` if `samples_per_pixel` is less than 4, or
+/// `InsufficientData` if `buf` ends partway through a pixel
+pub fn convert_cmyk_buffer(buf: &[u8], samples_per_pixel: usize) -> Result<Vec<u8>> {
+    if samples_per_pixel < 4 {
+        return Err(TiffError::InvalidTag {
+            tag: tags::SAMPLES_PER_PIXEL,
+            reason: format!("CMYK needs at least 4 samples, found {samples_per_pixel}"),
+        });
+    }
+    if !buf.len().is_multiple_of(samples_per_pixel) {
+        return Err(TiffError::InsufficientData {
+            operation: "converting CMYK data",
+            needed: buf.len().next_multiple_of(samples_per_pixel),
+            available: buf.len(),
+        });
+    }
+
+    let mut rgb = Vec::with_capacity(buf.len() / samples_per_pixel * (samples_per_pixel - 1));
+    for pixel in buf.chunks_exact(samples_per_pixel) {
+        let (r, g, b) = cmyk_to_rgb(pixel[0], pixel[1], pixel[2], pixel[3]);
+        rgb.extend_from_slice(&[r, g, b]);
+        rgb.extend_from_slice(&pixel[4..]);
+    }
+    Ok(rgb)
+}
+
+/// RGB pixels converted from an image's CMYK data
+#[derive(Debug, Clone, PartialEq)]
+pub struct CmykConversion {
+    /// RGB samples followed by any extra samples, for each pixel
+    pub pixels: Vec<u8>,
+    /// Samples per output pixel: 3 plus the image's extra samples
+    pub samples_per_pixel: usize,
+    /// Whether the image embeds an ICC profile (tag 34675) that the
+    /// device conversion ignored; if so, a color-managed conversion
+    /// through that profile would give more accurate colors
+    pub icc_profile_skipped: bool,
+}
+
+/// Convert an image's decoded CMYK data to RGB
+///
+/// Reads SamplesPerPixel from the IFD and notes whether color management
+/// was skipped because an ICC profile is present.
+///
+/// # Errors
+/// Returns the errors of [`convert_cmyk_buffer`]
+pub fn convert_cmyk_image<T: TiffDataSource>(
+    ifd: &ImageFileDirectory,
+    reader: &TiffReader<T>,
+    endian: Endian,
+    buf: &[u8],
+) -> Result<CmykConversion> {
+    let samples_per_pixel = ifd.samples_per_pixel_or_default(reader, endian)? as usize;
+    Ok(CmykConversion {
+        pixels: convert_cmyk_buffer(buf, samples_per_pixel)?,
+        samples_per_pixel: samples_per_pixel - 1,
+        icc_profile_skipped: ifd.find_entry(tags::ICC_PROFILE).is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{TestTiff, first_ifd};
+
+    fn full_resolution() -> YCbCrParams {
+        YCbCrParams { sub_sampling: (1, 1), ..YCbCrParams::default() }
+    }
+
+    #[test]
+    fn test_ycbcr_to_rgb() {
+        assert_eq!(ycbcr_to_rgb(0, 128, 128), (0, 0, 0));
+        assert_eq!(ycbcr_to_rgb(255, 128, 128), (255, 255, 255));
+        assert_eq!(ycbcr_to_rgb(128, 128, 128), (128, 128, 128));
+        // Pure red, green and blue as JPEG encodes them
+        assert_eq!(ycbcr_to_rgb(76, 85, 255), (254, 0, 0));
+        assert_eq!(ycbcr_to_rgb(150, 44, 21), (0, 255, 1));
+        assert_eq!(ycbcr_to_rgb(29, 255, 107), (0, 0, 254));
+    }
+
+    #[test]
+    fn test_convert_full_resolution() {
+        let data = [0, 128, 128, 255, 128, 128, 76, 85, 255, 29, 255, 107];
+        let rgb = convert_ycbcr_buffer(&data, 2, 2, &full_resolution()).unwrap();
+        assert_eq!(rgb, vec![0, 0, 0, 255, 255, 255, 254, 0, 0, 0, 0, 254]);
+
+        assert!(matches!(
+            convert_ycbcr_buffer(&data[..11], 2, 2, &full_resolution()),
+            Err(TiffError::InsufficientData { needed: 12, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reference_black_white() {
+        // Studio range: Y from 16 to 235, chroma 128 +/- 112
+        let params = YCbCrParams {
+            reference_black_white: [16.0, 235.0, 128.0, 240.0, 128.0, 240.0],
+            ..full_resolution()
+        };
+        assert_eq!(params.to_rgb(16, 128, 128), (0, 0, 0));
+        assert_eq!(params.to_rgb(235, 128, 128), (255, 255, 255));
+    }
+
+    #[test]
+    fn test_convert_subsampled() {
+        // 3x2 image with (2, 2) subsampling: two blocks, the second cropped
+        // to one column
+        let data = [10, 20, 30, 40, 128, 128, 50, 60, 70, 80, 128, 128];
+        let rgb = convert_ycbcr_buffer(&data, 3, 2, &YCbCrParams::default()).unwrap();
+        let gray: Vec<u8> = rgb.chunks(3).map(|p| p[0]).collect();
+        assert_eq!(gray, vec![10, 20, 50, 30, 40, 70]);
+
+        // (2, 1): one chroma pair per two pixels of a row
+        let params = YCbCrParams { sub_sampling: (2, 1), ..YCbCrParams::default() };
+        let data = [0, 255, 128, 255, 76, 76, 85, 255];
+        let rgb = convert_ycbcr_buffer(&data, 2, 2, &params).unwrap();
+        assert_eq!(rgb, vec![178, 0, 0, 255, 164, 255, 254, 0, 0, 254, 0, 0]);
+
+        let params = YCbCrParams { sub_sampling: (1, 2), ..YCbCrParams::default() };
+        assert!(matches!(
+            convert_ycbcr_buffer(&data, 2, 2, &params),
+            Err(TiffError::InvalidTag { tag: tags::YCBCR_SUB_SAMPLING, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cmyk_to_rgb() {
+        assert_eq!(cmyk_to_rgb(0, 0, 0, 0), (255, 255, 255));
+        assert_eq!(cmyk_to_rgb(0, 0, 0, 255), (0, 0, 0));
+        assert_eq!(cmyk_to_rgb(255, 0, 0, 0), (0, 255, 255));
+        assert_eq!(cmyk_to_rgb(0, 128, 255, 128), (127, 63, 0));
+    }
+
+    #[test]
+    fn test_convert_cmyk_buffer() {
+        let rgb = convert_cmyk_buffer(&[0, 0, 0, 0, 255, 255, 0, 0], 4).unwrap();
+        assert_eq!(rgb, vec![255, 255, 255, 0, 0, 255]);
+
+        // Alpha passes through
+        let rgba = convert_cmyk_buffer(&[0, 0, 0, 255, 7, 0, 255, 0, 0, 200], 5).unwrap();
+        assert_eq!(rgba, vec![0, 0, 0, 7, 255, 0, 255, 200]);
+
+        assert!(matches!(convert_cmyk_buffer(&[0; 6], 3), Err(TiffError::InvalidTag { .. })));
+        assert!(matches!(
+            convert_cmyk_buffer(&[0; 6], 4),
+            Err(TiffError::InsufficientData { needed: 8, available: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_convert_cmyk_image_notes_icc_profile() {
+        let convert = |icc: bool| {
+            let mut tiff = TestTiff::new(Endian::Little);
+            tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).short(tags::SAMPLES_PER_PIXEL, &[4]);
+            if icc {
+                tiff.raw(tags::ICC_PROFILE, 7, 4, b"icc!".to_vec());
+            }
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+            convert_cmyk_image(&ifd, &reader, endian, &[0, 0, 0, 0]).unwrap()
+        };
+        let plain = convert(false);
+        assert_eq!((plain.pixels, plain.samples_per_pixel), (vec![255, 255, 255], 3));
+        assert!(!plain.icc_profile_skipped);
+        assert!(convert(true).icc_profile_skipped);
+    }
+
+    #[test]
+    fn test_params_from_ifd() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[1])
+            .short(tags::YCBCR_SUB_SAMPLING, &[1, 1])
+            .rational(tags::YCBCR_COEFFICIENTS, &[(2126, 10000), (7152, 10000), (722, 10000)]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let params = YCbCrParams::from_ifd(&ifd, &reader, endian).unwrap();
+        assert_eq!(params.sub_sampling, (1, 1));
+        assert_eq!(params.coefficients, [0.2126, 0.7152, 0.0722]);
+        assert_eq!(params.reference_black_white, YCbCrParams::default().reference_black_white);
+    }
+}
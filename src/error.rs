@@ -1,6 +1,8 @@
 // tiff-core/src/error.rs
 //! Error types for TIFF operations
 
+use crate::prelude::*;
+
 /// TIFF-specific error type
 #[derive(Debug)]
 pub enum TiffError {
@@ -65,10 +67,14 @@ pub enum TiffError {
         /// Context about where the invalid string was found
         context: String,
     },
+
+    /// I/O error from a file-backed data source
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
 }
 
-impl std::fmt::Display for TiffError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for TiffError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             TiffError::InsufficientData { operation, needed, available } => {
                 write!(f, "Insufficient data for {operation}: needed {needed} bytes, but only {available} available")
@@ -97,17 +103,36 @@ impl std::fmt::Display for TiffError {
             TiffError::InvalidString { context } => {
                 write!(f, "Invalid string data in {context}")
             }
+            #[cfg(feature = "std")]
+            TiffError::Io(error) => {
+                write!(f, "I/O error: {error}")
+            }
         }
     }
 }
 
-impl std::error::Error for TiffError {}
+impl core::error::Error for TiffError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            TiffError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for TiffError {
+    fn from(error: std::io::Error) -> Self {
+        TiffError::Io(error)
+    }
+}
 
 /// Result type for TIFF operations
 /// 
 /// This is a convenience alias that saves you from writing 
 /// `Result<T, TiffError>` everywhere
-pub type Result<T> = std::result::Result<T, TiffError>;
+pub type Result<T> = core::result::Result<T, TiffError>;
 
 #[cfg(test)]
 mod tests {
@@ -146,4 +171,14 @@ mod tests {
             "Index 100 out of bounds (maximum: 50)"
         );
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_error() {
+        use core::error::Error;
+        let error = TiffError::from(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "short read"));
+        assert_eq!(error.to_string(), "I/O error: short read");
+        assert!(error.source().is_some());
+        assert!(TiffError::InvalidMagic { found: 0 }.source().is_none());
+    }
 }
\ No newline at end of file
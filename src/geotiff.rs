@@ -0,0 +1,518 @@
+// tiff-core/src/geotiff.rs
+//! GeoTIFF metadata
+//!
+//! GeoTIFF stores its georeferencing keys in three TIFF tags: the
+//! GeoKeyDirectory (34735), an array of shorts, plus GeoDoubleParams
+//! (34736) and GeoAsciiParams (34737), which hold the values too large to
+//! fit in a directory entry. This module resolves those tags into keys.
+//!
+//! The raster-to-model mapping lives in separate tags: ModelTiepoint
+//! (33922) with ModelPixelScale (33550), or a full ModelTransformation
+//! (34264) matrix. [`GeoTransform`] turns either form into an affine
+//! transform.
+
+use crate::{
+    error::{Result, TiffError},
+    header::Endian,
+    ifd::{FieldType, ImageFileDirectory, TagValue},
+    reader::{TiffDataSource, TiffReader},
+    tags::tags,
+};
+use crate::prelude::*;
+
+/// The value of a single GeoKey
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoKeyValue {
+    /// Short values, stored inline or in the GeoKeyDirectory itself
+    Shorts(Vec<u16>),
+    /// Values from GeoDoubleParams
+    Doubles(Vec<f64>),
+    /// A string from GeoAsciiParams, without its `|` terminator
+    Ascii(String),
+}
+
+/// A GeoKey: its numeric ID and resolved value
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoKey {
+    /// Key ID, e.g. 1024 for GTModelTypeGeoKey
+    pub id: u16,
+    /// Resolved value
+    pub value: GeoKeyValue,
+}
+
+/// A parsed GeoKeyDirectory
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoKeyDirectory {
+    /// KeyDirectoryVersion (always 1 so far)
+    pub version: u16,
+    /// KeyRevision as (major, minor)
+    pub revision: (u16, u16),
+    /// Keys in directory order
+    pub keys: Vec<GeoKey>,
+}
+
+impl GeoKeyDirectory {
+    /// Read and resolve the GeoKeyDirectory of an IFD
+    ///
+    /// # Returns
+    /// `None` if the IFD has no GeoKeyDirectory tag
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the directory is truncated or a key points
+    /// outside the parameter arrays
+    pub fn parse<T: TiffDataSource>(
+        ifd: &ImageFileDirectory,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<Self>> {
+        let directory = match ifd.get_tag_value(tags::GEO_KEY_DIRECTORY, reader, endian)? {
+            Some(TagValue::Shorts(values)) => values,
+            Some(_) => return Err(invalid("GeoKeyDirectory must be SHORT values".to_string())),
+            None => return Ok(None),
+        };
+        let doubles = match ifd.get_tag_value(tags::GEO_DOUBLE_PARAMS, reader, endian)? {
+            Some(TagValue::Doubles(values)) => values,
+            _ => Vec::new(),
+        };
+        // Keys index GeoAsciiParams by byte, so each piece is sliced from
+        // the raw bytes before decoding
+        let ascii = match ifd.find_entry(tags::GEO_ASCII_PARAMS) {
+            Some(entry) if entry.field_type == FieldType::Ascii as u16 => {
+                reader.read_value_bytes(entry, endian)?
+            }
+            _ => Vec::new(),
+        };
+        Self::resolve(&directory, &doubles, &ascii, |bytes| {
+            reader.decode_ascii(bytes, "GeoAsciiParams")
+        })
+        .map(Some)
+    }
+
+    /// Resolve a GeoKeyDirectory from the raw values of the three GeoTIFF tags
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the directory is truncated or a key's
+    /// offset and count reach past the end of the array it points into
+    pub fn from_params(directory: &[u16], doubles: &[f64], ascii: &str) -> Result<Self> {
+        Self::resolve(directory, doubles, ascii.as_bytes(), |bytes| {
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        })
+    }
+
+    /// Resolve the keys, decoding each GeoAsciiParams piece with `decode`
+    fn resolve(
+        directory: &[u16],
+        doubles: &[f64],
+        ascii: &[u8],
+        decode: impl Fn(Vec<u8>) -> Result<String>,
+    ) -> Result<Self> {
+        let [version, major, minor, key_count, ..] = *directory else {
+            return Err(invalid(format!("header needs 4 values, found {}", directory.len())));
+        };
+        let entries = directory[4..].chunks_exact(4).take(key_count as usize);
+        if entries.len() < key_count as usize {
+            return Err(invalid(format!(
+                "header lists {key_count} keys but only {} fit",
+                entries.len()
+            )));
+        }
+
+        let keys = entries
+            .map(|entry| {
+                let [id, location, count, value_offset] = [entry[0], entry[1], entry[2], entry[3]];
+                let range = || {
+                    let start = value_offset as usize;
+                    start..start + count as usize
+                };
+                let out_of_range = |array: &str, len: usize| {
+                    invalid(format!(
+                        "key {id} reads {array}[{}..{}] but it has {len} values",
+                        value_offset,
+                        value_offset as usize + count as usize
+                    ))
+                };
+                let value = match location {
+                    0 => GeoKeyValue::Shorts(vec![value_offset]),
+                    tags::GEO_KEY_DIRECTORY => GeoKeyValue::Shorts(
+                        directory
+                            .get(range())
+                            .ok_or_else(|| out_of_range("GeoKeyDirectory", directory.len()))?
+                            .to_vec(),
+                    ),
+                    tags::GEO_DOUBLE_PARAMS => GeoKeyValue::Doubles(
+                        doubles
+                            .get(range())
+                            .ok_or_else(|| out_of_range("GeoDoubleParams", doubles.len()))?
+                            .to_vec(),
+                    ),
+                    tags::GEO_ASCII_PARAMS => {
+                        let bytes = ascii
+                            .get(range())
+                            .ok_or_else(|| out_of_range("GeoAsciiParams", ascii.len()))?;
+                        let bytes = bytes.strip_suffix(b"|").unwrap_or(bytes);
+                        GeoKeyValue::Ascii(decode(bytes.to_vec())?)
+                    }
+                    other => return Err(invalid(format!("key {id} has unknown location {other}"))),
+                };
+                Ok(GeoKey { id, value })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            version,
+            revision: (major, minor),
+            keys,
+        })
+    }
+
+    /// Get the value of a key by its ID
+    pub fn get(&self, id: u16) -> Option<&GeoKeyValue> {
+        self.keys.iter().find(|key| key.id == id).map(|key| &key.value)
+    }
+
+    /// Get a key's value as a single short
+    ///
+    /// # Returns
+    /// The first short, or `None` if the key is missing or not SHORT
+    pub fn get_short(&self, id: u16) -> Option<u16> {
+        match self.get(id)? {
+            GeoKeyValue::Shorts(values) => values.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Get a key's value as a single double
+    ///
+    /// # Returns
+    /// The first double, or `None` if the key is missing or not stored
+    /// in GeoDoubleParams
+    pub fn get_double(&self, id: u16) -> Option<f64> {
+        match self.get(id)? {
+            GeoKeyValue::Doubles(values) => values.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Get a key's value as a string
+    ///
+    /// # Returns
+    /// The string, or `None` if the key is missing or not stored in
+    /// GeoAsciiParams
+    pub fn get_ascii(&self, id: u16) -> Option<&str> {
+        match self.get(id)? {
+            GeoKeyValue::Ascii(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// An affine transform between pixel (column, row) and model (x, y)
+/// coordinates
+///
+/// Coefficients follow the GDAL convention:
+/// `x = c[0] + col * c[1] + row * c[2]` and
+/// `y = c[3] + col * c[4] + row * c[5]`. Pixel coordinates are measured
+/// from the top-left corner of the top-left pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTransform {
+    /// The six affine coefficients
+    pub coefficients: [f64; 6],
+}
+
+impl GeoTransform {
+    /// Build the transform for an IFD
+    ///
+    /// ModelTiepoint with ModelPixelScale is used when both are present;
+    /// otherwise ModelTransformation.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the IFD has neither form, the tags aren't
+    /// DOUBLE values of the right length, or the transform can't be
+    /// inverted
+    pub fn from_ifd<T: TiffDataSource>(
+        ifd: &ImageFileDirectory,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Self> {
+        let doubles = |tag| match ifd.get_tag_value(tag, reader, endian)? {
+            Some(TagValue::Doubles(values)) => Ok(Some(values)),
+            Some(_) => Err(TiffError::InvalidTag {
+                tag,
+                reason: "must be DOUBLE values".to_string(),
+            }),
+            None => Ok(None),
+        };
+        let tiepoint = doubles(tags::MODEL_TIEPOINT)?;
+        let scale = doubles(tags::MODEL_PIXEL_SCALE)?;
+        if let (Some(tiepoint), Some(scale)) = (&tiepoint, &scale) {
+            return Self::from_tiepoint_and_scale(tiepoint, scale);
+        }
+        match doubles(tags::MODEL_TRANSFORMATION)? {
+            Some(matrix) => Self::from_matrix(&matrix),
+            None => Err(TiffError::InvalidTag {
+                tag: tags::MODEL_TIEPOINT,
+                reason: "georeferencing needs ModelTiepoint and ModelPixelScale, or ModelTransformation"
+                    .to_string(),
+            }),
+        }
+    }
+
+    /// Build a transform from a tiepoint and pixel scale
+    ///
+    /// Only the first tiepoint `(i, j, k, x, y, z)` is used. Model y grows
+    /// upwards while rows grow downwards, so the y scale is negated.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if there are fewer than 6 tiepoint or 2 scale
+    /// values, or a scale is zero
+    pub fn from_tiepoint_and_scale(tiepoint: &[f64], scale: &[f64]) -> Result<Self> {
+        let [i, j, _, x, y, _, ..] = *tiepoint else {
+            return Err(TiffError::InvalidTag {
+                tag: tags::MODEL_TIEPOINT,
+                reason: format!("needs 6 values, found {}", tiepoint.len()),
+            });
+        };
+        let [scale_x, scale_y, ..] = *scale else {
+            return Err(TiffError::InvalidTag {
+                tag: tags::MODEL_PIXEL_SCALE,
+                reason: format!("needs at least 2 values, found {}", scale.len()),
+            });
+        };
+        Self::checked(tags::MODEL_PIXEL_SCALE, [x - i * scale_x, scale_x, 0.0, y + j * scale_y, 0.0, -scale_y])
+    }
+
+    /// Build a transform from a row-major 4x4 ModelTransformation matrix
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the matrix doesn't have 16 values or its
+    /// 2D part can't be inverted
+    pub fn from_matrix(matrix: &[f64]) -> Result<Self> {
+        if matrix.len() != 16 {
+            return Err(TiffError::InvalidTag {
+                tag: tags::MODEL_TRANSFORMATION,
+                reason: format!("needs 16 values, found {}", matrix.len()),
+            });
+        }
+        Self::checked(
+            tags::MODEL_TRANSFORMATION,
+            [matrix[3], matrix[0], matrix[1], matrix[7], matrix[4], matrix[5]],
+        )
+    }
+
+    /// Wrap coefficients, rejecting a transform that can't be inverted
+    fn checked(tag: u16, coefficients: [f64; 6]) -> Result<Self> {
+        let transform = Self { coefficients };
+        let det = transform.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return Err(TiffError::InvalidTag {
+                tag,
+                reason: "transform is not invertible".to_string(),
+            });
+        }
+        Ok(transform)
+    }
+
+    fn determinant(&self) -> f64 {
+        let c = &self.coefficients;
+        c[1] * c[5] - c[2] * c[4]
+    }
+
+    /// Map a pixel position to model coordinates
+    pub fn pixel_to_world(&self, col: f64, row: f64) -> (f64, f64) {
+        let c = &self.coefficients;
+        (c[0] + col * c[1] + row * c[2], c[3] + col * c[4] + row * c[5])
+    }
+
+    /// Map model coordinates to a pixel position
+    pub fn world_to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        let c = &self.coefficients;
+        let (dx, dy) = (x - c[0], y - c[3]);
+        let det = self.determinant();
+        ((dx * c[5] - dy * c[2]) / det, (dy * c[1] - dx * c[4]) / det)
+    }
+}
+
+fn invalid(reason: String) -> TiffError {
+    TiffError::InvalidTag {
+        tag: tags::GEO_KEY_DIRECTORY,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{TestTiff, first_ifd};
+
+    /// Header plus GTModelType (inline), GeogCitation (ASCII) and
+    /// GeogSemiMajorAxis (double)
+    const DIRECTORY: [u16; 16] = [
+        1, 1, 0, 3,
+        1024, 0, 1, 2,
+        2049, 34737, 7, 0,
+        2057, 34736, 1, 1,
+    ];
+
+    #[test]
+    fn test_from_params_resolves_each_location() {
+        let directory = GeoKeyDirectory::from_params(&DIRECTORY, &[0.0, 6_378_137.0], "WGS 84|").unwrap();
+        assert_eq!(directory.version, 1);
+        assert_eq!(directory.revision, (1, 0));
+        assert_eq!(
+            directory.keys,
+            vec![
+                GeoKey { id: 1024, value: GeoKeyValue::Shorts(vec![2]) },
+                GeoKey { id: 2049, value: GeoKeyValue::Ascii("WGS 84".to_string()) },
+                GeoKey { id: 2057, value: GeoKeyValue::Doubles(vec![6_378_137.0]) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let directory = GeoKeyDirectory::from_params(&DIRECTORY, &[0.0, 6_378_137.0], "WGS 84|").unwrap();
+        assert_eq!(directory.get_short(1024), Some(2));
+        assert_eq!(directory.get_ascii(2049), Some("WGS 84"));
+        assert_eq!(directory.get_double(2057), Some(6_378_137.0));
+        assert_eq!(directory.get(2057), Some(&GeoKeyValue::Doubles(vec![6_378_137.0])));
+
+        // Wrong type or missing key
+        assert_eq!(directory.get_double(1024), None);
+        assert_eq!(directory.get_short(2049), None);
+        assert_eq!(directory.get_ascii(2057), None);
+        assert_eq!(directory.get(3072), None);
+    }
+
+    #[test]
+    fn test_out_of_range_double_reference_is_invalid_tag() {
+        // GeogSemiMajorAxis points at index 1, but only one double exists
+        match GeoKeyDirectory::from_params(&DIRECTORY, &[6_378_137.0], "WGS 84|") {
+            Err(TiffError::InvalidTag { tag, reason }) => {
+                assert_eq!(tag, tags::GEO_KEY_DIRECTORY);
+                assert_eq!(reason, "key 2057 reads GeoDoubleParams[1..2] but it has 1 values");
+            }
+            other => panic!("expected InvalidTag, got {other:?}"),
+        }
+
+        // A huge count into the ASCII params
+        let mut directory = DIRECTORY;
+        directory[10] = u16::MAX;
+        assert!(matches!(
+            GeoKeyDirectory::from_params(&directory, &[0.0, 1.0], "WGS 84|"),
+            Err(TiffError::InvalidTag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated_directory() {
+        assert!(matches!(GeoKeyDirectory::from_params(&[1, 1, 0], &[], ""), Err(TiffError::InvalidTag { .. })));
+        // Header claims 3 keys but only 2 follow
+        assert!(matches!(
+            GeoKeyDirectory::from_params(&DIRECTORY[..12], &[0.0, 1.0], "WGS 84|"),
+            Err(TiffError::InvalidTag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_geotransform_from_tiepoint_and_scale() {
+        // Pixel (0, 0) at (500000, 4100000), 30 m pixels
+        let transform =
+            GeoTransform::from_tiepoint_and_scale(&[0.0, 0.0, 0.0, 500_000.0, 4_100_000.0, 0.0], &[30.0, 30.0, 0.0])
+                .unwrap();
+        assert_eq!(transform.coefficients, [500_000.0, 30.0, 0.0, 4_100_000.0, 0.0, -30.0]);
+        assert_eq!(transform.pixel_to_world(10.0, 20.0), (500_300.0, 4_099_400.0));
+        assert_eq!(transform.world_to_pixel(500_300.0, 4_099_400.0), (10.0, 20.0));
+
+        // A tiepoint away from the origin
+        let transform = GeoTransform::from_tiepoint_and_scale(&[2.0, 3.0, 0.0, 100.0, 200.0, 0.0], &[0.5, 0.25, 0.0])
+            .unwrap();
+        assert_eq!(transform.pixel_to_world(2.0, 3.0), (100.0, 200.0));
+        assert_eq!(transform.pixel_to_world(0.0, 0.0), (99.0, 200.75));
+    }
+
+    #[test]
+    fn test_geotransform_from_matrix() {
+        // Rotated: x = 10 + 2 col + 1 row, y = 20 + 1 col - 2 row
+        let matrix = [
+            2.0, 1.0, 0.0, 10.0,
+            1.0, -2.0, 0.0, 20.0,
+            0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+        let transform = GeoTransform::from_matrix(&matrix).unwrap();
+        assert_eq!(transform.pixel_to_world(3.0, 4.0), (20.0, 15.0));
+        assert_eq!(transform.world_to_pixel(20.0, 15.0), (3.0, 4.0));
+
+        assert!(matches!(GeoTransform::from_matrix(&matrix[..12]), Err(TiffError::InvalidTag { .. })));
+        assert!(matches!(GeoTransform::from_matrix(&[0.0; 16]), Err(TiffError::InvalidTag { .. })));
+    }
+
+    #[test]
+    fn test_geotransform_from_ifd() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[1])
+            .double(tags::MODEL_PIXEL_SCALE, &[1.0, 2.0, 0.0])
+            .double(tags::MODEL_TIEPOINT, &[0.0, 0.0, 0.0, 5.0, 6.0, 0.0]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        let transform = GeoTransform::from_ifd(&ifd, &reader, endian).unwrap();
+        assert_eq!(transform.coefficients, [5.0, 1.0, 0.0, 6.0, 0.0, -2.0]);
+
+        // Falls back to ModelTransformation
+        let mut matrix = [0.0; 16];
+        (matrix[0], matrix[3], matrix[5], matrix[7], matrix[15]) = (1.0, 5.0, -2.0, 6.0, 1.0);
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).double(tags::MODEL_TRANSFORMATION, &matrix);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(GeoTransform::from_ifd(&ifd, &reader, endian).unwrap(), transform);
+
+        // Scale alone isn't enough
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).double(tags::MODEL_PIXEL_SCALE, &[1.0, 1.0, 0.0]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(
+            GeoTransform::from_ifd(&ifd, &reader, endian),
+            Err(TiffError::InvalidTag { tag: tags::MODEL_TIEPOINT, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_from_ifd() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[1])
+            .short(tags::GEO_KEY_DIRECTORY, &DIRECTORY)
+            .double(tags::GEO_DOUBLE_PARAMS, &[0.0, 6_378_137.0])
+            .ascii(tags::GEO_ASCII_PARAMS, "WGS 84|");
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        let directory = GeoKeyDirectory::parse(&ifd, &reader, endian).unwrap().unwrap();
+        assert_eq!(directory.keys.len(), 3);
+
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(GeoKeyDirectory::parse(&ifd, &reader, endian).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_slices_latin1_ascii_by_byte() {
+        // GTCitation is "Zürich" in Latin-1, so decoding first would shift
+        // GeogCitation by a byte
+        let params = b"Z\xFCrich|WGS 84|\0".to_vec();
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[1])
+            .short(tags::GEO_KEY_DIRECTORY, &[1, 1, 0, 2, 1026, 34737, 7, 0, 2049, 34737, 7, 7])
+            .raw(tags::GEO_ASCII_PARAMS, 2, params.len() as u32, params);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        let directory = GeoKeyDirectory::parse(&ifd, &reader, endian).unwrap().unwrap();
+        assert_eq!(directory.get(1026), Some(&GeoKeyValue::Ascii("Zürich".to_string())));
+        assert_eq!(directory.get(2049), Some(&GeoKeyValue::Ascii("WGS 84".to_string())));
+
+        let reader = reader.with_strict_ascii(true);
+        assert!(matches!(
+            GeoKeyDirectory::parse(&ifd, &reader, endian),
+            Err(TiffError::InvalidString { .. })
+        ));
+    }
+}
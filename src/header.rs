@@ -2,6 +2,7 @@
 //! TIFF header structures and parsing
 
 use crate::{TiffError, Result};
+use crate::prelude::*;
 
 /// Byte order (endianness) of the TIFF file
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,15 +13,15 @@ pub enum Endian {
     Big,
 }
 
-/// TIFF file header (first 8 bytes of every TIFF file)
+/// TIFF file header (first 8 bytes of a classic TIFF, 16 of a BigTIFF)
 #[derive(Debug, Clone)]
 pub struct TiffHeader {
     /// Byte order indicator
     pub endian: Endian,
-    /// Magic number (should always be 42)
+    /// Magic number: 42 for classic TIFF, 43 for BigTIFF
     pub magic: u16,
     /// Offset to the first Image File Directory
-    pub ifd_offset: u32,
+    pub ifd_offset: u64,
 }
 
 impl TiffHeader {
@@ -29,11 +30,20 @@ impl TiffHeader {
     
     /// The expected magic number in TIFF files (42 - Answer to Life, Universe, and Everything!)
     pub const MAGIC_NUMBER: u16 = 42;
+
+    /// The magic number used by BigTIFF files (64-bit offsets)
+    pub const BIGTIFF_MAGIC_NUMBER: u16 = 43;
+
+    /// The size of a BigTIFF header in bytes
+    pub const BIGTIFF_SIZE: usize = 16;
     
-    /// Parse a TIFF header from the first 8 bytes of data
+    /// Parse a TIFF header from the start of a file
+    ///
+    /// A BigTIFF header adds an offset byte size (always 8) and a reserved
+    /// zero after the magic number, followed by a 64-bit IFD offset.
     /// 
     /// # Arguments
-    /// * `data` - Byte slice containing at least 8 bytes
+    /// * `data` - Byte slice containing at least 8 bytes (16 for BigTIFF)
     /// 
     /// # Returns
     /// * `Ok(TiffHeader)` if parsing succeeds
@@ -56,13 +66,16 @@ impl TiffHeader {
         let magic = endian.read_u16(magic_bytes);
         
         // Validate magic number
+        if magic == Self::BIGTIFF_MAGIC_NUMBER {
+            return Self::parse_bigtiff(data, endian);
+        }
         if magic != Self::MAGIC_NUMBER {
             return Err(TiffError::InvalidMagic { found: magic });
         }
         
         // Parse IFD offset from bytes 4-7 using the detected endianness
         let ifd_offset_bytes = [data[4], data[5], data[6], data[7]];
-        let ifd_offset = endian.read_u32(ifd_offset_bytes);
+        let ifd_offset = endian.read_u32(ifd_offset_bytes) as u64;
         
         Ok(TiffHeader {
             endian,
@@ -71,6 +84,48 @@ impl TiffHeader {
         })
     }
     
+    /// Parse the rest of a BigTIFF header once the magic number is known
+    fn parse_bigtiff(data: &[u8], endian: Endian) -> Result<Self> {
+        if data.len() < Self::BIGTIFF_SIZE {
+            return Err(TiffError::InsufficientData {
+                operation: "reading BigTIFF header",
+                needed: Self::BIGTIFF_SIZE,
+                available: data.len(),
+            });
+        }
+
+        let offset_size = endian.read_u16([data[4], data[5]]);
+        if offset_size != 8 {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!("BigTIFF with {offset_size}-byte offsets"),
+            });
+        }
+        let reserved = endian.read_u16([data[6], data[7]]);
+        if reserved != 0 {
+            return Err(TiffError::MalformedFile {
+                reason: format!("BigTIFF header reserved field is {reserved}, not 0"),
+            });
+        }
+
+        let mut offset_bytes = [0; 8];
+        offset_bytes.copy_from_slice(&data[8..16]);
+        Ok(TiffHeader {
+            endian,
+            magic: Self::BIGTIFF_MAGIC_NUMBER,
+            ifd_offset: endian.read_u64(offset_bytes),
+        })
+    }
+
+    /// Check if this is a BigTIFF file (magic 43, 64-bit offsets)
+    pub fn is_bigtiff(&self) -> bool {
+        self.magic == Self::BIGTIFF_MAGIC_NUMBER
+    }
+
+    /// Get the size of this header in bytes (8, or 16 for BigTIFF)
+    pub fn size(&self) -> usize {
+        if self.is_bigtiff() { Self::BIGTIFF_SIZE } else { Self::SIZE }
+    }
+
     /// Get the endianness of this TIFF file
     pub fn endianness(&self) -> Endian {
         self.endian
@@ -200,19 +255,47 @@ mod tests {
     
     #[test]
     fn test_invalid_magic() {
-        // Valid endian but wrong magic number (43 instead of 42)
-        let data = [0x49, 0x49, 0x2B, 0x00, 0x08, 0x00, 0x00, 0x00];
+        // Valid endian but wrong magic number (44 instead of 42)
+        let data = [0x49, 0x49, 0x2C, 0x00, 0x08, 0x00, 0x00, 0x00];
         
         let result = TiffHeader::parse(&data);
         assert!(result.is_err());
         
         if let Err(TiffError::InvalidMagic { found }) = result {
-            assert_eq!(found, 43);
+            assert_eq!(found, 44);
         } else {
             panic!("Expected InvalidMagic error");
         }
     }
     
+    #[test]
+    fn test_bigtiff_header() {
+        // BigTIFF: "MM" + 43 + offset size 8 + reserved 0 + 64-bit offset
+        let data = [
+            0x4D, 0x4D, 0x00, 0x2B, 0x00, 0x08, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x10,
+        ];
+
+        let header = TiffHeader::parse(&data).unwrap();
+        assert!(header.is_bigtiff());
+        assert_eq!(header.size(), 16);
+        assert_eq!(header.ifd_offset, 0x1_0000_0010);
+
+        // The first 8 bytes alone aren't enough
+        assert!(matches!(
+            TiffHeader::parse(&data[..8]),
+            Err(TiffError::InsufficientData { needed: 16, .. })
+        ));
+
+        let mut bad_size = data;
+        bad_size[5] = 4;
+        assert!(matches!(TiffHeader::parse(&bad_size), Err(TiffError::UnsupportedFeature { .. })));
+
+        let mut bad_reserved = data;
+        bad_reserved[7] = 1;
+        assert!(matches!(TiffHeader::parse(&bad_reserved), Err(TiffError::MalformedFile { .. })));
+    }
+
     #[test]
     fn test_invalid_byte_order() {
         // Invalid byte order indicator
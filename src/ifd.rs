@@ -3,14 +3,19 @@
 //!
 //! IFDs contain all the metadata about a TIFF image - dimensions, compression,
 //! where the actual image data is stored, etc. Each IFD contains a series of
-//! 12-byte entries that describe different aspects of the image.
+//! 12-byte entries (20-byte in BigTIFF) that describe different aspects of
+//! the image.
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
 
 use crate::{TiffError, Result};
-use crate::header::Endian;
-use crate::reader::{TiffReader, TiffDataSource};
-use crate::tags::{self, Compression, PhotometricInterpretation, ResolutionUnit, SampleFormat};
+use crate::header::{Endian, TiffHeader};
+use crate::reader::{check_read_limit, TiffReader, TiffDataSource, TiffImageReader};
+use crate::tags::{self, Compression, FillOrder, Orientation, PhotometricInterpretation, PlanarConfiguration, ResolutionUnit, SampleFormat, Tag};
+use crate::prelude::*;
 
-/// An Image File Directory entry (12 bytes)
+/// An Image File Directory entry (12 bytes, or 20 in BigTIFF)
 /// 
 /// Each entry describes one piece of metadata about the image.
 /// The structure is always the same, but the interpretation depends
@@ -27,11 +32,11 @@ pub struct IfdEntry {
     
     /// Number of values of this type
     /// Examples: 1 for a single width value, 3 for RGB bits per sample
-    pub count: u32,
+    pub count: u64,
     
-    /// Either the value itself (if ≤ 4 bytes) or offset to the value
-    /// This is the tricky part - depends on field_type and count
-    pub value_offset: u32,
+    /// Either the value itself (if ≤ 4 bytes, or ≤ 8 in BigTIFF) or offset
+    /// to the value. This is the tricky part - depends on field_type and count
+    pub value_offset: u64,
 }
 
 /// Data types used in TIFF tags
@@ -64,6 +69,12 @@ pub enum FieldType {
     Float = 11,
     /// 64-bit IEEE floating point
     Double = 12,
+    /// 64-bit unsigned integer (BigTIFF)
+    Long8 = 16,
+    /// 64-bit signed integer (BigTIFF)
+    SLong8 = 17,
+    /// 64-bit IFD offset (BigTIFF)
+    Ifd8 = 18,
 }
 
 impl FieldType {
@@ -82,6 +93,9 @@ impl FieldType {
             10 => Ok(FieldType::SRational),
             11 => Ok(FieldType::Float),
             12 => Ok(FieldType::Double),
+            16 => Ok(FieldType::Long8),
+            17 => Ok(FieldType::SLong8),
+            18 => Ok(FieldType::Ifd8),
             _ => Err(TiffError::InvalidFieldType { found: value }),
         }
     }
@@ -93,6 +107,7 @@ impl FieldType {
             FieldType::Short | FieldType::SShort => 2,
             FieldType::Long | FieldType::SLong | FieldType::Float => 4,
             FieldType::Rational | FieldType::SRational | FieldType::Double => 8,
+            FieldType::Long8 | FieldType::SLong8 | FieldType::Ifd8 => 8,
         }
     }
 }
@@ -102,6 +117,7 @@ impl FieldType {
 /// This provides a convenient overview of the key image properties
 /// without having to call multiple methods.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImageSummary {
     /// Image width in pixels
     pub width: u32,
@@ -117,6 +133,8 @@ pub struct ImageSummary {
     pub photometric_interpretation: Option<PhotometricInterpretation>,
     /// Whether the image uses tiled layout
     pub is_tiled: bool,
+    /// NewSubfileType flags (0 when the tag is absent)
+    pub new_subfile_type: u32,
 }
 
 impl ImageSummary {
@@ -127,7 +145,28 @@ impl ImageSummary {
 
     /// Calculate bytes per pixel (rounded up)
     pub fn bytes_per_pixel(&self) -> u32 {
-        (self.bits_per_pixel() + 7) / 8
+        self.bits_per_pixel().div_ceil(8)
+    }
+
+    /// Calculate the bytes in one row of chunky (interleaved) pixel data
+    ///
+    /// Rows are padded to a whole byte, so sub-byte layouts round up:
+    /// a 100-pixel 1-bit row is 13 bytes, not 12.5.
+    pub fn row_stride_bytes(&self) -> u32 {
+        stride_bytes(self.width, self.bits_per_pixel())
+    }
+
+    /// Calculate the bytes in one row of a single plane of planar pixel data
+    ///
+    /// With PlanarConfiguration = 2 each channel is stored separately, so
+    /// a row holds `width` samples of that channel only, padded to a byte.
+    ///
+    /// # Returns
+    /// `None` if `channel` is not a valid sample index
+    pub fn plane_stride_bytes(&self, channel: usize) -> Option<u32> {
+        self.bits_per_sample
+            .get(channel)
+            .map(|&bits| stride_bytes(self.width, bits))
     }
 
     /// Check if this is a grayscale image
@@ -144,6 +183,16 @@ impl ImageSummary {
         matches!(self.photometric_interpretation, Some(PhotometricInterpretation::Rgb))
     }
 
+    /// Check if this image is a transparency mask rather than a color image
+    ///
+    /// True when PhotometricInterpretation is TransparencyMask (4) or when
+    /// NewSubfileType has its transparency mask bit (value 4) set. Masks
+    /// are 1-bit images that apply to another image in the same file.
+    pub fn is_mask(&self) -> bool {
+        self.photometric_interpretation == Some(PhotometricInterpretation::TransparencyMask)
+            || self.new_subfile_type & 4 != 0
+    }
+
     /// Check if this image has an alpha channel
     pub fn has_alpha(&self) -> bool {
         self.samples_per_pixel == 2 && self.is_grayscale() ||  // Grayscale + Alpha
@@ -179,11 +228,21 @@ impl ImageSummary {
     }
 }
 
+/// Bytes needed for `width` pixels of `bits` bits each, rounded up to a whole byte
+fn stride_bytes(width: u32, bits: u32) -> u32 {
+    (width as u64 * bits as u64).div_ceil(8).min(u32::MAX as u64) as u32
+}
+
 /// The value stored in a TIFF tag
 /// 
 /// Different tags store different types of data. This enum represents
 /// all the possible value types that can be stored in TIFF tags.
+///
+/// With the `serde` feature, values serialize as `{"type": "Shorts",
+/// "values": [1, 2]}` so they round-trip through formats like JSON.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "values"))]
 pub enum TagValue {
     /// Unsigned 8-bit integers
     Bytes(Vec<u8>),
@@ -209,6 +268,10 @@ pub enum TagValue {
     Floats(Vec<f32>),
     /// 64-bit floating point
     Doubles(Vec<f64>),
+    /// Unsigned 64-bit integers (BigTIFF LONG8 and IFD8)
+    Longs8(Vec<u64>),
+    /// Signed 64-bit integers (BigTIFF SLONG8)
+    SLongs8(Vec<i64>),
 }
 
 impl TagValue {
@@ -218,6 +281,20 @@ impl TagValue {
             TagValue::Shorts(v) if !v.is_empty() => Some(v[0] as u32),
             TagValue::Longs(v) if !v.is_empty() => Some(v[0]),
             TagValue::Bytes(v) if !v.is_empty() => Some(v[0] as u32),
+            TagValue::Longs8(v) if !v.is_empty() => u32::try_from(v[0]).ok(),
+            _ => None,
+        }
+    }
+
+    /// Try to get the first value as a u64
+    ///
+    /// Like `as_u32`, but also keeps LONG8 values that don't fit in 32 bits.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            TagValue::Longs8(v) if !v.is_empty() => Some(v[0]),
+            TagValue::Longs(v) if !v.is_empty() => Some(v[0] as u64),
+            TagValue::Shorts(v) if !v.is_empty() => Some(v[0] as u64),
+            TagValue::Bytes(v) if !v.is_empty() => Some(v[0] as u64),
             _ => None,
         }
     }
@@ -232,18 +309,61 @@ impl TagValue {
     }
 
     /// Try to get as a string
+    ///
+    /// ASCII values holding several NUL-separated strings return only the
+    /// first; use `as_string_list` for all of them.
     pub fn as_string(&self) -> Option<&str> {
         match self {
-            TagValue::Ascii(s) => Some(s),
+            TagValue::Ascii(s) => s.split('\0').next(),
+            _ => None,
+        }
+    }
+
+    /// Try to get as a list of NUL-separated strings
+    ///
+    /// The spec lets one ASCII value hold several strings (InkNames, for
+    /// example), each NUL-terminated. Trailing empty strings left by
+    /// terminators or padding are dropped.
+    pub fn as_string_list(&self) -> Option<Vec<String>> {
+        let TagValue::Ascii(s) = self else {
+            return None;
+        };
+        let mut strings: Vec<String> = s.split('\0').map(String::from).collect();
+        while strings.last().is_some_and(|last| last.is_empty()) {
+            strings.pop();
+        }
+        Some(strings)
+    }
+
+    /// Try to get as raw bytes (BYTE or UNDEFINED values)
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            TagValue::Bytes(v) | TagValue::Undefined(v) => Some(v),
             _ => None,
         }
     }
 
     /// Try to get as a vec of u32s
+    ///
+    /// LONG8 values are accepted only if every one fits in a u32.
     pub fn as_u32_vec(&self) -> Option<Vec<u32>> {
         match self {
             TagValue::Longs(v) => Some(v.clone()),
             TagValue::Shorts(v) => Some(v.iter().map(|&x| x as u32).collect()),
+            TagValue::Longs8(v) => v.iter().map(|&x| u32::try_from(x).ok()).collect(),
+            _ => None,
+        }
+    }
+
+    /// Try to get as a vec of u64s
+    ///
+    /// Accepts SHORT, LONG and LONG8 values, so strip and tile offsets can
+    /// be read the same way from classic TIFF and BigTIFF files.
+    pub fn as_u64_vec(&self) -> Option<Vec<u64>> {
+        match self {
+            TagValue::Longs8(v) => Some(v.clone()),
+            TagValue::Longs(v) => Some(v.iter().map(|&x| x as u64).collect()),
+            TagValue::Shorts(v) => Some(v.iter().map(|&x| x as u64).collect()),
             _ => None,
         }
     }
@@ -254,6 +374,18 @@ impl TagValue {
             TagValue::SLongs(v) if !v.is_empty() => Some(v[0]),
             TagValue::SShorts(v) if !v.is_empty() => Some(v[0] as i32),
             TagValue::SBytes(v) if !v.is_empty() => Some(v[0] as i32),
+            TagValue::SLongs8(v) if !v.is_empty() => i32::try_from(v[0]).ok(),
+            _ => None,
+        }
+    }
+
+    /// Try to get the first value as an i64 (for signed types)
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            TagValue::SLongs8(v) if !v.is_empty() => Some(v[0]),
+            TagValue::SLongs(v) if !v.is_empty() => Some(v[0] as i64),
+            TagValue::SShorts(v) if !v.is_empty() => Some(v[0] as i64),
+            TagValue::SBytes(v) if !v.is_empty() => Some(v[0] as i64),
             _ => None,
         }
     }
@@ -298,26 +430,287 @@ impl TagValue {
             _ => None,
         }
     }
+
+    /// Get the number of values
+    ///
+    /// ASCII counts bytes of the string (without its NUL terminator);
+    /// rationals count numerator/denominator pairs.
+    pub fn len(&self) -> usize {
+        match self {
+            TagValue::Bytes(v) | TagValue::Undefined(v) => v.len(),
+            TagValue::Ascii(s) => s.len(),
+            TagValue::Shorts(v) => v.len(),
+            TagValue::Longs(v) => v.len(),
+            TagValue::Rationals(v) => v.len(),
+            TagValue::SBytes(v) => v.len(),
+            TagValue::SShorts(v) => v.len(),
+            TagValue::SLongs(v) => v.len(),
+            TagValue::SRationals(v) => v.len(),
+            TagValue::Floats(v) => v.len(),
+            TagValue::Doubles(v) => v.len(),
+            TagValue::Longs8(v) => v.len(),
+            TagValue::SLongs8(v) => v.len(),
+        }
+    }
+
+    /// Check if there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over every value as an f64
+    ///
+    /// Integers are cast, rationals are divided (a zero denominator gives
+    /// infinity or NaN) and ASCII yields nothing. 64-bit integers beyond
+    /// 2^53 lose precision.
+    pub fn iter_f64(&self) -> impl Iterator<Item = f64> + '_ {
+        let values: Box<dyn Iterator<Item = f64> + '_> = match self {
+            TagValue::Bytes(v) | TagValue::Undefined(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::Ascii(_) => Box::new(core::iter::empty()),
+            TagValue::Shorts(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::Longs(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::Rationals(v) => Box::new(v.iter().map(|&(n, d)| n as f64 / d as f64)),
+            TagValue::SBytes(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::SShorts(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::SLongs(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::SRationals(v) => Box::new(v.iter().map(|&(n, d)| n as f64 / d as f64)),
+            TagValue::Floats(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::Doubles(v) => Box::new(v.iter().copied()),
+            TagValue::Longs8(v) => Box::new(v.iter().map(|&x| x as f64)),
+            TagValue::SLongs8(v) => Box::new(v.iter().map(|&x| x as f64)),
+        };
+        values
+    }
+
+    /// Render the value as a human-readable string
+    ///
+    /// Numbers are comma-separated, rationals are shown as `n/d`, ASCII is
+    /// quoted, and bytes/undefined data are shown as hex. Arrays longer than
+    /// `max_items` are truncated with a `...(N more)` suffix.
+    pub fn to_display_string(&self, max_items: usize) -> String {
+        match self {
+            TagValue::Ascii(s) => format!("{s:?}"),
+            TagValue::Bytes(v) | TagValue::Undefined(v) => {
+                join_limited(v.iter().map(|b| format!("{b:02x}")), v.len(), max_items, " ")
+            }
+            TagValue::Shorts(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::Longs(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::SBytes(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::SShorts(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::SLongs(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::Floats(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::Doubles(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::Longs8(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::SLongs8(v) => join_limited(v.iter(), v.len(), max_items, ", "),
+            TagValue::Rationals(v) => {
+                join_limited(v.iter().map(|(n, d)| format!("{n}/{d}")), v.len(), max_items, ", ")
+            }
+            TagValue::SRationals(v) => {
+                join_limited(v.iter().map(|(n, d)| format!("{n}/{d}")), v.len(), max_items, ", ")
+            }
+        }
+    }
+}
+
+/// Join at most `max_items` items, noting how many were left out
+fn join_limited<I>(items: I, total: usize, max_items: usize, separator: &str) -> String
+where
+    I: Iterator,
+    I::Item: core::fmt::Display,
+{
+    let shown: Vec<String> = items.take(max_items).map(|item| item.to_string()).collect();
+    let mut result = shown.join(separator);
+    if total > shown.len() {
+        if !result.is_empty() {
+            result.push_str(separator);
+        }
+        result.push_str(&format!("...({} more)", total - shown.len()));
+    }
+    result
+}
+
+/// One step in the path from the main IFD chain to a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IfdPathSegment {
+    /// Position in the main IFD chain, or within a SubIFDs list
+    Index(usize),
+    /// Followed the SubIFDs tag (330)
+    SubIfd,
+    /// Followed the EXIF IFD pointer (34665)
+    Exif,
+    /// Followed the GPS IFD pointer (34853)
+    Gps,
+    /// Followed the Interoperability IFD pointer (40965)
+    Interop,
+}
+
+impl core::fmt::Display for IfdPathSegment {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IfdPathSegment::Index(index) => write!(f, "{index}"),
+            IfdPathSegment::SubIfd => f.write_str("SubIFD"),
+            IfdPathSegment::Exif => f.write_str("Exif"),
+            IfdPathSegment::Gps => f.write_str("GPS"),
+            IfdPathSegment::Interop => f.write_str("Interop"),
+        }
+    }
+}
+
+/// A directory found while walking a file, together with how it was reached
+///
+/// The path displays as e.g. `[0]`, `[0, SubIFD, 1]` or `[0, Exif]`.
+#[derive(Debug, Clone)]
+pub struct IfdPath {
+    /// Steps from the main chain down to this directory
+    pub segments: Vec<IfdPathSegment>,
+    /// Byte offset of the directory in the file
+    pub offset: usize,
+    /// The parsed directory
+    pub ifd: ImageFileDirectory,
+}
+
+impl core::fmt::Display for IfdPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("[")?;
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{segment}")?;
+        }
+        f.write_str("]")
+    }
 }
 
 /// An Image File Directory containing tag entries
 /// 
 /// This represents one "page" or "image" in a TIFF file. Multi-page
 /// TIFFs have multiple IFDs linked together.
+///
+/// Values parsed through `get_tag_value` (and so by every accessor built
+/// on it) are cached, so repeated accessor calls don't re-read the data.
+/// Cached values are kept per reader and strict-ASCII setting, so using
+/// the IFD with another reader parses again, and per entry contents, so
+/// editing an entry in `entries` parses its value again.
+///
+/// Because of the cache, an IFD can't be built with a struct literal;
+/// use `ImageFileDirectory::new`.
 #[derive(Debug, Clone)]
 pub struct ImageFileDirectory {
     /// The IFD entries (tags)
     pub entries: Vec<IfdEntry>,
     /// Offset to the next IFD (0 if this is the last one)
     pub next_ifd_offset: usize,
+    /// Values already parsed by `get_tag_value`
+    cache: TagCache,
+}
+
+/// Parsed tag values keyed by reader, strict-ASCII setting and entry
+///
+/// The key holds the whole entry, so an edited entry misses instead of
+/// returning a stale value; entries are never invalidated. A clone
+/// starts empty. Values are shared, so a hit doesn't copy them. The map
+/// sits behind a `Mutex` so IFDs stay `Sync`; builds without std use a
+/// `SpinLock` instead.
+#[derive(Default)]
+struct TagCache {
+    #[cfg(feature = "std")]
+    values: std::sync::Mutex<BTreeMap<TagCacheKey, Arc<TagValue>>>,
+    #[cfg(not(feature = "std"))]
+    values: SpinLock<BTreeMap<TagCacheKey, Arc<TagValue>>>,
+}
+
+/// `TiffReader::cache_key` of the reader that parsed a value, plus the
+/// entry's tag, field type, count and value offset
+type TagCacheKey = ((usize, bool), (u16, u16, u64, u64));
+
+impl TagCache {
+    /// Run `f` with the cached values
+    fn with<R>(&self, f: impl FnOnce(&mut BTreeMap<TagCacheKey, Arc<TagValue>>) -> R) -> R {
+        #[cfg(feature = "std")]
+        {
+            // A poisoned lock only means a panic while inserting a finished value
+            let mut values = self.values.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&mut values)
+        }
+        #[cfg(not(feature = "std"))]
+        self.values.with(f)
+    }
+}
+
+/// Minimal lock for builds without `std::sync::Mutex`
+///
+/// The cache holds it only for a single map lookup or insert, so spinning
+/// is cheap.
+#[cfg(not(feature = "std"))]
+#[derive(Default)]
+struct SpinLock<T> {
+    locked: core::sync::atomic::AtomicBool,
+    value: core::cell::UnsafeCell<T>,
+}
+
+// SAFETY: `value` is only reached through `with`, which holds `locked`
+#[cfg(not(feature = "std"))]
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> SpinLock<T> {
+    /// Run `f` with exclusive access to the value
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        /// Releases the lock when dropped, including while unwinding
+        struct Unlock<'a>(&'a AtomicBool);
+        impl Drop for Unlock<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+        let _unlock = Unlock(&self.locked);
+        // SAFETY: holding the lock gives exclusive access to `value`
+        f(unsafe { &mut *self.value.get() })
+    }
+}
+
+impl Clone for TagCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl core::fmt::Debug for TagCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let cached = self.with(|values| values.len());
+        f.debug_struct("TagCache").field("cached", &cached).finish()
+    }
 }
 
 impl ImageFileDirectory {
+    /// Create an IFD from its entries and next-IFD link
+    pub fn new(entries: Vec<IfdEntry>, next_ifd_offset: usize) -> Self {
+        Self {
+            entries,
+            next_ifd_offset,
+            cache: TagCache::default(),
+        }
+    }
+
     /// Find an entry by tag number
     pub fn find_entry(&self, tag: u16) -> Option<&IfdEntry> {
         self.entries.iter().find(|entry| entry.tag == tag)
     }
 
+    /// Find an entry by typed tag
+    ///
+    /// Equivalent to `find_entry(tag.as_u16())`.
+    pub fn find_tag(&self, tag: Tag) -> Option<&IfdEntry> {
+        self.find_entry(tag.as_u16())
+    }
+
     /// Get the number of entries
     pub fn len(&self) -> usize {
         self.entries.len()
@@ -328,115 +721,335 @@ impl ImageFileDirectory {
         self.entries.is_empty()
     }
 
+    /// Iterate over the entries paired with their tag names
+    ///
+    /// Tags without a known name are labeled "Unknown".
+    pub fn iter_named(&self) -> impl Iterator<Item = (&IfdEntry, &'static str)> {
+        self.entries.iter().map(|entry| (entry, tags::tag_name(entry.tag)))
+    }
+
+    /// Get a structural view of the entries without parsing any values
+    ///
+    /// Returns each entry's tag, field type, and count, sorted by tag. This
+    /// never touches the data source, so it's cheap enough for structural
+    /// comparison and display.
+    ///
+    /// Entries with an unknown field type are skipped rather than failing
+    /// the whole call, matching how readers are expected to ignore them.
+    pub fn entry_descriptors(&self) -> Vec<(u16, FieldType, u64)> {
+        let mut descriptors: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                FieldType::from_u16(entry.field_type)
+                    .ok()
+                    .map(|field_type| (entry.tag, field_type, entry.count))
+            })
+            .collect();
+        descriptors.sort_by_key(|descriptor| descriptor.0);
+        descriptors
+    }
+
     /// Get a parsed tag value by tag number
     /// 
     /// This is a convenience method that finds the entry and parses its value.
+    /// The first successful parse of each tag is cached on the IFD.
     pub fn get_tag_value<T: TiffDataSource>(
         &self, 
         tag: u16, 
         reader: &TiffReader<T>, 
         endian: Endian
     ) -> Result<Option<TagValue>> {
-        if let Some(entry) = self.find_entry(tag) {
-            Ok(Some(reader.parse_tag_value(entry, endian)?))
+        Ok(self.cached_tag_value(tag, reader, endian)?.map(|value| (*value).clone()))
+    }
+
+    /// Get a parsed tag value through the cache without copying it
+    ///
+    /// The accessors use this, since most only need a scalar out of the value.
+    fn cached_tag_value<T: TiffDataSource>(
+        &self,
+        tag: u16,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<Arc<TagValue>>> {
+        let Some(entry) = self.find_entry(tag) else {
+            return Ok(None);
+        };
+        let key = (reader.cache_key(), (entry.tag, entry.field_type, entry.count, entry.value_offset));
+        if let Some(value) = self.cache.with(|values| values.get(&key).cloned()) {
+            return Ok(Some(value));
+        }
+        let value = Arc::new(reader.parse_tag_value(entry, endian)?);
+        self.cache.with(|values| values.insert(key, Arc::clone(&value)));
+        Ok(Some(value))
+    }
+
+    /// Format every tag as a `Name (#tag): value` line
+    ///
+    /// Entries are listed in file order; arrays are truncated after 64
+    /// values. Tags without a known name are labeled "Unknown". A value
+    /// that fails to parse is shown as `<error: ...>` on its own line, so
+    /// one malformed entry doesn't hide the rest of the directory.
+    pub fn dump<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> String {
+        let mut text = String::new();
+        for (entry, name) in self.iter_named() {
+            let value = match reader.parse_tag_value(entry, endian) {
+                Ok(value) => value.to_display_string(64),
+                Err(err) => format!("<error: {err}>"),
+            };
+            text.push_str(&format!("{name} (#{}): {value}\n", entry.tag));
+        }
+        text
+    }
+
+    /// Get the IFD offsets stored in a pointer tag such as SubIFDs or ExifIFD
+    ///
+    /// Pointer tags are LONG or IFD (type 13) values, or LONG8/IFD8 in
+    /// BigTIFF; IFD isn't a `FieldType`, so the offsets are read directly
+    /// from the entry.
+    ///
+    /// # Returns
+    /// The offsets in tag order, or an empty vec if the tag is missing
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the tag has any other field type
+    pub(crate) fn ifd_pointer_offsets<T: TiffDataSource>(
+        &self,
+        tag: u16,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Vec<usize>> {
+        let Some(entry) = self.find_entry(tag) else {
+            return Ok(Vec::new());
+        };
+        let size = match entry.field_type {
+            4 | 13 => 4,
+            16 | 18 => 8,
+            other => {
+                return Err(TiffError::InvalidTag {
+                    tag,
+                    reason: format!("expected LONG or IFD offsets, found field type {other}"),
+                });
+            }
+        };
+
+        let total = usize::try_from(entry.count).unwrap_or(usize::MAX).saturating_mul(size);
+        let bytes = if total <= reader.offset_size() {
+            reader.inline_value_bytes(entry, endian)[..total].to_vec()
+        } else if total > reader.len() {
+            return Err(TiffError::InsufficientData {
+                operation: "reading IFD pointers",
+                needed: total,
+                available: reader.len(),
+            });
         } else {
-            Ok(None)
+            reader.read_bytes_at(entry.value_offset as usize, total)?
+        };
+        Ok(bytes
+            .chunks_exact(size)
+            .map(|b| match *b {
+                [a, b, c, d] => endian.read_u32([a, b, c, d]) as usize,
+                _ => endian.read_u64([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as usize,
+            })
+            .collect())
+    }
+
+    /// Get a parsed tag value, checking that it has exactly `expected_count` values
+    ///
+    /// Useful for fixed-size tags such as ModelPixelScale (3 doubles).
+    /// The count is checked from the IFD entry before the value is read.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the entry's count differs from `expected_count`
+    pub fn get_tag_value_expecting<T: TiffDataSource>(
+        &self,
+        tag: u16,
+        expected_count: u32,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<TagValue>> {
+        let Some(entry) = self.find_entry(tag) else {
+            return Ok(None);
+        };
+        if entry.count != expected_count as u64 {
+            return Err(TiffError::InvalidTag {
+                tag,
+                reason: format!("expected {expected_count} values, found {}", entry.count),
+            });
         }
+        Ok(Some(reader.parse_tag_value(entry, endian)?))
     }
 
     // =============================================================================
     // Basic image information convenience methods
     // =============================================================================
 
+    /// Get the NewSubfileType flags
+    ///
+    /// Bit 0 (1) marks a reduced-resolution version of another image, bit 1
+    /// (2) a page of a multi-page document and bit 2 (4) a transparency mask.
+    pub fn new_subfile_type<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
+        Ok(self.cached_tag_value(tags::tags::NEW_SUBFILE_TYPE, reader, endian)?
+            .and_then(|v| v.as_u32()))
+    }
+
     /// Get image width in pixels
     pub fn image_width<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::IMAGE_WIDTH, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::IMAGE_WIDTH, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
     /// Get image height in pixels
     pub fn image_height<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::IMAGE_LENGTH, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::IMAGE_LENGTH, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
     /// Get bits per sample (per channel)
     pub fn bits_per_sample<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u32>>> {
-        Ok(self.get_tag_value(tags::tags::BITS_PER_SAMPLE, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::BITS_PER_SAMPLE, reader, endian)?
             .and_then(|v| v.as_u32_vec()))
     }
 
     /// Get samples (channels) per pixel
     pub fn samples_per_pixel<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::SAMPLES_PER_PIXEL, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::SAMPLES_PER_PIXEL, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
+    /// Get samples per pixel, falling back to the spec default of 1
+    ///
+    /// Use this rather than `samples_per_pixel()` wherever a concrete
+    /// count is needed, so every caller applies the same default.
+    pub fn samples_per_pixel_or_default<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<u32> {
+        Ok(self.samples_per_pixel(reader, endian)?.unwrap_or(1))
+    }
+
     /// Get compression type
     pub fn compression<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Compression>> {
-        Ok(self.get_tag_value(tags::tags::COMPRESSION, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::COMPRESSION, reader, endian)?
             .and_then(|v| v.as_u32())
             .and_then(Compression::from_u32))
     }
 
     /// Get photometric interpretation
     pub fn photometric_interpretation<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<PhotometricInterpretation>> {
-        Ok(self.get_tag_value(tags::tags::PHOTOMETRIC_INTERPRETATION, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::PHOTOMETRIC_INTERPRETATION, reader, endian)?
             .and_then(|v| v.as_u32())
             .and_then(PhotometricInterpretation::from_u32))
     }
 
     /// Get sample format
     pub fn sample_format<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<SampleFormat>> {
-        Ok(self.get_tag_value(tags::tags::SAMPLE_FORMAT, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::SAMPLE_FORMAT, reader, endian)?
             .and_then(|v| v.as_u32())
             .and_then(SampleFormat::from_u32))
     }
 
+    /// Get fill order (bit order within each byte)
+    pub fn fill_order<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<FillOrder>> {
+        Ok(self.cached_tag_value(tags::tags::FILL_ORDER, reader, endian)?
+            .and_then(|v| v.as_u32())
+            .and_then(FillOrder::from_u32))
+    }
+
+    /// Get the orientation of the stored image relative to the display
+    pub fn orientation<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Orientation>> {
+        Ok(self.cached_tag_value(tags::tags::ORIENTATION, reader, endian)?
+            .and_then(|v| v.as_u32())
+            .and_then(Orientation::from_u32))
+    }
+
+    /// Get the predictor applied before compression (1 = none, 2 = horizontal, 3 = floating point)
+    pub fn predictor<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
+        Ok(self.cached_tag_value(tags::tags::PREDICTOR, reader, endian)?
+            .and_then(|v| v.as_u32()))
+    }
+
+    /// Get planar configuration (chunky or planar sample layout)
+    pub fn planar_configuration<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<PlanarConfiguration>> {
+        Ok(self.cached_tag_value(tags::tags::PLANAR_CONFIGURATION, reader, endian)?
+            .and_then(|v| v.as_u32())
+            .and_then(PlanarConfiguration::from_u32))
+    }
+
     // =============================================================================
     // Image data organization convenience methods
     // =============================================================================
 
     /// Get strip offsets (where image data is stored)
-    pub fn strip_offsets<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u32>>> {
-        Ok(self.get_tag_value(tags::tags::STRIP_OFFSETS, reader, endian)?
-            .and_then(|v| v.as_u32_vec()))
+    pub fn strip_offsets<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u64>>> {
+        Ok(self.cached_tag_value(tags::tags::STRIP_OFFSETS, reader, endian)?
+            .and_then(|v| v.as_u64_vec()))
     }
 
     /// Get strip byte counts (how much data per strip)
-    pub fn strip_byte_counts<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u32>>> {
-        Ok(self.get_tag_value(tags::tags::STRIP_BYTE_COUNTS, reader, endian)?
-            .and_then(|v| v.as_u32_vec()))
+    pub fn strip_byte_counts<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u64>>> {
+        Ok(self.cached_tag_value(tags::tags::STRIP_BYTE_COUNTS, reader, endian)?
+            .and_then(|v| v.as_u64_vec()))
     }
 
     /// Get rows per strip
     pub fn rows_per_strip<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::ROWS_PER_STRIP, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::ROWS_PER_STRIP, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
+    /// Get rows per strip, falling back to the spec default of one strip
+    ///
+    /// A missing RowsPerStrip means the whole image is a single strip.
+    /// Writers also use huge values (commonly 0xFFFFFFFF) to mean "one
+    /// strip", so the result is clamped to the image height; an invalid 0
+    /// is treated as missing. Use this rather than `rows_per_strip()`
+    /// wherever strips are counted.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if ImageLength is missing
+    pub fn rows_per_strip_or_default<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<u32> {
+        let height = self.image_height(reader, endian)?.ok_or(TiffError::InvalidTag {
+            tag: tags::tags::IMAGE_LENGTH,
+            reason: "required tag is missing".to_string(),
+        })?;
+        let rows_per_strip = match self.rows_per_strip(reader, endian)? {
+            Some(0) | None => height,
+            Some(rows) => rows.min(height),
+        };
+        Ok(rows_per_strip.max(1))
+    }
+
+    /// Get the number of strips the layout calls for, from RowsPerStrip and the height
+    ///
+    /// This doesn't depend on how many offsets the file actually lists.
+    ///
+    /// # Returns
+    /// None for tiled images or a missing ImageLength
+    pub fn strips_per_image<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<usize>> {
+        Ok(self.strip_layout(reader, endian)?
+            .map(|(height, rows_per_strip)| height.div_ceil(rows_per_strip) as usize))
+    }
+
     /// Get tile width (for tiled images)
     pub fn tile_width<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::TILE_WIDTH, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::TILE_WIDTH, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
     /// Get tile height (for tiled images)
     pub fn tile_height<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<u32>> {
-        Ok(self.get_tag_value(tags::tags::TILE_LENGTH, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::TILE_LENGTH, reader, endian)?
             .and_then(|v| v.as_u32()))
     }
 
     /// Get tile offsets (for tiled images)
-    pub fn tile_offsets<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u32>>> {
-        Ok(self.get_tag_value(tags::tags::TILE_OFFSETS, reader, endian)?
-            .and_then(|v| v.as_u32_vec()))
+    pub fn tile_offsets<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u64>>> {
+        Ok(self.cached_tag_value(tags::tags::TILE_OFFSETS, reader, endian)?
+            .and_then(|v| v.as_u64_vec()))
     }
 
     /// Get tile byte counts (for tiled images)
-    pub fn tile_byte_counts<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u32>>> {
-        Ok(self.get_tag_value(tags::tags::TILE_BYTE_COUNTS, reader, endian)?
-            .and_then(|v| v.as_u32_vec()))
+    pub fn tile_byte_counts<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u64>>> {
+        Ok(self.cached_tag_value(tags::tags::TILE_BYTE_COUNTS, reader, endian)?
+            .and_then(|v| v.as_u64_vec()))
     }
 
     /// Check if this image uses tiled layout (vs strip layout)
@@ -444,100 +1057,652 @@ impl ImageFileDirectory {
         Ok(self.tile_width(reader, endian)?.is_some())
     }
 
+    /// Get the index of the strip that holds image row `row`
+    ///
+    /// RowsPerStrip defaults as in `rows_per_strip_or_default`. For planar
+    /// images the index is within the first plane; add
+    /// `plane * strips_per_plane` for the others.
+    ///
+    /// # Returns
+    /// None for tiled images, a missing ImageLength, or a row past the image
+    pub fn strip_for_row<T: TiffDataSource>(&self, row: u32, reader: &TiffReader<T>, endian: Endian) -> Result<Option<usize>> {
+        Ok(self.strip_layout(reader, endian)?
+            .filter(|&(height, _)| row < height)
+            .map(|(_, rows_per_strip)| (row / rows_per_strip) as usize))
+    }
+
+    /// Get the rows covered by strip `index` as (first row, row count)
+    ///
+    /// The last strip is usually shorter; its row count is clamped to the
+    /// image height.
+    ///
+    /// # Returns
+    /// None for tiled images, a missing ImageLength, or an index past the
+    /// last strip
+    pub fn rows_in_strip<T: TiffDataSource>(&self, index: usize, reader: &TiffReader<T>, endian: Endian) -> Result<Option<(u32, u32)>> {
+        let Some((height, rows_per_strip)) = self.strip_layout(reader, endian)? else {
+            return Ok(None);
+        };
+        if index >= height.div_ceil(rows_per_strip) as usize {
+            return Ok(None);
+        }
+        let first_row = index as u32 * rows_per_strip;
+        Ok(Some((first_row, rows_per_strip.min(height - first_row))))
+    }
+
+    /// Get the row-major index of the tile that holds pixel (`x`, `y`)
+    ///
+    /// For planar images the index is within the first plane.
+    ///
+    /// # Returns
+    /// None for stripped images, missing dimensions, or a pixel outside the image
+    pub fn tile_for_pixel<T: TiffDataSource>(&self, x: u32, y: u32, reader: &TiffReader<T>, endian: Endian) -> Result<Option<usize>> {
+        let (Some(width), Some(height)) = (self.image_width(reader, endian)?, self.image_height(reader, endian)?) else {
+            return Ok(None);
+        };
+        let (Some(tile_width), Some(tile_height)) = (self.tile_width(reader, endian)?, self.tile_height(reader, endian)?) else {
+            return Ok(None);
+        };
+        if x >= width || y >= height || tile_width == 0 || tile_height == 0 {
+            return Ok(None);
+        }
+        let tiles_across = width.div_ceil(tile_width) as usize;
+        Ok(Some((y / tile_height) as usize * tiles_across + (x / tile_width) as usize))
+    }
+
+    /// Get (image height, rows per strip) for a stripped image
+    ///
+    /// None for tiled images or a missing ImageLength.
+    fn strip_layout<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<(u32, u32)>> {
+        if self.is_tiled(reader, endian)? {
+            return Ok(None);
+        }
+        let Some(height) = self.image_height(reader, endian)? else {
+            return Ok(None);
+        };
+        Ok(Some((height, self.rows_per_strip_or_default(reader, endian)?)))
+    }
+
+    /// Get the (offset, byte count) range of every strip or tile
+    ///
+    /// Uses the tile tags for tiled images and the strip tags otherwise.
+    /// Returns an empty vec when the location tags are missing.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the offset and byte count arrays differ in length
+    pub fn image_data_ranges<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Vec<(usize, usize)>> {
+        let (offsets, byte_counts) = if self.is_tiled(reader, endian)? {
+            (self.tile_offsets(reader, endian)?, self.tile_byte_counts(reader, endian)?)
+        } else {
+            (self.strip_offsets(reader, endian)?, self.strip_byte_counts(reader, endian)?)
+        };
+
+        let (Some(offsets), Some(byte_counts)) = (offsets, byte_counts) else {
+            return Ok(Vec::new());
+        };
+
+        if offsets.len() != byte_counts.len() {
+            return Err(TiffError::MalformedFile {
+                reason: format!(
+                    "{} data offsets but {} byte counts",
+                    offsets.len(),
+                    byte_counts.len()
+                ),
+            });
+        }
+
+        Ok(offsets
+            .iter()
+            .zip(&byte_counts)
+            .map(|(&offset, &count)| {
+                (usize::try_from(offset).unwrap_or(usize::MAX), usize::try_from(count).unwrap_or(usize::MAX))
+            })
+            .collect())
+    }
+
+    /// Check that every out-of-line value and every strip or tile lies inside the file
+    ///
+    /// Reads only the offset and byte count arrays, so a file with bad
+    /// offsets can be rejected before any pixel data is touched. Entries
+    /// with unknown field types are skipped, since their size is unknown.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` naming the first value, strip or tile that
+    /// ends past the end of the file or whose end overflows, or any error
+    /// from `image_data_ranges`
+    pub fn validate_offsets<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<()> {
+        for entry in &self.entries {
+            match reader.check_value_bounds(entry) {
+                Ok(()) | Err(TiffError::InvalidFieldType { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let block = if self.is_tiled(reader, endian)? { "tile" } else { "strip" };
+        for (index, (offset, count)) in self.image_data_ranges(reader, endian)?.into_iter().enumerate() {
+            let Some(end) = offset.checked_add(count) else {
+                return Err(TiffError::MalformedFile {
+                    reason: format!("{block} {index} at offset {offset} ({count} bytes) ends past the addressable range"),
+                });
+            };
+            if end > reader.len() {
+                return Err(TiffError::MalformedFile {
+                    reason: format!(
+                        "{block} {index} at offset {offset} ({count} bytes) ends at byte {end}, past the end of the file ({} bytes)",
+                        reader.len()
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the raw, still-compressed bytes of every strip or tile
+    ///
+    /// Blocks are returned in file order (strip index or row-major tile
+    /// index), without decompression or predictor reversal. Useful for
+    /// copying pixel data between files or checksumming it.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if a block lies outside the file, or any error
+    /// from `image_data_ranges`
+    pub fn read_all_strips_raw<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Vec<Vec<u8>>> {
+        self.image_data_ranges(reader, endian)?
+            .into_iter()
+            .map(|(offset, count)| reader.read_bytes_at(offset, count))
+            .collect()
+    }
+
+    /// Read the raw bytes of every strip or tile concatenated into one buffer
+    ///
+    /// # Errors
+    /// Same as `read_all_strips_raw`
+    pub fn read_all_image_data_raw<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Vec<u8>> {
+        Ok(self.read_all_strips_raw(reader, endian)?.concat())
+    }
+
+    // =============================================================================
+    // Decoded image data
+    // =============================================================================
+
+    /// Read a strip and return its decompressed bytes
+    ///
+    /// The Compression and Predictor tags are applied. The last strip of
+    /// an image may hold fewer than RowsPerStrip rows, and is decoded to
+    /// just the rows it covers.
+    ///
+    /// This is a shortcut for `TiffImageReader::new(...)?.read_strip(index)`;
+    /// build a `TiffImageReader` once when reading many strips.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for an index past the last strip,
+    /// `UnsupportedFeature` for tiled images or unsupported compression,
+    /// and `InvalidTag` if the image dimensions are missing
+    pub fn read_strip<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian, index: usize) -> Result<Vec<u8>> {
+        TiffImageReader::new(reader, self, endian)?.read_strip(index)
+    }
+
+    /// Read the tile at the given tile column and row and return its decompressed bytes
+    ///
+    /// Tiles are numbered row-major, with `ceil(ImageWidth / TileWidth)`
+    /// tiles per row. TIFF pads edge tiles that extend past the image out
+    /// to the full tile size, and the returned buffer is that full padded
+    /// tile; it is not cropped to the image.
+    ///
+    /// This is a shortcut for `TiffImageReader::new(...)?.read_tile(col, row)`.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for coordinates outside the tile grid,
+    /// `UnsupportedFeature` for stripped images or unsupported compression,
+    /// and `InvalidTag` if the image dimensions are missing
+    pub fn read_tile<T: TiffDataSource>(
+        &self,
+        reader: &TiffReader<T>,
+        endian: Endian,
+        tile_col: u32,
+        tile_row: u32,
+    ) -> Result<Vec<u8>> {
+        TiffImageReader::new(reader, self, endian)?.read_tile(tile_col, tile_row)
+    }
+
+    /// Decode the whole image into one contiguous row-major buffer
+    ///
+    /// Strips or tiles are decompressed, predictor-reversed and assembled
+    /// into `height` rows of `width` pixels; padded edge tiles are
+    /// cropped. Planar images are interleaved into chunky pixels. See
+    /// `TiffImageReader::read_image`.
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` for compression schemes without a
+    /// decoder, and `InvalidTag` if the image dimensions are missing
+    pub fn read_image<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Vec<u8>> {
+        TiffImageReader::new(reader, self, endian)?.read_image()
+    }
+
     // =============================================================================
     // Resolution convenience methods
     // =============================================================================
 
     /// Get X resolution (horizontal)
+    ///
+    /// The spec requires RATIONAL, but SRATIONAL values are read too so
+    /// that slightly-off files still load. `TiffFile::from_bytes_validated`
+    /// rejects them.
     pub fn x_resolution<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<f64>> {
-        Ok(self.get_tag_value(tags::tags::X_RESOLUTION, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::X_RESOLUTION, reader, endian)?
             .and_then(|v| v.as_rational_f64()))
     }
 
     /// Get Y resolution (vertical)
+    ///
+    /// Like `x_resolution`, SRATIONAL values are accepted.
     pub fn y_resolution<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<f64>> {
-        Ok(self.get_tag_value(tags::tags::Y_RESOLUTION, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::Y_RESOLUTION, reader, endian)?
             .and_then(|v| v.as_rational_f64()))
     }
 
     /// Get resolution unit
     pub fn resolution_unit<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<ResolutionUnit>> {
-        Ok(self.get_tag_value(tags::tags::RESOLUTION_UNIT, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::RESOLUTION_UNIT, reader, endian)?
             .and_then(|v| v.as_u32())
             .and_then(ResolutionUnit::from_u32))
     }
 
+    /// Get the palette (ColorMap, tag 320) as (red, green, blue) entries
+    ///
+    /// The tag stores all reds, then all greens, then all blues, each as
+    /// 16-bit values. Entry `i` of the result is the color for pixel index `i`.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the value count isn't a multiple of 3
+    pub fn color_map<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<(u16, u16, u16)>>> {
+        let Some(TagValue::Shorts(values)) = self.get_tag_value(tags::tags::COLORMAP, reader, endian)? else {
+            return Ok(None);
+        };
+        if values.len() % 3 != 0 {
+            return Err(TiffError::InvalidTag {
+                tag: tags::tags::COLORMAP,
+                reason: format!("{} values is not a multiple of 3", values.len()),
+            });
+        }
+        let (reds, rest) = values.split_at(values.len() / 3);
+        let (greens, blues) = rest.split_at(values.len() / 3);
+        Ok(Some(
+            reds.iter()
+                .zip(greens)
+                .zip(blues)
+                .map(|((&r, &g), &b)| (r, g, b))
+                .collect(),
+        ))
+    }
+
+    /// Get the YCbCrCoefficients (LumaRed, LumaGreen, LumaBlue)
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the tag doesn't have exactly 3 values
+    pub fn ycbcr_coefficients<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<[f64; 3]>> {
+        self.rationals_expecting::<_, 3>(tags::tags::YCBCR_COEFFICIENTS, reader, endian)
+    }
+
+    /// Get the YCbCrSubSampling factors as (horizontal, vertical)
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the tag doesn't have exactly 2 values
+    pub fn ycbcr_sub_sampling<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<(u16, u16)>> {
+        Ok(self.get_tag_value_expecting(tags::tags::YCBCR_SUB_SAMPLING, 2, reader, endian)?
+            .and_then(|v| match v {
+                TagValue::Shorts(s) => Some((s[0], s[1])),
+                _ => None,
+            }))
+    }
+
+    /// Get the ReferenceBlackWhite codes as [black, white] pairs for each
+    /// of the three components
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the tag doesn't have exactly 6 values
+    pub fn reference_black_white<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<[f64; 6]>> {
+        self.rationals_expecting::<_, 6>(tags::tags::REFERENCE_BLACK_WHITE, reader, endian)
+    }
+
+    /// Read a tag of exactly `N` RATIONALs as floats
+    fn rationals_expecting<T: TiffDataSource, const N: usize>(
+        &self,
+        tag: u16,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<[f64; N]>> {
+        Ok(self.get_tag_value_expecting(tag, N as u32, reader, endian)?
+            .and_then(|v| match v {
+                TagValue::Rationals(r) if r.iter().all(|&(_, den)| den != 0) => {
+                    Some(core::array::from_fn(|i| r[i].0 as f64 / r[i].1 as f64))
+                }
+                _ => None,
+            }))
+    }
+
+    /// Get the printed size of the image in millimeters as (width, height)
+    ///
+    /// Divides the pixel dimensions by XResolution and YResolution and
+    /// converts from the ResolutionUnit (25.4 mm per inch, 10 mm per cm).
+    ///
+    /// # Returns
+    /// `None` if the dimensions, either resolution or the unit is missing,
+    /// if a resolution is not positive, or if the unit is `None` (no
+    /// absolute unit)
+    pub fn physical_size_mm<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<(f64, f64)>> {
+        let mm_per_unit = match self.resolution_unit(reader, endian)? {
+            Some(ResolutionUnit::Inch) => 25.4,
+            Some(ResolutionUnit::Centimeter) => 10.0,
+            Some(ResolutionUnit::None) | None => return Ok(None),
+        };
+        let (Some(width), Some(height), Some(x_res), Some(y_res)) = (
+            self.image_width(reader, endian)?,
+            self.image_height(reader, endian)?,
+            self.x_resolution(reader, endian)?,
+            self.y_resolution(reader, endian)?,
+        ) else {
+            return Ok(None);
+        };
+        if x_res <= 0.0 || y_res <= 0.0 {
+            return Ok(None);
+        }
+        Ok(Some((
+            width as f64 / x_res * mm_per_unit,
+            height as f64 / y_res * mm_per_unit,
+        )))
+    }
+
     // =============================================================================
     // Metadata convenience methods
     // =============================================================================
 
+    /// Get the shared JPEG tables stream (JPEGTables, tag 347)
+    ///
+    /// For Compression = 7 this is an abbreviated JPEG stream (SOI, tables,
+    /// EOI) holding the quantization and Huffman tables that the individual
+    /// strips or tiles omit.
+    pub fn jpeg_tables<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u8>>> {
+        Ok(self.cached_tag_value(tags::tags::JPEG_TABLES, reader, endian)?
+            .and_then(|v| v.as_bytes().map(|b| b.to_vec())))
+    }
+
     /// Get image description
     pub fn image_description<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::IMAGE_DESCRIPTION, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::IMAGE_DESCRIPTION, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get make/manufacturer
     pub fn make<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::MAKE, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::MAKE, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get model
     pub fn model<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::MODEL, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::MODEL, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get software used to create the image
     pub fn software<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::SOFTWARE, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::SOFTWARE, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get creation date/time
     pub fn date_time<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::DATE_TIME, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::DATE_TIME, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get artist/photographer
     pub fn artist<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::ARTIST, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::ARTIST, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
     /// Get copyright notice
     pub fn copyright<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
-        Ok(self.get_tag_value(tags::tags::COPYRIGHT, reader, endian)?
+        Ok(self.cached_tag_value(tags::tags::COPYRIGHT, reader, endian)?
             .and_then(|v| v.as_string().map(|s| s.to_string())))
     }
 
+    /// Collect every known ASCII tag into a name -> value map
+    ///
+    /// Scans the entries once and parses only those with the ASCII field
+    /// type and a name in `tag_name` (ImageDescription, Make, Model,
+    /// Software, DateTime, Artist, Copyright, ...). With a strict-ASCII
+    /// reader, entries whose text isn't valid UTF-8 are skipped rather than
+    /// failing the whole map.
+    pub fn string_metadata<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<BTreeMap<&'static str, String>> {
+        let mut metadata = BTreeMap::new();
+        for entry in &self.entries {
+            let name = tags::tag_name(entry.tag);
+            if entry.field_type != FieldType::Ascii as u16 || name == "Unknown" {
+                continue;
+            }
+            match reader.parse_tag_value(entry, endian) {
+                Ok(TagValue::Ascii(value)) => {
+                    metadata.insert(name, value);
+                }
+                Ok(_) | Err(TiffError::InvalidString { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Get page number as (page, total pages)
+    ///
+    /// Pages are numbered from 0. A total of 0 means the page count is unknown.
+    pub fn page_number<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<(u16, u16)>> {
+        Ok(self.cached_tag_value(tags::tags::PAGE_NUMBER, reader, endian)?
+            .and_then(|v| match &*v {
+                TagValue::Shorts(values) if values.len() >= 2 => Some((values[0], values[1])),
+                _ => None,
+            }))
+    }
+
+    /// Get the embedded ICC color profile
+    ///
+    /// Returns the raw profile bytes, ready to hand to a color management
+    /// system. The profile itself is not parsed.
+    pub fn icc_profile<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u8>>> {
+        Ok(self.cached_tag_value(tags::tags::ICC_PROFILE, reader, endian)?
+            .and_then(|v| v.as_bytes().map(|b| b.to_vec())))
+    }
+
+    /// Get the embedded XMP packet as raw bytes
+    ///
+    /// Writers store XMP as either BYTE or UNDEFINED; both are accepted.
+    /// The XML is not parsed.
+    pub fn xmp<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u8>>> {
+        Ok(self.cached_tag_value(tags::tags::XMP, reader, endian)?
+            .and_then(|v| v.as_bytes().map(|b| b.to_vec())))
+    }
+
+    /// Get the embedded XMP packet as text
+    ///
+    /// Invalid UTF-8 is replaced with U+FFFD rather than failing.
+    pub fn xmp_string<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<String>> {
+        Ok(self.xmp(reader, endian)?
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// Get the embedded IPTC-NAA record as raw bytes
+    ///
+    /// Some writers declare the block as LONG so it stays 4-byte aligned;
+    /// those values are turned back into bytes in file order, so the
+    /// result is the same IIM byte stream either way.
+    pub fn iptc<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<Option<Vec<u8>>> {
+        Ok(self.cached_tag_value(tags::tags::IPTC, reader, endian)?
+            .and_then(|v| match &*v {
+                TagValue::Bytes(bytes) | TagValue::Undefined(bytes) => Some(bytes.clone()),
+                TagValue::Longs(longs) => Some(
+                    longs
+                        .iter()
+                        .flat_map(|&long| match endian {
+                            Endian::Little => long.to_le_bytes(),
+                            Endian::Big => long.to_be_bytes(),
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            }))
+    }
+
     // =============================================================================
-    // Validation and summary methods
+    // Sub-IFD pointers
     // =============================================================================
 
-    /// Check if this IFD has all required tags for a valid TIFF
-    pub fn is_valid_tiff<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<bool> {
-        let has_width = self.image_width(reader, endian)?.is_some();
-        let has_height = self.image_height(reader, endian)?.is_some();
-        let has_strips = self.strip_offsets(reader, endian)?.is_some();
-        let has_strip_counts = self.strip_byte_counts(reader, endian)?.is_some();
-        let has_tiles = self.tile_offsets(reader, endian)?.is_some();
-        let has_tile_counts = self.tile_byte_counts(reader, endian)?.is_some();
+    /// Read the EXIF IFD this directory points to
+    ///
+    /// The EXIF IFD holds camera metadata such as ExposureTime and FNumber;
+    /// read its values with `get_tag_value` on the returned directory.
+    ///
+    /// # Returns
+    /// `None` if there is no ExifIFD tag or it points at offset 0
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if the pointer lies outside the file, or
+    /// `InvalidTag` if the pointer tag isn't LONG or IFD
+    pub fn exif_ifd<T: TiffDataSource>(
+        &self,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<ImageFileDirectory>> {
+        self.pointed_ifd(tags::tags::EXIF_IFD, reader, endian)
+    }
 
-        // Must have width and height
-        // Must have either (strips + strip counts) OR (tiles + tile counts)
-        Ok(has_width && has_height && 
-           ((has_strips && has_strip_counts) || (has_tiles && has_tile_counts)))
+    /// Read the GPS IFD this directory points to
+    ///
+    /// # Returns
+    /// `None` if there is no GPSIFD tag or it points at offset 0
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if the pointer lies outside the file, or
+    /// `InvalidTag` if the pointer tag isn't LONG or IFD
+    pub fn gps_ifd<T: TiffDataSource>(
+        &self,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<ImageFileDirectory>> {
+        self.pointed_ifd(tags::tags::GPS_IFD, reader, endian)
     }
 
-    /// Get a summary of the image described by this IFD
-    pub fn image_summary<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<ImageSummary> {
-        let width = self.image_width(reader, endian)?.unwrap_or(0);
-        let height = self.image_height(reader, endian)?.unwrap_or(0);
-        let samples_per_pixel = self.samples_per_pixel(reader, endian)?.unwrap_or(1);
-        let bits_per_sample = self.bits_per_sample(reader, endian)?
+    /// Get the GPS position as signed decimal degrees (latitude, longitude)
+    ///
+    /// Reads GPSLatitude/GPSLongitude and their references from the GPS
+    /// IFD. Southern latitudes and western longitudes are negative.
+    ///
+    /// # Returns
+    /// `None` if there is no GPS IFD or any of the four tags is missing
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if a coordinate isn't three RATIONALs with
+    /// non-zero denominators or a reference isn't N/S or E/W
+    pub fn gps_coordinates<T: TiffDataSource>(
+        &self,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<(f64, f64)>> {
+        let Some(gps) = self.gps_ifd(reader, endian)? else {
+            return Ok(None);
+        };
+        let coordinate = |tag: u16, ref_tag: u16, negative: &str, positive: &str| -> Result<Option<f64>> {
+            let (Some(value), Some(reference)) =
+                (gps.get_tag_value(tag, reader, endian)?, gps.get_tag_value(ref_tag, reader, endian)?)
+            else {
+                return Ok(None);
+            };
+            let invalid = |reason: String| TiffError::InvalidTag { tag, reason };
+            let degrees = match value {
+                TagValue::Rationals(v) if v.len() == 3 && v.iter().all(|&(_, den)| den != 0) => {
+                    let [d, m, s] = [0, 1, 2].map(|i| v[i].0 as f64 / v[i].1 as f64);
+                    d + m / 60.0 + s / 3600.0
+                }
+                _ => return Err(invalid("expected 3 RATIONALs with non-zero denominators".to_string())),
+            };
+            match reference.as_string().map(str::trim) {
+                Some(r) if r == positive => Ok(Some(degrees)),
+                Some(r) if r == negative => Ok(Some(-degrees)),
+                other => Err(TiffError::InvalidTag {
+                    tag: ref_tag,
+                    reason: format!("expected \"{positive}\" or \"{negative}\", found {other:?}"),
+                }),
+            }
+        };
+        let latitude = coordinate(tags::tags::GPS_LATITUDE, tags::tags::GPS_LATITUDE_REF, "S", "N")?;
+        let longitude = coordinate(tags::tags::GPS_LONGITUDE, tags::tags::GPS_LONGITUDE_REF, "W", "E")?;
+        Ok(latitude.zip(longitude))
+    }
+
+    /// Read the child IFDs listed in the SubIFDs tag (330)
+    ///
+    /// SubIFDs usually hold reduced-resolution versions of this image or
+    /// masks for it. Only this level is read; call `sub_ifds` on the
+    /// results to go deeper, or use `TiffFile::walk_all_ifds`, which also
+    /// guards against pointer cycles.
+    ///
+    /// # Returns
+    /// The directories in tag order, skipping offsets of 0; empty if the
+    /// tag is missing
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if a pointer lies outside the file, or
+    /// `InvalidTag` if the tag isn't LONG or IFD
+    pub fn sub_ifds<T: TiffDataSource>(
+        &self,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Vec<ImageFileDirectory>> {
+        self.ifd_pointer_offsets(tags::tags::SUB_IFDS, reader, endian)?
+            .into_iter()
+            .filter(|&offset| offset != 0)
+            .map(|offset| read_pointed_ifd(offset, reader, endian))
+            .collect()
+    }
+
+    /// Read the single IFD a pointer tag points to
+    fn pointed_ifd<T: TiffDataSource>(
+        &self,
+        tag: u16,
+        reader: &TiffReader<T>,
+        endian: Endian,
+    ) -> Result<Option<ImageFileDirectory>> {
+        match self.ifd_pointer_offsets(tag, reader, endian)?.first() {
+            Some(&offset) if offset != 0 => read_pointed_ifd(offset, reader, endian).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    // =============================================================================
+    // Validation and summary methods
+    // =============================================================================
+
+    /// Check if this IFD has all required tags for a valid TIFF
+    pub fn is_valid_tiff<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<bool> {
+        let has_width = self.image_width(reader, endian)?.is_some();
+        let has_height = self.image_height(reader, endian)?.is_some();
+        let has_strips = self.strip_offsets(reader, endian)?.is_some();
+        let has_strip_counts = self.strip_byte_counts(reader, endian)?.is_some();
+        let has_tiles = self.tile_offsets(reader, endian)?.is_some();
+        let has_tile_counts = self.tile_byte_counts(reader, endian)?.is_some();
+
+        // Must have width and height
+        // Must have either (strips + strip counts) OR (tiles + tile counts)
+        Ok(has_width && has_height && 
+           ((has_strips && has_strip_counts) || (has_tiles && has_tile_counts)))
+    }
+
+    /// Get a summary of the image described by this IFD
+    pub fn image_summary<T: TiffDataSource>(&self, reader: &TiffReader<T>, endian: Endian) -> Result<ImageSummary> {
+        let width = self.image_width(reader, endian)?.unwrap_or(0);
+        let height = self.image_height(reader, endian)?.unwrap_or(0);
+        let samples_per_pixel = self.samples_per_pixel_or_default(reader, endian)?;
+        let bits_per_sample = self.bits_per_sample(reader, endian)?
             .unwrap_or_else(|| vec![1; samples_per_pixel as usize]);
         let compression = self.compression(reader, endian)?.unwrap_or(Compression::None);
         let photometric = self.photometric_interpretation(reader, endian)?;
@@ -551,12 +1716,72 @@ impl ImageFileDirectory {
             compression,
             photometric_interpretation: photometric,
             is_tiled,
+            new_subfile_type: self.new_subfile_type(reader, endian)?.unwrap_or(0),
         })
     }
 }
 
+/// Read the IFD at a pointer tag's offset, checking it lies in the file
+fn read_pointed_ifd<T: TiffDataSource>(
+    offset: usize,
+    reader: &TiffReader<T>,
+    endian: Endian,
+) -> Result<ImageFileDirectory> {
+    if offset >= reader.len() {
+        return Err(TiffError::OutOfBounds {
+            index: offset,
+            max: reader.len(),
+        });
+    }
+    reader.read_ifd_at(offset, endian)
+}
+
 /// Extension methods for TiffReader to handle IFD parsing
 impl<T: TiffDataSource> TiffReader<T> {
+    /// Get the size of offsets and inline values: 4 bytes, or 8 in BigTIFF
+    pub(crate) fn offset_size(&self) -> usize {
+        if self.is_bigtiff() { 8 } else { 4 }
+    }
+
+    /// Get the size of an IFD's entry count: 2 bytes, or 8 in BigTIFF
+    pub(crate) fn entry_count_size(&self) -> usize {
+        if self.is_bigtiff() { 8 } else { 2 }
+    }
+
+    /// Get the size of one IFD entry: 12 bytes, or 20 in BigTIFF
+    pub(crate) fn ifd_entry_size(&self) -> usize {
+        4 + 2 * self.offset_size()
+    }
+
+    /// Get the total size of an IFD with `entries` entries, including its
+    /// entry count and next-IFD link
+    pub(crate) fn ifd_byte_size(&self, entries: usize) -> usize {
+        self.entry_count_size() + entries * self.ifd_entry_size() + self.offset_size()
+    }
+
+    /// Read a 4-byte (classic) or 8-byte (BigTIFF) offset at `offset`
+    fn read_offset_at(&self, offset: usize, endian: Endian) -> Result<u64> {
+        if self.is_bigtiff() {
+            self.read_u64_at(offset, endian)
+        } else {
+            Ok(self.read_u32_at(offset, endian)? as u64)
+        }
+    }
+
+    /// Recover the bytes of an entry's value field exactly as they appear
+    /// in the file (4 bytes, or 8 in BigTIFF)
+    ///
+    /// value_offset was decoded with the file's byte order, so re-encoding
+    /// it the same way gives back the original bytes for II and MM files.
+    pub(crate) fn inline_value_bytes(&self, entry: &IfdEntry, endian: Endian) -> Vec<u8> {
+        match (self.is_bigtiff(), endian) {
+            (true, Endian::Little) => entry.value_offset.to_le_bytes().to_vec(),
+            (true, Endian::Big) => entry.value_offset.to_be_bytes().to_vec(),
+            (false, Endian::Little) => (entry.value_offset as u32).to_le_bytes().to_vec(),
+            (false, Endian::Big) => (entry.value_offset as u32).to_be_bytes().to_vec(),
+        }
+    }
+
     /// Read an IFD (Image File Directory) at the given offset
     /// 
     /// # Arguments
@@ -569,32 +1794,118 @@ impl<T: TiffDataSource> TiffReader<T> {
         // Seek to the IFD location
         self.seek(offset)?;
 
-        // Read number of directory entries (2 bytes)
-        let num_entries = self.read_u16(endian)?;
+        // Read number of directory entries (2 bytes, 8 in BigTIFF)
+        let num_entries = if self.is_bigtiff() {
+            usize::try_from(self.read_u64(endian)?).unwrap_or(usize::MAX)
+        } else {
+            self.read_u16(endian)? as usize
+        };
         
-        let mut entries = Vec::with_capacity(num_entries as usize);
+        // A BigTIFF count can claim far more entries than the file holds
+        let mut entries = Vec::with_capacity(num_entries.min(self.remaining() / self.ifd_entry_size()));
         
-        // Read each IFD entry (12 bytes each)
+        // Read each IFD entry (12 bytes each, 20 in BigTIFF)
         for _ in 0..num_entries {
             let entry = self.read_ifd_entry(endian)?;
             entries.push(entry);
         }
 
-        // Read offset to next IFD (4 bytes)
-        let next_ifd_offset = self.read_u32(endian)? as usize;
+        // Read offset to next IFD (4 bytes, 8 in BigTIFF)
+        let next_ifd_offset = self.read_offset_at(self.position(), endian)? as usize;
+        self.skip(self.offset_size())?;
 
-        Ok(ImageFileDirectory {
-            entries,
-            next_ifd_offset,
-        })
+        Ok(ImageFileDirectory::new(entries, next_ifd_offset))
+    }
+
+    /// Read an IFD at the given offset without moving the read position
+    ///
+    /// Same result as `read_ifd`, but usable through a shared reference,
+    /// e.g. when following pointers to sub-IFDs from a parsed `TiffFile`.
+    pub fn read_ifd_at(&self, offset: usize, endian: Endian) -> Result<ImageFileDirectory> {
+        let num_entries = usize::try_from(self.read_ifd_entry_count(offset, endian)?).unwrap_or(usize::MAX);
+        let entry_size = self.ifd_entry_size();
+        let offset_size = self.offset_size();
+        let first_entry = offset + self.entry_count_size();
+        let mut entries = Vec::with_capacity(num_entries.min(self.len() / entry_size));
+        for i in 0..num_entries {
+            let entry_offset = first_entry + i * entry_size;
+            entries.push(IfdEntry {
+                tag: self.read_u16_at(entry_offset, endian)?,
+                field_type: self.read_u16_at(entry_offset + 2, endian)?,
+                count: self.read_offset_at(entry_offset + 4, endian)?,
+                value_offset: self.read_offset_at(entry_offset + 4 + offset_size, endian)?,
+            });
+        }
+        let next_ifd_offset = self.read_offset_at(first_entry + num_entries * entry_size, endian)? as usize;
+
+        Ok(ImageFileDirectory::new(entries, next_ifd_offset))
     }
 
-    /// Read a single IFD entry (12 bytes)
+    /// Read an IFD, keeping only entries whose tag is in `tags`
+    ///
+    /// Every entry still has to be read to find the next-IFD link, but
+    /// dropped entries never have their values parsed and don't take up
+    /// space in the returned directory. This is useful for indexers that
+    /// only need a handful of tags from IFDs with large blobs (MakerNotes,
+    /// ICC profiles, ...).
+    ///
+    /// Filtered IFDs are not suitable for round-tripping through the writer,
+    /// since any tag not in `tags` is lost.
+    pub fn read_ifd_filtered(&mut self, offset: usize, endian: Endian, tags: &[u16]) -> Result<ImageFileDirectory> {
+        let mut ifd = self.read_ifd(offset, endian)?;
+        ifd.entries.retain(|entry| tags.contains(&entry.tag));
+        Ok(ifd)
+    }
+
+    /// Read just the entry count of the IFD at `offset`
+    ///
+    /// This is the count at the start of every IFD (2 bytes, or 8 in
+    /// BigTIFF), read without parsing any entries - useful for cheap
+    /// structural surveys.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the offset points inside the header, or
+    /// `OutOfBounds` if it's past the end of the data
+    pub fn read_ifd_entry_count(&self, offset: usize, endian: Endian) -> Result<u64> {
+        let header_size = if self.is_bigtiff() { TiffHeader::BIGTIFF_SIZE } else { TiffHeader::SIZE };
+        if offset < header_size {
+            return Err(TiffError::MalformedFile {
+                reason: format!("IFD offset {offset} points inside the file header"),
+            });
+        }
+        if self.is_bigtiff() {
+            self.read_u64_at(offset, endian)
+        } else {
+            Ok(self.read_u16_at(offset, endian)? as u64)
+        }
+    }
+
+    /// Read just the next-IFD offset of the IFD at `offset`
+    ///
+    /// Together with `read_ifd_entry_count` this allows walking the IFD
+    /// chain without parsing any entries.
+    pub fn read_ifd_next_offset(&self, offset: usize, endian: Endian) -> Result<usize> {
+        let num_entries = self.read_ifd_entry_count(offset, endian)?;
+        let link_offset = usize::try_from(num_entries)
+            .ok()
+            .and_then(|entries| entries.checked_mul(self.ifd_entry_size()))
+            .and_then(|size| size.checked_add(offset + self.entry_count_size()))
+            .ok_or(TiffError::OutOfBounds {
+                index: usize::MAX,
+                max: self.len(),
+            })?;
+        Ok(self.read_offset_at(link_offset, endian)? as usize)
+    }
+
+    /// Read a single IFD entry (12 bytes, or 20 in BigTIFF)
     fn read_ifd_entry(&mut self, endian: Endian) -> Result<IfdEntry> {
         let tag = self.read_u16(endian)?;
         let field_type = self.read_u16(endian)?;
-        let count = self.read_u32(endian)?;
-        let value_offset = self.read_u32(endian)?;
+        let (count, value_offset) = if self.is_bigtiff() {
+            (self.read_u64(endian)?, self.read_u64(endian)?)
+        } else {
+            (self.read_u32(endian)? as u64, self.read_u32(endian)? as u64)
+        };
 
         Ok(IfdEntry {
             tag,
@@ -604,29 +1915,103 @@ impl<T: TiffDataSource> TiffReader<T> {
         })
     }
 
+    /// Get the size in bytes of an entry's value
+    ///
+    /// # Errors
+    /// Returns `InvalidFieldType` for an unknown field type, or `InvalidTag`
+    /// if the size overflows
+    pub(crate) fn value_byte_size(&self, entry: &IfdEntry) -> Result<usize> {
+        let field_type = FieldType::from_u16(entry.field_type)?;
+        usize::try_from(entry.count)
+            .ok()
+            .and_then(|count| count.checked_mul(field_type.byte_size()))
+            .ok_or_else(|| TiffError::InvalidTag {
+                tag: entry.tag,
+                reason: format!("{} {:?} values overflow the value size", entry.count, field_type),
+            })
+    }
+
+    /// Check that an entry's value lies inside the file
+    ///
+    /// Inline values always pass. Out-of-line values must end at or before
+    /// the end of the file.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` for a value past the end of the file, or any
+    /// error from `value_byte_size`
+    pub(crate) fn check_value_bounds(&self, entry: &IfdEntry) -> Result<()> {
+        let size = self.value_byte_size(entry)?;
+        if size <= self.offset_size() {
+            return Ok(());
+        }
+        let end = entry.value_offset.saturating_add(size as u64);
+        if end <= self.len() as u64 {
+            return Ok(());
+        }
+        Err(TiffError::MalformedFile {
+            reason: format!(
+                "{} (tag {}) value ends at byte {end}, past the end of the file ({} bytes)",
+                tags::tag_name(entry.tag), entry.tag, self.len()
+            ),
+        })
+    }
+
     /// Parse the actual value from an IFD entry
     /// 
     /// This is where the magic happens - determining whether the value
     /// is stored inline or at an offset, and parsing it according to
     /// the field type.
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` for a value larger than the whole file, or in
+    /// strict ASCII mode for an ASCII entry with count 0
     pub fn parse_tag_value(&self, entry: &IfdEntry, endian: Endian) -> Result<TagValue> {
         let field_type = FieldType::from_u16(entry.field_type)?;
-        let total_bytes = field_type.byte_size() * entry.count as usize;
-        
-        // If the value fits in 4 bytes, it's stored directly in value_offset
-        // Otherwise, value_offset is a pointer to the actual data
-        if total_bytes <= 4 {
-            // Value is stored in the value_offset field itself
-            let bytes = match endian {
-                Endian::Little => entry.value_offset.to_le_bytes(),
-                Endian::Big => entry.value_offset.to_be_bytes(),
-            };
-            self.parse_value_from_bytes(&bytes[..total_bytes.min(4)], field_type, entry.count, endian)
+        // ASCII values always include a NUL terminator, so count 0 means
+        // the entry is broken; outside strict mode it reads as empty
+        if field_type == FieldType::Ascii && entry.count == 0 {
+            if !self.is_strict_ascii() {
+                return Ok(TagValue::Ascii(String::new()));
+            }
+            return Err(TiffError::InvalidTag {
+                tag: entry.tag,
+                reason: "ASCII value has count 0 (no NUL terminator)".to_string(),
+            });
+        }
+        let count = usize::try_from(entry.count).unwrap_or(usize::MAX);
+        let data = self.read_value_bytes(entry, endian)?;
+        self.parse_value_from_bytes(&data, field_type, count, endian)
+    }
+
+    /// Read the raw bytes of an entry's value, without parsing them
+    ///
+    /// # Errors
+    /// Returns `InvalidFieldType` for an unknown field type, or `InvalidTag`
+    /// for a value larger than the whole file
+    pub(crate) fn read_value_bytes(&self, entry: &IfdEntry, endian: Endian) -> Result<Vec<u8>> {
+        let total_bytes = self.value_byte_size(entry)?;
+        check_read_limit(total_bytes, Self::MAX_TAG_VALUE_BYTES)?;
+        let inline_size = self.offset_size();
+        if total_bytes > inline_size && total_bytes > self.len() {
+            return Err(TiffError::InvalidTag {
+                tag: entry.tag,
+                reason: format!(
+                    "{} {:?} values need {total_bytes} bytes but the file is only {} bytes",
+                    entry.count, FieldType::from_u16(entry.field_type)?, self.len()
+                ),
+            });
+        }
+
+        // If the value fits in the value field (4 bytes, 8 in BigTIFF), it's
+        // stored there directly, left-justified by byte address. Otherwise
+        // value_offset is a pointer to the actual data
+        if total_bytes <= inline_size {
+            let mut bytes = self.inline_value_bytes(entry, endian);
+            bytes.truncate(total_bytes);
+            Ok(bytes)
         } else {
-            // Read data from the offset
-            let data_start = entry.value_offset as usize;
-            let data = self.read_bytes_at(data_start, total_bytes)?;
-            self.parse_value_from_bytes(&data, field_type, entry.count, endian)
+            let data_start = usize::try_from(entry.value_offset).unwrap_or(usize::MAX);
+            self.read_bytes_at(data_start, total_bytes)
         }
     }
 
@@ -635,7 +2020,7 @@ impl<T: TiffDataSource> TiffReader<T> {
         &self, 
         data: &[u8], 
         field_type: FieldType, 
-        count: u32, 
+        count: usize, 
         endian: Endian
     ) -> Result<TagValue> {
         match field_type {
@@ -648,15 +2033,11 @@ impl<T: TiffDataSource> TiffReader<T> {
                 if let Some(&0) = string_data.last() {
                     string_data.pop();
                 }
-                let string = String::from_utf8(string_data)
-                    .map_err(|_| TiffError::InvalidString { 
-                        context: "ASCII tag".to_string() 
-                    })?;
-                Ok(TagValue::Ascii(string))
+                Ok(TagValue::Ascii(self.decode_ascii(string_data, "ASCII tag")?))
             }
             FieldType::Short => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 2 + 2 > data.len() {
                         break;
                     }
@@ -668,7 +2049,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::Long => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 4 + 4 > data.len() {
                         break;
                     }
@@ -680,7 +2061,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::Rational => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 8 + 8 > data.len() {
                         break;
                     }
@@ -701,7 +2082,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::SShort => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 2 + 2 > data.len() {
                         break;
                     }
@@ -713,7 +2094,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::SLong => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 4 + 4 > data.len() {
                         break;
                     }
@@ -725,7 +2106,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::SRational => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 8 + 8 > data.len() {
                         break;
                     }
@@ -739,7 +2120,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::Float => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 4 + 4 > data.len() {
                         break;
                     }
@@ -753,7 +2134,7 @@ impl<T: TiffDataSource> TiffReader<T> {
             }
             FieldType::Double => {
                 let mut values = Vec::new();
-                for i in 0..count as usize {
+                for i in 0..count {
                     if i * 8 + 8 > data.len() {
                         break;
                     }
@@ -768,6 +2149,17 @@ impl<T: TiffDataSource> TiffReader<T> {
                 }
                 Ok(TagValue::Doubles(values))
             }
+            FieldType::Long8 | FieldType::Ifd8 | FieldType::SLong8 => {
+                let values = data
+                    .chunks_exact(8)
+                    .take(count)
+                    .map(|b| endian.read_u64([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]));
+                if field_type == FieldType::SLong8 {
+                    Ok(TagValue::SLongs8(values.map(|value| value as i64).collect()))
+                } else {
+                    Ok(TagValue::Longs8(values.collect()))
+                }
+            }
         }
     }
 }
@@ -775,6 +2167,8 @@ impl<T: TiffDataSource> TiffReader<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::InMemorySource;
+    use crate::testutil::{TestTiff, first_ifd, raw_le_ifd, u32_bytes};
 
     #[test]
     fn test_field_type_conversion() {
@@ -793,6 +2187,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_tag_value_conversions() {
         let shorts = TagValue::Shorts(vec![123, 456]);
         assert_eq!(shorts.as_u32(), Some(123));
@@ -829,6 +2224,101 @@ mod tests {
         assert!((neg_pi + 3.142857).abs() < 0.001);
     }
 
+    #[test]
+    fn test_ascii_string_list() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .ascii(tags::tags::SOFTWARE, "Cyan\0Magenta\0Yellow\0")
+            .ascii(tags::tags::ARTIST, "");
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let value = ifd.get_tag_value(tags::tags::SOFTWARE, &reader, endian).unwrap().unwrap();
+        assert_eq!(value.as_string(), Some("Cyan"));
+        assert_eq!(value.as_string_list().unwrap(), vec!["Cyan", "Magenta", "Yellow"]);
+
+        let empty = ifd.get_tag_value(tags::tags::ARTIST, &reader, endian).unwrap().unwrap();
+        assert_eq!(empty.as_string(), Some(""));
+        assert!(empty.as_string_list().unwrap().is_empty());
+        assert_eq!(TagValue::Ascii("a\0\0b".to_string()).as_string_list().unwrap(), vec!["a", "", "b"]);
+        assert!(TagValue::Shorts(vec![1]).as_string_list().is_none());
+    }
+
+    #[test]
+    fn test_tag_value_len_and_iter_f64() {
+        let byte_counts = TagValue::Shorts(vec![100, 200, 50]);
+        assert_eq!(byte_counts.len(), 3);
+        assert_eq!(byte_counts.iter_f64().sum::<f64>(), 350.0);
+
+        let rationals = TagValue::Rationals(vec![(1, 2), (3, 4), (5, 0)]);
+        assert_eq!(rationals.len(), 3);
+        let values: Vec<f64> = rationals.iter_f64().collect();
+        assert_eq!(values[..2], [0.5, 0.75]);
+        assert!(values[2].is_infinite());
+
+        let doubles = TagValue::Doubles(vec![1.5, 2.5, 5.0]);
+        assert_eq!(doubles.iter_f64().sum::<f64>() / doubles.len() as f64, 3.0);
+
+        let ascii = TagValue::Ascii("abc".to_string());
+        assert_eq!((ascii.len(), ascii.iter_f64().count()), (3, 0));
+        assert!(TagValue::Longs(vec![]).is_empty());
+        assert!(!TagValue::SRationals(vec![(-1, 2)]).is_empty());
+        assert_eq!(TagValue::SRationals(vec![(-1, 2)]).iter_f64().next(), Some(-0.5));
+    }
+
+    #[test]
+    fn test_tag_value_64_bit_conversions() {
+        let big = TagValue::Longs8(vec![5_000_000_000, 1]);
+        assert_eq!(big.as_u64(), Some(5_000_000_000));
+        assert_eq!(big.as_u32(), None);
+        assert_eq!(big.as_u64_vec(), Some(vec![5_000_000_000, 1]));
+        assert_eq!(TagValue::Longs(vec![u32::MAX]).as_u64(), Some(u32::MAX as u64));
+        assert_eq!(TagValue::Shorts(vec![7]).as_u64(), Some(7));
+        assert_eq!(TagValue::Bytes(vec![9]).as_u64(), Some(9));
+        assert_eq!(TagValue::Longs(vec![]).as_u64(), None);
+        assert_eq!(TagValue::SLongs(vec![1]).as_u64(), None);
+        assert_eq!(TagValue::Shorts(vec![1, 2]).as_u64_vec(), Some(vec![1, 2]));
+
+        assert_eq!(TagValue::SLongs8(vec![-5_000_000_000]).as_i64(), Some(-5_000_000_000));
+        assert_eq!(TagValue::SLongs8(vec![-5_000_000_000]).as_i32(), None);
+        assert_eq!(TagValue::SLongs(vec![-7]).as_i64(), Some(-7));
+        assert_eq!(TagValue::SShorts(vec![-8]).as_i64(), Some(-8));
+        assert_eq!(TagValue::SBytes(vec![-9]).as_i64(), Some(-9));
+        assert_eq!(TagValue::Longs(vec![1]).as_i64(), None);
+    }
+
+    #[test]
+    fn test_display_string_numbers() {
+        assert_eq!(TagValue::Shorts(vec![1, 2, 3]).to_display_string(10), "1, 2, 3");
+        assert_eq!(TagValue::Longs(vec![70000]).to_display_string(10), "70000");
+        assert_eq!(TagValue::SShorts(vec![-5, 5]).to_display_string(10), "-5, 5");
+        assert_eq!(TagValue::SLongs(vec![-70000]).to_display_string(10), "-70000");
+        assert_eq!(TagValue::SBytes(vec![-1, 1]).to_display_string(10), "-1, 1");
+        assert_eq!(TagValue::Floats(vec![1.5, 2.0]).to_display_string(10), "1.5, 2");
+        assert_eq!(TagValue::Doubles(vec![0.25]).to_display_string(10), "0.25");
+    }
+
+    #[test]
+    fn test_display_string_rationals() {
+        assert_eq!(TagValue::Rationals(vec![(72, 1), (300, 2)]).to_display_string(10), "72/1, 300/2");
+        assert_eq!(TagValue::SRationals(vec![(-1, 3)]).to_display_string(10), "-1/3");
+    }
+
+    #[test]
+    fn test_display_string_ascii_and_bytes() {
+        assert_eq!(TagValue::Ascii("Hello".to_string()).to_display_string(10), "\"Hello\"");
+        assert_eq!(TagValue::Bytes(vec![0x00, 0xAB, 0x10]).to_display_string(10), "00 ab 10");
+        assert_eq!(TagValue::Undefined(vec![0xFF]).to_display_string(10), "ff");
+    }
+
+    #[test]
+    fn test_display_string_truncation() {
+        let longs = TagValue::Longs((1..=10).collect());
+        assert_eq!(longs.to_display_string(3), "1, 2, 3, ...(7 more)");
+        assert_eq!(longs.to_display_string(0), "...(10 more)");
+        assert_eq!(TagValue::Bytes(vec![1, 2, 3]).to_display_string(2), "01 02 ...(1 more)");
+        assert_eq!(TagValue::Shorts(vec![]).to_display_string(3), "");
+    }
+
     #[test]
     fn test_ifd_entry_creation() {
         let entry = IfdEntry {
@@ -852,6 +2342,7 @@ mod tests {
             compression: Compression::None,
             photometric_interpretation: Some(PhotometricInterpretation::Rgb),
             is_tiled: false,
+            new_subfile_type: 0,
         };
 
         assert_eq!(summary.bits_per_pixel(), 24);
@@ -867,6 +2358,43 @@ mod tests {
         assert!(desc.contains("stripped"));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_shape() {
+        let summary = ImageSummary {
+            width: 64,
+            height: 32,
+            samples_per_pixel: 1,
+            bits_per_sample: vec![16],
+            compression: Compression::Lzw,
+            photometric_interpretation: Some(PhotometricInterpretation::BlackIsZero),
+            is_tiled: true,
+            new_subfile_type: 0,
+        };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "width": 64,
+                "height": 32,
+                "samples_per_pixel": 1,
+                "bits_per_sample": [16],
+                "compression": "Lzw",
+                "photometric_interpretation": "BlackIsZero",
+                "is_tiled": true,
+                "new_subfile_type": 0,
+            })
+        );
+
+        let value = TagValue::Rationals(vec![(72, 1), (300, 2)]);
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "Rationals", "values": [[72, 1], [300, 2]]}));
+        match serde_json::from_value::<TagValue>(json).unwrap() {
+            TagValue::Rationals(v) => assert_eq!(v, vec![(72, 1), (300, 2)]),
+            other => panic!("expected Rationals, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_image_summary_grayscale_alpha() {
         let summary = ImageSummary {
@@ -877,6 +2405,7 @@ mod tests {
             compression: Compression::Lzw,
             photometric_interpretation: Some(PhotometricInterpretation::BlackIsZero),
             is_tiled: true,
+            new_subfile_type: 0,
         };
 
         assert_eq!(summary.bits_per_pixel(), 16);
@@ -900,6 +2429,7 @@ mod tests {
             compression: Compression::PackBits,
             photometric_interpretation: Some(PhotometricInterpretation::Rgb),
             is_tiled: false,
+            new_subfile_type: 0,
         };
 
         assert_eq!(summary.bits_per_pixel(), 32);
@@ -913,6 +2443,1144 @@ mod tests {
         assert!(desc.contains("32-bit"));
     }
 
-    // TODO: Add tests for actual IFD reading once we have test data
-    // This will require creating mock TIFF data with a proper IFD structure
-}
\ No newline at end of file
+    #[test]
+    fn test_enum_accessors_accept_long_values() {
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            tiff.add_ifd()
+                .long(tags::tags::COMPRESSION, &[32773])
+                .long(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+                .long(tags::tags::FILL_ORDER, &[2])
+                .long(tags::tags::PLANAR_CONFIGURATION, &[2])
+                .long(tags::tags::RESOLUTION_UNIT, &[3])
+                .long(tags::tags::SAMPLE_FORMAT, &[3]);
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.compression(&reader, endian).unwrap(), Some(Compression::PackBits));
+            assert_eq!(
+                ifd.photometric_interpretation(&reader, endian).unwrap(),
+                Some(PhotometricInterpretation::Rgb)
+            );
+            assert_eq!(ifd.fill_order(&reader, endian).unwrap(), Some(FillOrder::LsbToMsb));
+            assert_eq!(ifd.planar_configuration(&reader, endian).unwrap(), Some(PlanarConfiguration::Planar));
+            assert_eq!(ifd.resolution_unit(&reader, endian).unwrap(), Some(ResolutionUnit::Centimeter));
+            assert_eq!(ifd.sample_format(&reader, endian).unwrap(), Some(SampleFormat::Float));
+        }
+    }
+
+    #[test]
+    fn test_physical_size_mm() {
+        let file = |unit: Option<u16>, x_res: (u32, u32)| {
+            let mut tiff = TestTiff::new(Endian::Little);
+            let ifd = tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[2480])
+                .short(tags::tags::IMAGE_LENGTH, &[3508])
+                .rational(tags::tags::X_RESOLUTION, &[x_res])
+                .rational(tags::tags::Y_RESOLUTION, &[(300, 1)]);
+            if let Some(unit) = unit {
+                ifd.short(tags::tags::RESOLUTION_UNIT, &[unit]);
+            }
+            first_ifd(tiff.build())
+        };
+
+        // An A4 page scanned at 300 dpi
+        let (reader, ifd, endian) = file(Some(2), (300, 1));
+        let (width, height) = ifd.physical_size_mm(&reader, endian).unwrap().unwrap();
+        assert!((width - 209.97).abs() < 0.01, "{width}");
+        assert!((height - 297.01).abs() < 0.01, "{height}");
+
+        // The same pixel counts at 300 pixels per centimeter
+        let (reader, ifd, endian) = file(Some(3), (300, 1));
+        let (width, _) = ifd.physical_size_mm(&reader, endian).unwrap().unwrap();
+        assert!((width - 82.67).abs() < 0.01, "{width}");
+
+        for (unit, x_res) in [(None, (300, 1)), (Some(1), (300, 1)), (Some(2), (0, 1))] {
+            let (reader, ifd, endian) = file(unit, x_res);
+            assert_eq!(ifd.physical_size_mm(&reader, endian).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_image_summary_is_mask() {
+        let mut summary = ImageSummary {
+            width: 64,
+            height: 64,
+            samples_per_pixel: 1,
+            bits_per_sample: vec![1],
+            compression: Compression::PackBits,
+            photometric_interpretation: Some(PhotometricInterpretation::TransparencyMask),
+            is_tiled: false,
+            new_subfile_type: 0,
+        };
+        assert!(summary.is_mask());
+
+        summary.photometric_interpretation = Some(PhotometricInterpretation::BlackIsZero);
+        assert!(!summary.is_mask());
+        // Reduced-resolution page, not a mask
+        summary.new_subfile_type = 1 | 2;
+        assert!(!summary.is_mask());
+        summary.new_subfile_type = 4;
+        assert!(summary.is_mask());
+    }
+
+    #[test]
+    fn test_image_summary_reads_new_subfile_type() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .long(tags::tags::NEW_SUBFILE_TYPE, &[4])
+            .short(tags::tags::IMAGE_WIDTH, &[8])
+            .short(tags::tags::IMAGE_LENGTH, &[8])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[0]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.new_subfile_type(&reader, endian).unwrap(), Some(4));
+        assert!(ifd.image_summary(&reader, endian).unwrap().is_mask());
+    }
+
+    #[test]
+    fn test_row_and_plane_stride() {
+        let mut summary = ImageSummary {
+            width: 100,
+            height: 1,
+            samples_per_pixel: 1,
+            bits_per_sample: vec![1],
+            compression: Compression::None,
+            photometric_interpretation: Some(PhotometricInterpretation::BlackIsZero),
+            is_tiled: false,
+            new_subfile_type: 0,
+        };
+        assert_eq!(summary.row_stride_bytes(), 13);
+        assert_eq!(summary.plane_stride_bytes(0), Some(13));
+        assert_eq!(summary.plane_stride_bytes(1), None);
+
+        summary.width = 3;
+        summary.bits_per_sample = vec![4];
+        assert_eq!(summary.row_stride_bytes(), 2);
+
+        summary.width = 5;
+        summary.samples_per_pixel = 3;
+        summary.bits_per_sample = vec![8, 8, 8];
+        assert_eq!(summary.row_stride_bytes(), 15);
+        assert_eq!(summary.plane_stride_bytes(2), Some(5));
+
+        summary.bits_per_sample = vec![5, 6, 5];
+        assert_eq!(summary.row_stride_bytes(), 10);
+        assert_eq!(summary.plane_stride_bytes(1), Some(4));
+    }
+
+    #[test]
+    fn test_single_strip_inline_long_offset() {
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            let strip = tiff.push_data(&[7; 6]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[3])
+                .short(tags::tags::IMAGE_LENGTH, &[2])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[6]);
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.strip_offsets(&reader, endian).unwrap(), Some(vec![strip as u64]));
+            assert_eq!(ifd.strip_byte_counts(&reader, endian).unwrap(), Some(vec![6]));
+            assert_eq!(ifd.image_data_ranges(&reader, endian).unwrap(), vec![(strip as usize, 6)]);
+        }
+    }
+
+    #[test]
+    fn test_strip_for_row_and_rows_in_strip() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[4])
+            .short(tags::tags::IMAGE_LENGTH, &[10])
+            .short(tags::tags::ROWS_PER_STRIP, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let strips: Vec<_> = (0..11).map(|row| ifd.strip_for_row(row, &reader, endian).unwrap()).collect();
+        assert_eq!(strips[..10], [0, 0, 0, 0, 1, 1, 1, 1, 2, 2].map(Some));
+        assert_eq!(strips[10], None);
+        assert_eq!(ifd.rows_in_strip(0, &reader, endian).unwrap(), Some((0, 4)));
+        // The last strip is clamped to the image height
+        assert_eq!(ifd.rows_in_strip(2, &reader, endian).unwrap(), Some((8, 2)));
+        assert_eq!(ifd.rows_in_strip(3, &reader, endian).unwrap(), None);
+        assert_eq!(ifd.tile_for_pixel(0, 0, &reader, endian).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rows_per_strip_defaults_to_one_strip() {
+        for rows_per_strip in [None, Some(u32::MAX), Some(0)] {
+            let mut tiff = TestTiff::new(Endian::Little);
+            let strip = tiff.push_data(&[0; 15]);
+            let ifd = tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[3])
+                .short(tags::tags::IMAGE_LENGTH, &[5])
+                .short(tags::tags::BITS_PER_SAMPLE, &[8])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[15]);
+            if let Some(rows) = rows_per_strip {
+                ifd.long(tags::tags::ROWS_PER_STRIP, &[rows]);
+            }
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.rows_per_strip_or_default(&reader, endian).unwrap(), 5);
+            assert_eq!(ifd.strips_per_image(&reader, endian).unwrap(), Some(1));
+            assert_eq!(ifd.rows_in_strip(0, &reader, endian).unwrap(), Some((0, 5)));
+            assert_eq!(ifd.strip_for_row(4, &reader, endian).unwrap(), Some(0));
+            let image = crate::reader::TiffImageReader::new(&reader, &ifd, endian).unwrap();
+            assert_eq!(image.strips_per_image(), 1);
+            assert_eq!(image.read_strip(0).unwrap().len(), 15);
+        }
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[3]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(
+            ifd.rows_per_strip_or_default(&reader, endian),
+            Err(TiffError::InvalidTag { tag: tags::tags::IMAGE_LENGTH, .. })
+        ));
+        assert_eq!(ifd.strips_per_image(&reader, endian).unwrap(), None);
+    }
+
+    #[test]
+    fn test_tile_for_pixel() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[40])
+            .short(tags::tags::IMAGE_LENGTH, &[20])
+            .short(tags::tags::TILE_WIDTH, &[16])
+            .short(tags::tags::TILE_LENGTH, &[16]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        // 3 tiles across, 2 down
+        assert_eq!(ifd.tile_for_pixel(0, 0, &reader, endian).unwrap(), Some(0));
+        assert_eq!(ifd.tile_for_pixel(39, 0, &reader, endian).unwrap(), Some(2));
+        assert_eq!(ifd.tile_for_pixel(16, 16, &reader, endian).unwrap(), Some(4));
+        assert_eq!(ifd.tile_for_pixel(40, 0, &reader, endian).unwrap(), None);
+        assert_eq!(ifd.tile_for_pixel(0, 20, &reader, endian).unwrap(), None);
+        assert_eq!(ifd.strip_for_row(0, &reader, endian).unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_offsets() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 6]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .ascii(tags::tags::SOFTWARE, "tiff-core test suite")
+            .raw(40000, 99, 100, vec![0; 4])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[6]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(ifd.validate_offsets(&reader, endian).is_ok());
+
+        let reason = |ifd: &ImageFileDirectory| match ifd.validate_offsets(&reader, endian) {
+            Err(TiffError::MalformedFile { reason }) => reason,
+            other => panic!("expected MalformedFile, got {other:?}"),
+        };
+        let mut bad = ifd.clone();
+        bad.entries.iter_mut().find(|e| e.tag == tags::tags::SOFTWARE).unwrap().value_offset = reader.len() as u64 - 4;
+        assert!(reason(&bad).starts_with("Software (tag 305) value ends at byte"));
+
+        let mut bad = ifd.clone();
+        bad.entries.iter_mut().find(|e| e.tag == tags::tags::STRIP_BYTE_COUNTS).unwrap().value_offset = 100_000;
+        assert!(reason(&bad).starts_with(&format!("strip 0 at offset {strip} (100000 bytes)")));
+
+        // LONG8 locations whose sum overflows
+        let mut tiff = TestTiff::new(Endian::Little);
+        let huge = tiff.push_data(&u64::MAX.to_le_bytes());
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[0])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[0]);
+        let (reader, mut ifd, endian) = first_ifd(tiff.build());
+        for entry in ifd.entries.iter_mut().filter(|e| tags::is_data_location_tag(e.tag)) {
+            entry.field_type = FieldType::Long8 as u16;
+            entry.value_offset = huge as u64;
+        }
+        match ifd.validate_offsets(&reader, endian) {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.ends_with("past the addressable range")),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_single_strip_inline_short_offset() {
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            let strip = tiff.push_data(&[7; 6]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[3])
+                .short(tags::tags::IMAGE_LENGTH, &[2])
+                .short(tags::tags::STRIP_OFFSETS, &[strip as u16])
+                .short(tags::tags::STRIP_BYTE_COUNTS, &[6]);
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.strip_offsets(&reader, endian).unwrap(), Some(vec![strip as u64]));
+            assert_eq!(ifd.strip_byte_counts(&reader, endian).unwrap(), Some(vec![6]));
+        }
+    }
+
+    #[test]
+    fn test_single_tile_inline_offsets() {
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            let tile = tiff.push_data(&[0; 256]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[16])
+                .short(tags::tags::IMAGE_LENGTH, &[16])
+                .short(tags::tags::TILE_WIDTH, &[16])
+                .short(tags::tags::TILE_LENGTH, &[16])
+                .long(tags::tags::TILE_OFFSETS, &[tile])
+                .short(tags::tags::TILE_BYTE_COUNTS, &[256]);
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.tile_offsets(&reader, endian).unwrap(), Some(vec![tile as u64]));
+            assert_eq!(ifd.tile_byte_counts(&reader, endian).unwrap(), Some(vec![256]));
+        }
+    }
+
+    #[test]
+    fn test_short_dimensions_above_i16_max() {
+        let shorts = TagValue::Shorts(vec![50000]);
+        assert_eq!(shorts.as_u16(), Some(50000));
+        assert_eq!(shorts.as_u32(), Some(50000));
+
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[50000])
+                .short(tags::tags::IMAGE_LENGTH, &[65535]);
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+
+            assert_eq!(ifd.image_width(&reader, endian).unwrap(), Some(50000));
+            assert_eq!(ifd.image_height(&reader, endian).unwrap(), Some(65535));
+            let summary = ifd.image_summary(&reader, endian).unwrap();
+            assert_eq!((summary.width, summary.height), (50000, 65535));
+        }
+    }
+
+    #[test]
+    fn test_read_ifd_entry_count_and_next_offset() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[1])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .ascii(tags::tags::SOFTWARE, "a long software name");
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let mut reader = TiffReader::new(InMemorySource::new(tiff.build()));
+        let header = reader.read_header().unwrap();
+        let first = header.ifd_offset as usize;
+
+        assert_eq!(reader.read_ifd_entry_count(first, Endian::Big).unwrap(), 3);
+        let second = reader.read_ifd_next_offset(first, Endian::Big).unwrap();
+        assert_eq!(second, reader.read_ifd(first, Endian::Big).unwrap().next_ifd_offset);
+        assert_eq!(reader.read_ifd_entry_count(second, Endian::Big).unwrap(), 1);
+        assert_eq!(reader.read_ifd_next_offset(second, Endian::Big).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_ifd_entry_count_rejects_bad_offsets() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let reader = TiffReader::new(InMemorySource::new(tiff.build()));
+
+        assert!(matches!(
+            reader.read_ifd_entry_count(4, Endian::Little),
+            Err(TiffError::MalformedFile { .. })
+        ));
+        assert!(matches!(
+            reader.read_ifd_entry_count(10_000, Endian::Little),
+            Err(TiffError::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_ifd_filtered() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[640])
+            .short(tags::tags::IMAGE_LENGTH, &[480])
+            .ascii(tags::tags::DATE_TIME, "2024:01:01 00:00:00")
+            .raw(37500, 7, 64, vec![0xAA; 64]);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let mut reader = TiffReader::new(InMemorySource::new(tiff.build()));
+        let header = reader.read_header().unwrap();
+        let offset = header.ifd_offset as usize;
+
+        let wanted = [tags::tags::IMAGE_WIDTH, tags::tags::IMAGE_LENGTH, tags::tags::DATE_TIME];
+        let ifd = reader.read_ifd_filtered(offset, Endian::Little, &wanted).unwrap();
+        let full = reader.read_ifd(offset, Endian::Little).unwrap();
+
+        assert_eq!(ifd.len(), 3);
+        assert!(ifd.find_entry(37500).is_none());
+        assert_eq!(ifd.next_ifd_offset, full.next_ifd_offset);
+        assert_eq!(ifd.image_width(&reader, Endian::Little).unwrap(), Some(640));
+        assert_eq!(
+            ifd.date_time(&reader, Endian::Little).unwrap().as_deref(),
+            Some("2024:01:01 00:00:00")
+        );
+    }
+
+    #[test]
+    fn test_inline_values_all_small_types() {
+        for endian in [Endian::Little, Endian::Big] {
+            let mut tiff = TestTiff::new(endian);
+            let u16b = |v: u16| crate::testutil::u16_bytes(endian, v).to_vec();
+            let u32b = |v: u32| crate::testutil::u32_bytes(endian, v).to_vec();
+            tiff.add_ifd()
+                .bytes(1000, &[0xAB])
+                .bytes(1001, &[1, 2, 3])
+                .bytes(1002, &[1, 2, 3, 4])
+                .raw(1003, 2, 2, b"A\0".to_vec())
+                .raw(1004, 2, 4, b"abc\0".to_vec())
+                .short(1005, &[0xBEEF])
+                .short(1006, &[7, 0xFFFF])
+                .raw(1007, 6, 2, vec![0xFF, 0x05])
+                .raw(1008, 7, 3, vec![9, 8, 7])
+                .raw(1009, 8, 2, [u16b(-2i16 as u16), u16b(300)].concat())
+                .long(1010, &[0xDEADBEEF])
+                .raw(1011, 9, 1, u32b(-123456i32 as u32))
+                .raw(1012, 11, 1, u32b(1.5f32.to_bits()));
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+            let value = |tag| ifd.get_tag_value(tag, &reader, endian).unwrap().unwrap();
+
+            assert!(matches!(value(1000), TagValue::Bytes(v) if v == vec![0xAB]));
+            assert!(matches!(value(1001), TagValue::Bytes(v) if v == vec![1, 2, 3]));
+            assert!(matches!(value(1002), TagValue::Bytes(v) if v == vec![1, 2, 3, 4]));
+            assert_eq!(value(1003).as_string(), Some("A"));
+            assert_eq!(value(1004).as_string(), Some("abc"));
+            assert!(matches!(value(1005), TagValue::Shorts(v) if v == vec![0xBEEF]));
+            assert!(matches!(value(1006), TagValue::Shorts(v) if v == vec![7, 0xFFFF]));
+            assert!(matches!(value(1007), TagValue::SBytes(v) if v == vec![-1, 5]));
+            assert!(matches!(value(1008), TagValue::Undefined(v) if v == vec![9, 8, 7]));
+            assert!(matches!(value(1009), TagValue::SShorts(v) if v == vec![-2, 300]));
+            assert!(matches!(value(1010), TagValue::Longs(v) if v == vec![0xDEADBEEF]));
+            assert!(matches!(value(1011), TagValue::SLongs(v) if v == vec![-123456]));
+            assert!(matches!(value(1012), TagValue::Floats(v) if v == vec![1.5]));
+        }
+    }
+
+    #[test]
+    fn test_inline_single_byte_big_endian_is_first_byte() {
+        // In an MM file, a single inline BYTE lives in the first byte of the
+        // value field, i.e. the most significant byte of value_offset
+        let entry = IfdEntry { tag: 1000, field_type: 1, count: 1, value_offset: 0xAB00_0000 };
+        let reader = TiffReader::new(InMemorySource::new(vec![0; 8]));
+        let value = reader.parse_tag_value(&entry, Endian::Big).unwrap();
+        assert!(matches!(value, TagValue::Bytes(v) if v == vec![0xAB]));
+
+        // In an II file the same byte address is the least significant byte
+        let entry = IfdEntry { tag: 1000, field_type: 1, count: 1, value_offset: 0x0000_00AB };
+        let value = reader.parse_tag_value(&entry, Endian::Little).unwrap();
+        assert!(matches!(value, TagValue::Bytes(v) if v == vec![0xAB]));
+    }
+
+    #[test]
+    fn test_read_all_strips_raw() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        let first = tiff.push_data(&[1, 2, 3]);
+        let second = tiff.push_data(&[4, 5]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[4])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[first, second])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[3, 2]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_all_strips_raw(&reader, endian).unwrap(), vec![vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(ifd.read_all_image_data_raw(&reader, endian).unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_read_all_strips_raw_tiles_and_errors() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let tile = tiff.push_data(&[9; 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &[tile])
+            .long(tags::tags::TILE_BYTE_COUNTS, &[4])
+            // Strip tags are ignored for tiled images
+            .long(tags::tags::STRIP_OFFSETS, &[100_000])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_all_strips_raw(&reader, endian).unwrap(), vec![vec![9; 4]]);
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[100_000])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(ifd.read_all_strips_raw(&reader, endian), Err(TiffError::OutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_read_strip_decodes_short_last_strip() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        // Two PackBits strips of a 3x5 image: 4 rows, then the 1-row remainder
+        let first = tiff.push_data(&[0xF6, 1, 0x00, 2]);
+        let last = tiff.push_data(&[0xFE, 3]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[5])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::COMPRESSION, &[32773])
+            .short(tags::tags::ROWS_PER_STRIP, &[4])
+            .long(tags::tags::STRIP_OFFSETS, &[first, last])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4, 2]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        let mut expected = vec![1; 11];
+        expected.push(2);
+        assert_eq!(ifd.read_strip(&reader, endian, 0).unwrap(), expected);
+        assert_eq!(ifd.read_strip(&reader, endian, 1).unwrap(), vec![3; 3]);
+        assert!(matches!(ifd.read_strip(&reader, endian, 2), Err(TiffError::OutOfBounds { index: 2, max: 2 })));
+    }
+
+    #[test]
+    fn test_read_strip_rejects_tiled_image() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let tile = tiff.push_data(&[9; 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &[tile])
+            .long(tags::tags::TILE_BYTE_COUNTS, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(ifd.read_strip(&reader, endian, 0), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_read_tile_returns_padded_edge_tiles() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        // 3x3 image of 2x2 tiles: a 2x2 grid whose right and bottom tiles are padded
+        let offsets: Vec<u32> = (0..4u8).map(|i| tiff.push_data(&[i * 10, i * 10 + 1, i * 10 + 2, i * 10 + 3])).collect();
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &offsets)
+            .long(tags::tags::TILE_BYTE_COUNTS, &[4; 4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_tile(&reader, endian, 0, 0).unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(ifd.read_tile(&reader, endian, 1, 0).unwrap(), vec![10, 11, 12, 13]);
+        assert_eq!(ifd.read_tile(&reader, endian, 1, 1).unwrap(), vec![30, 31, 32, 33]);
+        assert!(matches!(ifd.read_tile(&reader, endian, 2, 0), Err(TiffError::OutOfBounds { .. })));
+        assert!(matches!(ifd.read_tile(&reader, endian, 0, 2), Err(TiffError::OutOfBounds { .. })));
+
+        let mut tiff = TestTiff::new(Endian::Big);
+        let strip = tiff.push_data(&[0; 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(ifd.read_tile(&reader, endian, 0, 0), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    /// Expected pixels of the 3x3 RGB test image: pixel (x, y) is [x, y, x + 3y]
+    fn rgb_pixels() -> Vec<u8> {
+        (0..3u8).flat_map(|y| (0..3u8).flat_map(move |x| [x, y, x + 3 * y])).collect()
+    }
+
+    #[test]
+    fn test_read_image_stripped_rgb() {
+        let pixels = rgb_pixels();
+        let mut tiff = TestTiff::new(Endian::Little);
+        let first = tiff.push_data(&pixels[..18]);
+        let last = tiff.push_data(&pixels[18..]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .short(tags::tags::ROWS_PER_STRIP, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[first, last])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[18, 9]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_image(&reader, endian).unwrap(), pixels);
+    }
+
+    #[test]
+    fn test_read_image_crops_tiles() {
+        let pixels = rgb_pixels();
+        let pixel = |x: usize, y: usize| -> [u8; 3] {
+            if x < 3 && y < 3 { pixels[(y * 3 + x) * 3..][..3].try_into().unwrap() } else { [0xEE; 3] }
+        };
+        let mut tiff = TestTiff::new(Endian::Little);
+        let mut offsets = Vec::new();
+        for tile_y in 0..2 {
+            for tile_x in 0..2 {
+                let tile: Vec<u8> = (0..2)
+                    .flat_map(|row| (0..2).flat_map(move |col| pixel(tile_x * 2 + col, tile_y * 2 + row)))
+                    .collect();
+                offsets.push(tiff.push_data(&tile));
+            }
+        }
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &offsets)
+            .long(tags::tags::TILE_BYTE_COUNTS, &[12; 4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_image(&reader, endian).unwrap(), pixels);
+    }
+
+    #[test]
+    fn test_read_image_interleaves_planes() {
+        let pixels = rgb_pixels();
+        let plane = |sample: usize| -> Vec<u8> { pixels.iter().skip(sample).step_by(3).copied().collect() };
+
+        // Stripped: three planes of two strips each (2 rows, then 1)
+        let mut tiff = TestTiff::new(Endian::Big);
+        let mut offsets = Vec::new();
+        for sample in 0..3 {
+            let data = plane(sample);
+            offsets.push(tiff.push_data(&data[..6]));
+            offsets.push(tiff.push_data(&data[6..]));
+        }
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .short(tags::tags::PLANAR_CONFIGURATION, &[2])
+            .short(tags::tags::ROWS_PER_STRIP, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &offsets)
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[6, 3, 6, 3, 6, 3]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.read_image(&reader, endian).unwrap(), pixels);
+
+        // Tiled: one padded 4x4 tile per plane
+        let mut tiff = TestTiff::new(Endian::Big);
+        let offsets: Vec<u32> = (0..3)
+            .map(|sample| {
+                let data = plane(sample);
+                let mut tile = vec![0xEE; 16];
+                for y in 0..3 {
+                    tile[y * 4..y * 4 + 3].copy_from_slice(&data[y * 3..y * 3 + 3]);
+                }
+                tiff.push_data(&tile)
+            })
+            .collect();
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PLANAR_CONFIGURATION, &[2])
+            .short(tags::tags::TILE_WIDTH, &[4])
+            .short(tags::tags::TILE_LENGTH, &[4])
+            .long(tags::tags::TILE_OFFSETS, &offsets)
+            .long(tags::tags::TILE_BYTE_COUNTS, &[16; 3]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.planar_configuration(&reader, endian).unwrap(), Some(PlanarConfiguration::Planar));
+        assert_eq!(ifd.read_image(&reader, endian).unwrap(), pixels);
+    }
+
+    #[test]
+    fn test_read_image_unsupported_compression() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::COMPRESSION, &[3])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(ifd.read_image(&reader, endian), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_ascii_count_zero_is_invalid_only_when_strict() {
+        let entry = IfdEntry { tag: tags::tags::SOFTWARE, field_type: 2, count: 0, value_offset: 0 };
+        let reader = TiffReader::new(InMemorySource::new(vec![0; 8]));
+        assert_eq!(reader.parse_tag_value(&entry, Endian::Little).unwrap().as_string(), Some(""));
+
+        let reader = reader.with_strict_ascii(true);
+        match reader.parse_tag_value(&entry, Endian::Little) {
+            Err(TiffError::InvalidTag { tag, reason }) => {
+                assert_eq!(tag, tags::tags::SOFTWARE);
+                assert!(reason.contains("count 0"));
+            }
+            other => panic!("Expected InvalidTag error, got {other:?}"),
+        }
+
+        // A lone terminator is a valid empty string
+        let entry = IfdEntry { count: 1, ..entry };
+        assert_eq!(reader.parse_tag_value(&entry, Endian::Little).unwrap().as_string(), Some(""));
+    }
+
+    #[test]
+    fn test_undefined_blob_size_checked_against_file() {
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(b"blob of bytes");
+        let reader = TiffReader::new(InMemorySource::new(data));
+
+        let entry = IfdEntry { tag: 37500, field_type: 7, count: 13, value_offset: 8 };
+        let value = reader.parse_tag_value(&entry, Endian::Little).unwrap();
+        assert_eq!(value.as_bytes(), Some(&b"blob of bytes"[..]));
+
+        // A count bigger than the whole file can't be real
+        let entry = IfdEntry { count: 1_000_000, ..entry };
+        assert!(matches!(
+            reader.parse_tag_value(&entry, Endian::Little),
+            Err(TiffError::InvalidTag { tag: 37500, .. })
+        ));
+    }
+
+    #[test]
+    fn test_entry_descriptors_sorted_and_skip_unknown_types() {
+        let entry = |tag, field_type, count| IfdEntry { tag, field_type, count, value_offset: 0 };
+        let ifd = ImageFileDirectory::new(
+            vec![entry(258, 3, 3), entry(256, 4, 1), entry(40000, 99, 2), entry(257, 3, 1)],
+            0,
+        );
+        assert_eq!(
+            ifd.entry_descriptors(),
+            vec![
+                (256, FieldType::Long, 1),
+                (257, FieldType::Short, 1),
+                (258, FieldType::Short, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bits_per_sample_inline_and_offset() {
+        for endian in [Endian::Little, Endian::Big] {
+            // 1 SHORT (2 bytes) inline, 2 SHORTs (4 bytes) filling the slot,
+            // 3 SHORTs (6 bytes) stored at an offset
+            for bits in [vec![8u16], vec![16, 8], vec![8, 8, 8], vec![16, 16, 16, 16]] {
+                let mut tiff = TestTiff::new(endian);
+                tiff.add_ifd()
+                    .short(tags::tags::BITS_PER_SAMPLE, &bits)
+                    .short(tags::tags::SAMPLES_PER_PIXEL, &[bits.len() as u16]);
+                let (reader, ifd, endian) = first_ifd(tiff.build());
+
+                let entry = ifd.find_entry(tags::tags::BITS_PER_SAMPLE).unwrap();
+                assert_eq!(entry.count as usize, bits.len());
+                let expected: Vec<u32> = bits.iter().map(|&b| b as u32).collect();
+                assert_eq!(ifd.bits_per_sample(&reader, endian).unwrap(), Some(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_icc_profile() {
+        let profile: Vec<u8> = b"\0\0\x02\x0cappl\x02\x10\0\0mntrRGB XYZ ".to_vec();
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[1])
+            .raw(tags::tags::ICC_PROFILE, 7, profile.len() as u32, profile.clone());
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        let entry = ifd.find_entry(tags::tags::ICC_PROFILE).unwrap();
+        assert!(entry.value_offset > 8, "profile should be stored at an offset");
+        assert_eq!(ifd.icc_profile(&reader, endian).unwrap(), Some(profile));
+
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.icc_profile(&reader, endian).unwrap(), None);
+    }
+
+    #[test]
+    fn test_xmp_byte_and_undefined() {
+        let packet = b"<?xpacket begin=\"\"?><x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>";
+        for field_type in [1, 7] {
+            let mut tiff = TestTiff::new(Endian::Little);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[1])
+                .raw(tags::tags::XMP, field_type, packet.len() as u32, packet.to_vec());
+            let (reader, ifd, endian) = first_ifd(tiff.build());
+            assert_eq!(ifd.xmp(&reader, endian).unwrap().as_deref(), Some(&packet[..]));
+            let text = ifd.xmp_string(&reader, endian).unwrap().unwrap();
+            assert!(text.starts_with("<?xpacket"));
+        }
+    }
+
+    #[test]
+    fn test_xmp_string_is_lossy() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().bytes(tags::tags::XMP, &[b'<', 0xFF, b'x', b'>', b' ']);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.xmp_string(&reader, endian).unwrap().as_deref(), Some("<\u{FFFD}x> "));
+    }
+
+    #[test]
+    fn test_iptc_undefined_and_long() {
+        // Record 2, dataset 5 (ObjectName), length 4, "Test"
+        let record = [0x1C, 0x02, 0x05, 0x00, 0x04, b'T', b'e', b's', b't', 0, 0, 0];
+        for endian in [Endian::Little, Endian::Big] {
+            for field_type in [7, 4] {
+                let count = if field_type == 4 { record.len() as u32 / 4 } else { record.len() as u32 };
+                let mut tiff = TestTiff::new(endian);
+                tiff.add_ifd()
+                    .short(tags::tags::IMAGE_WIDTH, &[1])
+                    .raw(tags::tags::IPTC, field_type, count, record.to_vec());
+                let (reader, ifd, endian) = first_ifd(tiff.build());
+                assert_eq!(ifd.iptc(&reader, endian).unwrap(), Some(record.to_vec()));
+            }
+        }
+        assert_eq!(tags::tag_name(tags::tags::IPTC), "IPTC-NAA");
+    }
+
+    #[test]
+    fn test_string_metadata() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[1])
+            .ascii(tags::tags::MAKE, "Acme")
+            .ascii(tags::tags::SOFTWARE, "tiff-core test suite")
+            .ascii(tags::tags::DATE_TIME, "2024:01:02 03:04:05")
+            .ascii(40000, "private tag")
+            .raw(tags::tags::ARTIST, 2, 4, vec![0xFF, 0xFE, b'x', 0]);
+        let data = tiff.build();
+        let (reader, ifd, endian) = first_ifd(data.clone());
+
+        let metadata = ifd.string_metadata(&reader, endian).unwrap();
+        assert_eq!(metadata.len(), 4);
+        assert_eq!(metadata["Make"], "Acme");
+        assert_eq!(metadata["Software"], "tiff-core test suite");
+        assert_eq!(metadata["DateTime"], "2024:01:02 03:04:05");
+        assert_eq!(metadata["Artist"], "\u{FF}\u{FE}x");
+
+        // A strict reader skips the non-UTF-8 entry
+        let mut reader = TiffReader::new(InMemorySource::new(data)).with_strict_ascii(true);
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, endian).unwrap();
+        let metadata = ifd.string_metadata(&reader, endian).unwrap();
+        assert_eq!(metadata.len(), 3);
+        assert!(!metadata.contains_key("Artist"));
+    }
+
+    #[test]
+    fn test_ascii_latin1_fallback() {
+        let mut tiff = TestTiff::new(Endian::Big);
+        tiff.add_ifd().raw(tags::tags::ARTIST, 2, 5, vec![b'R', b'e', b'n', 0xE9, 0]);
+        let data = tiff.build();
+        let (reader, ifd, endian) = first_ifd(data.clone());
+        assert_eq!(ifd.artist(&reader, endian).unwrap().as_deref(), Some("René"));
+
+        let mut reader = TiffReader::new(InMemorySource::new(data)).with_strict_ascii(true);
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, endian).unwrap();
+        assert!(matches!(ifd.artist(&reader, endian), Err(TiffError::InvalidString { .. })));
+
+        // Valid UTF-8 is never reinterpreted
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().ascii(tags::tags::ARTIST, "René");
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.artist(&reader, endian).unwrap().as_deref(), Some("René"));
+    }
+
+    #[test]
+    fn test_tag_values_are_cached() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[640]).ascii(tags::tags::SOFTWARE, "cached value");
+        let data = tiff.build();
+        let blank = TiffReader::new(InMemorySource::new(vec![0; data.len()]));
+        let (reader, ifd, endian) = first_ifd(data);
+
+        assert_eq!(ifd.software(&reader, endian).unwrap().as_deref(), Some("cached value"));
+        // A hit hands back the cached value itself rather than re-parsing it
+        let first = ifd.cached_tag_value(tags::tags::SOFTWARE, &reader, endian).unwrap().unwrap();
+        let again = ifd.cached_tag_value(tags::tags::SOFTWARE, &reader, endian).unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(format!("{:?}", ifd.cache), "TagCache { cached: 1 }");
+
+        // Other readers, or the same bytes read strictly, get their own values
+        assert_ne!(ifd.software(&blank, endian).unwrap().as_deref(), Some("cached value"));
+        let strict = TiffReader::new(InMemorySource::new(reader.read_bytes_at(0, reader.len()).unwrap()))
+            .with_strict_ascii(true);
+        assert_eq!(ifd.software(&strict, endian).unwrap().as_deref(), Some("cached value"));
+        assert_eq!(format!("{:?}", ifd.cache), "TagCache { cached: 3 }");
+
+        let clone = ifd.clone();
+        assert_eq!(format!("{:?}", clone.cache), "TagCache { cached: 0 }");
+        assert_eq!(clone.image_width(&blank, endian).unwrap(), Some(640));
+    }
+
+    #[test]
+    fn test_edited_entry_is_not_served_from_cache() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[300]);
+        let (reader, mut ifd, endian) = first_ifd(tiff.build());
+
+        assert_eq!(ifd.image_width(&reader, endian).unwrap(), Some(300));
+        ifd.entries[0].value_offset = 7;
+        assert_eq!(ifd.image_width(&reader, endian).unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_find_tag() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[640]).long(40000, &[7]);
+        let (_, ifd, _) = first_ifd(tiff.build());
+
+        assert_eq!(ifd.find_tag(Tag::ImageWidth).map(|entry| entry.tag), Some(256));
+        assert_eq!(ifd.find_tag(Tag::Unknown(40000)).map(|entry| entry.tag), Some(40000));
+        assert!(ifd.find_tag(Tag::ImageLength).is_none());
+    }
+
+    #[test]
+    fn test_iter_named_and_dump() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[640])
+            .ascii(tags::tags::MAKE, "Acme")
+            .rational(tags::tags::X_RESOLUTION, &[(300, 1)])
+            .long(40000, &[1, 2]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let names: Vec<&str> = ifd.iter_named().map(|(_, name)| name).collect();
+        assert_eq!(names, ["ImageWidth", "Make", "XResolution", "Unknown"]);
+        assert!(ifd.iter_named().all(|(entry, name)| tags::tag_name(entry.tag) == name));
+
+        let dump = ifd.dump(&reader, endian);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "ImageWidth (#256): 640",
+                "Make (#271): \"Acme\"",
+                "XResolution (#282): 300/1",
+                "Unknown (#40000): 1, 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dump_keeps_going_after_bad_entry() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[640])
+            .raw(tags::tags::MAKE, 2, 100, u32_bytes(Endian::Little, 0x7fff_0000).to_vec())
+            .short(tags::tags::ORIENTATION, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let dump = ifd.dump(&reader, endian);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "ImageWidth (#256): 640");
+        assert!(lines[1].starts_with("Make (#271): <error: "));
+        assert_eq!(lines[2], "Orientation (#274): 1");
+    }
+
+    #[test]
+    fn test_oversized_tag_value_is_rejected() {
+        let entry = IfdEntry { tag: 1000, field_type: 12, count: u32::MAX as u64, value_offset: 8 };
+        let reader = TiffReader::new(InMemorySource::new(vec![0; 16]));
+        assert!(matches!(reader.parse_tag_value(&entry, Endian::Little), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_huge_counts_are_errors_not_panics() {
+        let reader = TiffReader::new(InMemorySource::new(vec![0; 16]));
+        for field_type in 1..=13 {
+            for value_offset in [0, 8, u32::MAX as u64] {
+                let entry = IfdEntry { tag: 1000, field_type, count: u32::MAX as u64, value_offset };
+                assert!(reader.parse_tag_value(&entry, Endian::Little).is_err());
+            }
+        }
+
+        let entry = IfdEntry { tag: 1000, field_type: 12, count: u64::MAX, value_offset: 8 };
+        match reader.parse_tag_value(&entry, Endian::Little) {
+            Err(TiffError::InvalidTag { tag: 1000, reason }) => assert!(reason.contains("overflow"), "{reason}"),
+            other => panic!("expected InvalidTag, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_exif_ifd() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let exposure = tiff.push_data(&[1, 0, 0, 0, 250, 0, 0, 0]);
+        let f_number = tiff.push_data(&[28, 0, 0, 0, 10, 0, 0, 0]);
+        let exif = tiff.push_data(&raw_le_ifd(&[
+            (tags::tags::EXPOSURE_TIME, 5, 1, exposure),
+            (tags::tags::F_NUMBER, 5, 1, f_number),
+            (tags::tags::ISO_SPEED_RATINGS, 3, 1, 400),
+        ]));
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]).long(tags::tags::EXIF_IFD, &[exif]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let exif = ifd.exif_ifd(&reader, endian).unwrap().unwrap();
+        let value = |tag| exif.get_tag_value(tag, &reader, endian).unwrap().unwrap();
+        assert_eq!(value(tags::tags::EXPOSURE_TIME).as_rational_f64(), Some(0.004));
+        assert_eq!(value(tags::tags::F_NUMBER).as_rational_f64(), Some(2.8));
+        assert_eq!(value(tags::tags::ISO_SPEED_RATINGS).as_u16(), Some(400));
+        assert_eq!(tags::tag_name(tags::tags::FOCAL_LENGTH), "FocalLength");
+    }
+
+    #[test]
+    fn test_exif_ifd_missing_or_out_of_bounds() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(ifd.exif_ifd(&reader, endian).unwrap().is_none());
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]).long(tags::tags::EXIF_IFD, &[0xFFFF]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(
+            ifd.exif_ifd(&reader, endian),
+            Err(TiffError::OutOfBounds { index: 0xFFFF, .. })
+        ));
+    }
+
+    /// A file whose GPS IFD holds the given latitude and longitude
+    fn gps_file(latitude: [(u32, u32); 3], lat_ref: &[u8; 2], longitude: [(u32, u32); 3], lon_ref: &[u8; 2]) -> Vec<u8> {
+        let rationals = |values: [(u32, u32); 3]| -> Vec<u8> {
+            values.iter().flat_map(|&(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat()).collect()
+        };
+        let mut tiff = TestTiff::new(Endian::Little);
+        let lat = tiff.push_data(&rationals(latitude));
+        let lon = tiff.push_data(&rationals(longitude));
+        let gps = tiff.push_data(&raw_le_ifd(&[
+            (tags::tags::GPS_LATITUDE_REF, 2, 2, u16::from_le_bytes(*lat_ref) as u32),
+            (tags::tags::GPS_LATITUDE, 5, 3, lat),
+            (tags::tags::GPS_LONGITUDE_REF, 2, 2, u16::from_le_bytes(*lon_ref) as u32),
+            (tags::tags::GPS_LONGITUDE, 5, 3, lon),
+        ]));
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]).long(tags::tags::GPS_IFD, &[gps]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_gps_coordinates() {
+        // Eiffel Tower: 48° 51' 29.6" N, 2° 17' 40.2" E
+        let data = gps_file([(48, 1), (51, 1), (296, 10)], b"N\0", [(2, 1), (17, 1), (402, 10)], b"E\0");
+        let (reader, ifd, endian) = first_ifd(data);
+        assert!(ifd.gps_ifd(&reader, endian).unwrap().is_some());
+        let (lat, lon) = ifd.gps_coordinates(&reader, endian).unwrap().unwrap();
+        assert!((lat - 48.858_222).abs() < 1e-6, "{lat}");
+        assert!((lon - 2.294_5).abs() < 1e-6, "{lon}");
+
+        // Sydney Opera House's latitude (33° 51' 25" S) with the Statue of
+        // Liberty's longitude (74° 2' 40.2" W)
+        let data = gps_file([(33, 1), (51, 1), (25, 1)], b"S\0", [(74, 1), (2, 1), (402, 10)], b"W\0");
+        let (reader, ifd, endian) = first_ifd(data);
+        let (lat, lon) = ifd.gps_coordinates(&reader, endian).unwrap().unwrap();
+        assert!((lat + 33.856_944).abs() < 1e-6, "{lat}");
+        assert!((lon + 74.044_5).abs() < 1e-6, "{lon}");
+    }
+
+    #[test]
+    fn test_gps_coordinates_missing_or_invalid() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.gps_coordinates(&reader, endian).unwrap(), None);
+
+        let data = gps_file([(1, 1), (0, 1), (0, 1)], b"X\0", [(1, 1), (0, 1), (0, 1)], b"E\0");
+        let (reader, ifd, endian) = first_ifd(data);
+        assert!(matches!(
+            ifd.gps_coordinates(&reader, endian),
+            Err(TiffError::InvalidTag { tag: tags::tags::GPS_LATITUDE_REF, .. })
+        ));
+
+        let data = gps_file([(1, 1), (0, 0), (0, 1)], b"N\0", [(1, 1), (0, 1), (0, 1)], b"E\0");
+        let (reader, ifd, endian) = first_ifd(data);
+        assert!(matches!(
+            ifd.gps_coordinates(&reader, endian),
+            Err(TiffError::InvalidTag { tag: tags::tags::GPS_LATITUDE, .. })
+        ));
+    }
+
+    #[test]
+    fn test_sub_ifds() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let half = tiff.push_data(&raw_le_ifd(&[(tags::tags::IMAGE_WIDTH, 3, 1, 8)]));
+        let quarter = tiff.push_data(&raw_le_ifd(&[(tags::tags::IMAGE_WIDTH, 3, 1, 4)]));
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[16]).long(tags::tags::SUB_IFDS, &[half, 0, quarter]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let widths: Vec<_> = ifd
+            .sub_ifds(&reader, endian)
+            .unwrap()
+            .iter()
+            .map(|sub| sub.image_width(&reader, endian).unwrap())
+            .collect();
+        assert_eq!(widths, vec![Some(8), Some(4)]);
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[16]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(ifd.sub_ifds(&reader, endian).unwrap().is_empty());
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[16]).long(tags::tags::SUB_IFDS, &[u32::MAX]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert!(matches!(ifd.sub_ifds(&reader, endian), Err(TiffError::OutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_jpeg_tables() {
+        let tables = [0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x03, 0x00, 0xFF, 0xD9];
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().raw(tags::tags::JPEG_TABLES, 7, tables.len() as u32, tables.to_vec());
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.jpeg_tables(&reader, endian).unwrap(), Some(tables.to_vec()));
+        assert_eq!(tags::tag_name(347), "JPEGTables");
+    }
+
+    #[test]
+    fn test_samples_per_pixel_default() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::IMAGE_WIDTH, &[1]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.samples_per_pixel(&reader, endian).unwrap(), None);
+        assert_eq!(ifd.samples_per_pixel_or_default(&reader, endian).unwrap(), 1);
+        assert_eq!(ifd.image_summary(&reader, endian).unwrap().samples_per_pixel, 1);
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::tags::SAMPLES_PER_PIXEL, &[3]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+        assert_eq!(ifd.samples_per_pixel_or_default(&reader, endian).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_get_tag_value_expecting() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .double(tags::tags::MODEL_PIXEL_SCALE, &[0.5, 0.5, 0.0])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8]);
+        let (reader, ifd, endian) = first_ifd(tiff.build());
+
+        let scale = ifd.get_tag_value_expecting(tags::tags::MODEL_PIXEL_SCALE, 3, &reader, endian).unwrap();
+        assert!(matches!(scale, Some(TagValue::Doubles(v)) if v == vec![0.5, 0.5, 0.0]));
+        assert!(ifd.get_tag_value_expecting(tags::tags::IMAGE_WIDTH, 1, &reader, endian).unwrap().is_none());
+
+        match ifd.get_tag_value_expecting(tags::tags::BITS_PER_SAMPLE, 3, &reader, endian) {
+            Err(TiffError::InvalidTag { tag, reason }) => {
+                assert_eq!(tag, tags::tags::BITS_PER_SAMPLE);
+                assert_eq!(reason, "expected 3 values, found 2");
+            }
+            other => panic!("expected InvalidTag, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_ifd_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ImageFileDirectory>();
+    }
+}
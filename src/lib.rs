@@ -11,11 +11,28 @@
 //! - `header`: TIFF header parsing and endianness handling
 //! - `ifd`: Image File Directory parsing and tag value extraction
 //! - `tags`: Standard TIFF tag definitions and enums
+//! - `writer`: Serializing IFDs and image data back into TIFF files
+//! - `codec`: Decompression and predictor reversal for image data
+//! - `geotiff`: GeoTIFF key directory parsing and geotransforms
+//! - `color`: Color space conversion to RGB
+//! - `async_reader`: Async data sources and IFD reading (`tokio` feature)
 //! - `error`: Error types and handling
 //!
+//! # Features
+//!
+//! - `gzip`: read whole-file gzipped TIFFs with `TiffFile::from_gzip`
+//! - `memmap2`: read files through a memory map with `MmapSource`
+//! - `std` (default): `FileSource` and `TiffError::Io`. Without it the
+//!   crate builds against `core` and `alloc` only; `memmap2` and `tokio`
+//!   turn it on
+//! - `tokio`: read headers, IFDs and tag values from async sources with
+//!   `AsyncTiffReader`
+//! - `serde`: `Serialize`/`Deserialize` for `ImageSummary`, `TagValue` and
+//!   the compression, photometric, resolution unit and sample format enums
+//!
 //! # Basic Usage
 //!
-//! ```rust
+//! ```rust,no_run
 //! use tiff_core::{TiffFile, InMemorySource};
 //! 
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -31,25 +48,192 @@
 //! # }
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 #![warn(rust_2018_idioms)]
 
+extern crate alloc;
+
 pub mod error;
 pub mod header;
 pub mod reader;
 pub mod ifd;
 pub mod tags;
+pub mod writer;
+pub mod codec;
+pub mod geotiff;
+pub mod color;
+#[cfg(feature = "tokio")]
+pub mod async_reader;
+
+#[cfg(test)]
+mod testutil;
+
+/// The `alloc` types the std prelude would otherwise provide
+mod prelude {
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+    pub(crate) use alloc::{format, vec};
+}
 
 // Re-export commonly used types for convenience
+pub use codec::CompressionCodec;
 pub use error::{TiffError, Result};
 pub use header::{Endian, TiffHeader};
-pub use reader::{TiffDataSource, TiffReader, InMemorySource};
-pub use ifd::{ImageFileDirectory, IfdEntry, TagValue, FieldType, ImageSummary};
+pub use reader::{TiffDataSource, TiffReader, TiffImageReader, TileOrder, InMemorySource, apply_orientation, expand_palette, f16_to_f32, unpack_bits, unpack_bits_u16};
+#[cfg(feature = "std")]
+pub use reader::FileSource;
+#[cfg(feature = "gzip")]
+pub use reader::GzipSource;
+#[cfg(feature = "memmap2")]
+pub use reader::MmapSource;
+pub use ifd::{ImageFileDirectory, IfdEntry, IfdPath, IfdPathSegment, TagValue, FieldType, ImageSummary};
+pub use writer::{TiffBuilder, TiffEditor, TiffWriter};
+pub use geotiff::{GeoKey, GeoKeyDirectory, GeoKeyValue, GeoTransform};
 pub use tags::{
-    Compression, PhotometricInterpretation, ResolutionUnit, SampleFormat,
-    tag_name, is_required_tag, is_layout_tag, is_data_location_tag,
+    Compression, FillOrder, Orientation, PhotometricInterpretation, PlanarConfiguration, ResolutionUnit, SampleFormat, Tag,
+    tag_name, tag_from_name, is_required_tag, is_layout_tag, is_data_location_tag,
 };
 
+use alloc::collections::BTreeSet;
+use crate::prelude::*;
+
+/// Header-level summary of a TIFF file
+///
+/// Returned by [`TiffFile::file_info`]. Its `Display` output reads like
+/// "Little-endian classic TIFF, 3 images".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    /// Byte order of the file
+    pub endian: Endian,
+    /// Whether the file is BigTIFF (magic 43) rather than classic TIFF
+    pub is_bigtiff: bool,
+    /// Number of IFDs in the main chain
+    pub ifd_count: usize,
+    /// Offset of the first IFD, as stored in the header
+    pub first_ifd_offset: u64,
+}
+
+impl core::fmt::Display for FileInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let endian = match self.endian {
+            Endian::Little => "Little-endian",
+            Endian::Big => "Big-endian",
+        };
+        let version = if self.is_bigtiff { "BigTIFF" } else { "classic TIFF" };
+        let plural = if self.ifd_count == 1 { "" } else { "s" };
+        write!(f, "{endian} {version}, {} image{plural}", self.ifd_count)
+    }
+}
+
+/// Loop, length and depth checks for walking IFD chains and pointer trees
+///
+/// Shared by every reader of the main chain and by `walk_all_ifds`, so
+/// they all reject the same malformed files.
+#[derive(Debug)]
+pub(crate) struct IfdChainGuard {
+    visited: BTreeSet<usize>,
+    max_ifds: usize,
+    max_depth: usize,
+}
+
+impl IfdChainGuard {
+    /// Start a guard allowing at most `max_ifds` directories
+    pub(crate) fn new(max_ifds: usize) -> Self {
+        Self { visited: BTreeSet::new(), max_ifds, max_depth: usize::MAX }
+    }
+
+    /// Also refuse directories nested more than `max_depth` pointers deep
+    pub(crate) fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Check if the IFD at `offset` has already been visited
+    pub(crate) fn is_visited(&self, offset: usize) -> bool {
+        self.visited.contains(&offset)
+    }
+
+    /// Record that the IFD at `offset` is about to be read
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if `offset` was already visited or the chain
+    /// already holds `max_ifds` directories
+    pub(crate) fn visit(&mut self, offset: usize) -> Result<()> {
+        if self.visited.contains(&offset) {
+            return Err(TiffError::MalformedFile {
+                reason: format!("IFD chain loops back to offset {offset}"),
+            });
+        }
+        if self.visited.len() == self.max_ifds {
+            return Err(TiffError::MalformedFile {
+                reason: format!("IFD chain exceeds the limit of {} IFDs", self.max_ifds),
+            });
+        }
+        self.visited.insert(offset);
+        Ok(())
+    }
+
+    /// Record that the IFD at `offset`, `depth` pointers below the main
+    /// chain, is about to be read
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if `depth` exceeds the depth limit, or any
+    /// error from `visit`
+    pub(crate) fn visit_nested(&mut self, offset: usize, depth: usize) -> Result<()> {
+        if depth > self.max_depth {
+            return Err(TiffError::MalformedFile {
+                reason: format!("IFD at offset {offset} is nested deeper than {} pointers", self.max_depth),
+            });
+        }
+        self.visit(offset)
+    }
+}
+
+/// A directory queued by `walk_all_ifds`, not yet read
+#[derive(Debug)]
+struct PendingIfd {
+    /// Index in the output of the directory that points here
+    parent: usize,
+    /// Pointers followed from the main chain
+    depth: usize,
+    /// Path segments to append to the parent's
+    steps: Vec<IfdPathSegment>,
+    offset: usize,
+}
+
+/// Iterator that parses a file's main IFD chain one directory at a time
+///
+/// Created by [`TiffFile::ifds_lazy`]. Each IFD is read only when the
+/// iterator advances, with the same loop and length checks as
+/// `from_reader`. Iteration stops after the first error.
+#[derive(Debug)]
+pub struct LazyIfds<'a, T: TiffDataSource> {
+    file: &'a TiffFile<T>,
+    next_offset: usize,
+    guard: IfdChainGuard,
+}
+
+impl<T: TiffDataSource> Iterator for LazyIfds<'_, T> {
+    type Item = Result<ImageFileDirectory>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = core::mem::take(&mut self.next_offset);
+        if offset == 0 {
+            return None;
+        }
+        let ifd = self
+            .guard
+            .visit(offset)
+            .and_then(|()| self.file.reader.read_ifd_at(offset, self.file.endianness()));
+        if let Ok(ifd) = &ifd {
+            self.next_offset = ifd.next_ifd_offset;
+        }
+        Some(ifd)
+    }
+}
+
 /// The main TIFF file structure
 /// 
 /// This represents a complete TIFF file with header and all IFDs.
@@ -65,19 +249,53 @@ pub struct TiffFile<T: TiffDataSource> {
 }
 
 impl<T: TiffDataSource> TiffFile<T> {
+    /// Longest main IFD chain `from_reader` will follow
+    ///
+    /// Far more pages than any real file has, while keeping a crafted
+    /// chain from growing `ifds` without bound.
+    pub const DEFAULT_MAX_IFDS: usize = 65536;
+
+    /// Deepest chain of SubIFD, EXIF, GPS and Interoperability pointers
+    /// `walk_all_ifds` will follow
+    ///
+    /// Real files nest a few levels at most (main, EXIF, Interoperability).
+    pub const MAX_SUB_IFD_DEPTH: usize = 32;
+
     /// Read a TIFF file from a data source
     /// 
-    /// This is the main entry point for parsing TIFF files.
-    pub fn from_reader(mut reader: TiffReader<T>) -> Result<Self> {
+    /// This is the main entry point for parsing TIFF files. The main IFD
+    /// chain is limited to [`Self::DEFAULT_MAX_IFDS`] directories.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the IFD chain loops back on itself or is
+    /// longer than the limit
+    pub fn from_reader(reader: TiffReader<T>) -> Result<Self> {
+        Self::from_reader_with_max_ifds(reader, Self::DEFAULT_MAX_IFDS)
+    }
+
+    /// Read a TIFF file from a data source, following at most `max_ifds`
+    /// directories in the main IFD chain
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the IFD chain loops back on itself or has
+    /// more than `max_ifds` directories
+    pub fn from_reader_with_max_ifds(mut reader: TiffReader<T>, max_ifds: usize) -> Result<Self> {
         // Read header first
         let header = reader.read_header()?;
         
         // Read all IFDs
         let mut ifds = Vec::new();
+        let mut guard = IfdChainGuard::new(max_ifds);
         let mut ifd_offset = header.ifd_offset as usize;
         
         while ifd_offset != 0 {
+            guard.visit(ifd_offset)?;
             let ifd = reader.read_ifd(ifd_offset, header.endianness())?;
+            if ifd.next_ifd_offset == ifd_offset {
+                return Err(TiffError::MalformedFile {
+                    reason: "IFD points to itself".to_string(),
+                });
+            }
             ifd_offset = ifd.next_ifd_offset;
             ifds.push(ifd);
         }
@@ -85,6 +303,85 @@ impl<T: TiffDataSource> TiffFile<T> {
         Ok(TiffFile { reader, header, ifds })
     }
 
+    /// Read a TIFF file, skipping over damage instead of failing
+    ///
+    /// For recovery tools that want as much of a corrupt file as possible.
+    /// Entries with an unknown field type or a value past the end of the
+    /// file are dropped from their IFD. A bad link in the main IFD chain (a
+    /// loop, an offset outside the file, a truncated IFD, or more than
+    /// [`Self::DEFAULT_MAX_IFDS`] directories) ends the chain there. Each
+    /// problem skipped is returned as a warning.
+    ///
+    /// # Returns
+    /// The file with every IFD that could be read, and the warnings in the
+    /// order they were found
+    ///
+    /// # Errors
+    /// Only a missing or invalid header is fatal
+    pub fn from_reader_lenient(mut reader: TiffReader<T>) -> Result<(Self, Vec<TiffError>)> {
+        let header = reader.read_header()?;
+        let endian = header.endianness();
+
+        let mut ifds = Vec::new();
+        let mut warnings = Vec::new();
+        let mut guard = IfdChainGuard::new(Self::DEFAULT_MAX_IFDS);
+        let mut ifd_offset = header.ifd_offset as usize;
+
+        while ifd_offset != 0 {
+            let ifd = match guard.visit(ifd_offset).and_then(|()| reader.read_ifd(ifd_offset, endian)) {
+                Ok(ifd) => ifd,
+                Err(e) => {
+                    warnings.push(e);
+                    break;
+                }
+            };
+            let entries = ifd.entries.into_iter()
+                .filter(|entry| match reader.check_value_bounds(entry) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        warnings.push(e);
+                        false
+                    }
+                })
+                .collect();
+            ifd_offset = ifd.next_ifd_offset;
+            ifds.push(ImageFileDirectory::new(entries, ifd_offset));
+        }
+
+        Ok((TiffFile { reader, header, ifds }, warnings))
+    }
+
+    /// Read a TIFF file's header and only its first IFD
+    ///
+    /// This is the minimal-work path for metadata extraction when only the
+    /// main image matters: `next_ifd_offset` is never followed, so long or
+    /// corrupt IFD chains are not touched. `image_count()` will be 1 (or 0
+    /// for a file with no IFDs) and subsequent pages are not loaded.
+    pub fn from_reader_first_only(mut reader: TiffReader<T>) -> Result<Self> {
+        let header = reader.read_header()?;
+
+        let mut ifds = Vec::new();
+        if header.ifd_offset != 0 {
+            ifds.push(reader.read_ifd(header.ifd_offset as usize, header.endianness())?);
+        }
+
+        Ok(TiffFile { reader, header, ifds })
+    }
+
+    /// Iterate over the main IFD chain, parsing each IFD on demand
+    ///
+    /// This re-reads the chain from the header rather than using `ifds`,
+    /// so pairing it with [`Self::from_reader_first_only`] gives a
+    /// streaming mode for documents with thousands of pages: only the IFDs
+    /// actually reached are parsed.
+    pub fn ifds_lazy(&self) -> LazyIfds<'_, T> {
+        LazyIfds {
+            file: self,
+            next_offset: self.header.ifd_offset as usize,
+            guard: IfdChainGuard::new(Self::DEFAULT_MAX_IFDS),
+        }
+    }
+
     /// Get the number of images (IFDs) in this file
     pub fn image_count(&self) -> usize {
         self.ifds.len()
@@ -105,6 +402,16 @@ impl<T: TiffDataSource> TiffFile<T> {
         self.header.endianness()
     }
 
+    /// Get a header-level summary of the file
+    pub fn file_info(&self) -> FileInfo {
+        FileInfo {
+            endian: self.endianness(),
+            is_bigtiff: self.header.is_bigtiff(),
+            ifd_count: self.ifds.len(),
+            first_ifd_offset: self.header.ifd_offset,
+        }
+    }
+
     /// Get basic image information from the main IFD
     pub fn main_image_info(&self) -> Result<Option<ImageSummary>> {
         if let Some(ifd) = self.main_ifd() {
@@ -136,6 +443,534 @@ impl<T: TiffDataSource> TiffFile<T> {
             Ok(false)
         }
     }
+
+    /// Check if any strip or tile extends past the end of the data source
+    ///
+    /// Truncated downloads are common in practice; this gives a single
+    /// yes/no across all IFDs before attempting to decode anything.
+    pub fn is_truncated(&self) -> Result<bool> {
+        let mut data_end = 0usize;
+        for ifd in &self.ifds {
+            for (offset, count) in ifd.image_data_ranges(&self.reader, self.endianness())? {
+                match offset.checked_add(count) {
+                    Some(end) => data_end = data_end.max(end),
+                    None => return Ok(true),
+                }
+            }
+        }
+        Ok(data_end > self.reader.len())
+    }
+
+    /// Verify that an image can be fully decoded, without keeping the pixels
+    ///
+    /// Runs every strip or tile through the same decode path as
+    /// `TiffImageReader` and discards the output, so a batch validator can
+    /// flag files with unsupported codecs, corrupt data or bad offsets
+    /// without allocating the whole image.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for an invalid IFD index, otherwise the first
+    /// error hit while decoding
+    pub fn dry_run_decode(&self, ifd_index: usize) -> Result<()> {
+        let ifd = self.ifds.get(ifd_index).ok_or(TiffError::OutOfBounds {
+            index: ifd_index,
+            max: self.ifds.len(),
+        })?;
+        TiffImageReader::new(&self.reader, ifd, self.endianness())?.decode_all_blocks()
+    }
+
+    /// Get IFD indices in logical page order
+    ///
+    /// Multi-page files aren't required to store pages in order, so this
+    /// sorts IFDs by the page component of their PageNumber tag (297).
+    /// IFDs without a PageNumber come after the numbered ones, in file
+    /// order; a file with no PageNumber tags at all keeps its file order.
+    pub fn pages_sorted_by_number(&self) -> Result<Vec<usize>> {
+        let mut pages = Vec::with_capacity(self.ifds.len());
+        for (index, ifd) in self.ifds.iter().enumerate() {
+            let page = ifd.page_number(&self.reader, self.endianness())?.map(|(page, _)| page);
+            pages.push((page, index));
+        }
+        pages.sort_by_key(|&(page, _)| (page.is_none(), page));
+        Ok(pages.into_iter().map(|(_, index)| index).collect())
+    }
+
+    /// Get the distinct field types used by entries across all IFDs
+    ///
+    /// Returned in ascending order of their TIFF type code. Entries with
+    /// unrecognized field type numbers are skipped.
+    pub fn field_types_used(&self) -> Vec<FieldType> {
+        let mut types: Vec<FieldType> = Vec::new();
+        for entry in self.ifds.iter().flat_map(|ifd| &ifd.entries) {
+            if let Ok(field_type) = FieldType::from_u16(entry.field_type)
+                && !types.contains(&field_type)
+            {
+                types.push(field_type);
+            }
+        }
+        types.sort_by_key(|&field_type| field_type as u16);
+        types
+    }
+
+    /// Look for values that make more sense in the opposite byte order
+    ///
+    /// This is a best-effort forensic heuristic, not spec behavior: the
+    /// spec requires one byte order per file, but broken tools sometimes
+    /// write some structures in the wrong one. Each IFD is checked for:
+    /// - enumerated SHORT tags (Compression, PhotometricInterpretation,
+    ///   FillOrder, ResolutionUnit, BitsPerSample, SamplesPerPixel) whose
+    ///   value is invalid but valid once byte-swapped
+    /// - LONG image or tile dimensions above 2^20 that are reasonable once
+    ///   byte-swapped
+    /// - out-of-line value offsets past the end of the file that land
+    ///   inside it once byte-swapped
+    ///
+    /// # Returns
+    /// One human-readable message per suspicious value; an empty vec means
+    /// nothing looked wrong (which doesn't prove the file is consistent)
+    pub fn detect_endian_anomalies(&self) -> Result<Vec<String>> {
+        const MAX_PLAUSIBLE_DIMENSION: u32 = 1 << 20;
+        let plausible_enum = |tag: u16, value: u32| match tag {
+            tags::tags::COMPRESSION => Compression::from_u32(value).is_some(),
+            tags::tags::PHOTOMETRIC_INTERPRETATION => PhotometricInterpretation::from_u32(value).is_some(),
+            tags::tags::FILL_ORDER => FillOrder::from_u32(value).is_some(),
+            tags::tags::RESOLUTION_UNIT => ResolutionUnit::from_u32(value).is_some(),
+            tags::tags::BITS_PER_SAMPLE => (1..=64).contains(&value),
+            tags::tags::SAMPLES_PER_PIXEL => (1..=16).contains(&value),
+            _ => true,
+        };
+
+        let endian = self.endianness();
+        let file_len = self.reader.len() as u64;
+        let inline_size = self.reader.offset_size() as u64;
+        let mut anomalies = Vec::new();
+
+        for (index, ifd) in self.ifds.iter().enumerate() {
+            for entry in &ifd.entries {
+                let name = tag_name(entry.tag);
+                let Ok(field_type) = FieldType::from_u16(entry.field_type) else {
+                    continue;
+                };
+
+                let size = (field_type.byte_size() as u64).saturating_mul(entry.count);
+                if size > inline_size {
+                    let swapped = if self.header.is_bigtiff() {
+                        entry.value_offset.swap_bytes()
+                    } else {
+                        (entry.value_offset as u32).swap_bytes() as u64
+                    };
+                    if entry.value_offset.saturating_add(size) > file_len && swapped.saturating_add(size) <= file_len {
+                        anomalies.push(format!(
+                            "IFD {index}: {name} (tag {}) value offset {} is past the end of the file, but {swapped} byte-swapped is not",
+                            entry.tag, entry.value_offset
+                        ));
+                    }
+                    continue;
+                }
+                if entry.count != 1 {
+                    continue;
+                }
+
+                let (value, swapped) = match self.reader.parse_tag_value(entry, endian)? {
+                    TagValue::Shorts(v) => (v[0] as u32, v[0].swap_bytes() as u32),
+                    TagValue::Longs(v) => (v[0], v[0].swap_bytes()),
+                    _ => continue,
+                };
+                let suspicious = match entry.tag {
+                    tags::tags::IMAGE_WIDTH
+                    | tags::tags::IMAGE_LENGTH
+                    | tags::tags::TILE_WIDTH
+                    | tags::tags::TILE_LENGTH => {
+                        field_type == FieldType::Long
+                            && value > MAX_PLAUSIBLE_DIMENSION
+                            && (1..=MAX_PLAUSIBLE_DIMENSION).contains(&swapped)
+                    }
+                    tag => field_type == FieldType::Short && !plausible_enum(tag, value) && plausible_enum(tag, swapped),
+                };
+                if suspicious {
+                    anomalies.push(format!(
+                        "IFD {index}: {name} is {value}, but {swapped} if byte-swapped"
+                    ));
+                }
+            }
+        }
+        Ok(anomalies)
+    }
+
+    /// Get the byte range touched by the file's structures and data
+    ///
+    /// Covers the header, every IFD reachable through `walk_all_ifds`
+    /// (including SubIFDs, EXIF and GPS directories), every out-of-line tag
+    /// value and every strip or tile. Comparing the end to the file length
+    /// reveals appended data or trailing padding; gaps inside the range
+    /// are not reported.
+    ///
+    /// # Returns
+    /// `(start, end)` as a half-open range; `start` is always 0 since the
+    /// header is at the start of the file
+    pub fn data_extent(&self) -> Result<(usize, usize)> {
+        let endian = self.endianness();
+        let inline_size = self.reader.offset_size() as u64;
+        let mut end = self.header.size() as u64;
+
+        for path in self.walk_all_ifds()? {
+            let ifd = &path.ifd;
+            end = end.max((path.offset + self.reader.ifd_byte_size(ifd.entries.len())) as u64);
+            for entry in &ifd.entries {
+                let Ok(field_type) = FieldType::from_u16(entry.field_type) else {
+                    continue;
+                };
+                let size = (field_type.byte_size() as u64).saturating_mul(entry.count);
+                if size > inline_size {
+                    end = end.max(entry.value_offset.saturating_add(size));
+                }
+            }
+            for (offset, count) in ifd.image_data_ranges(&self.reader, endian)? {
+                end = end.max(offset as u64 + count as u64);
+            }
+        }
+
+        Ok((0, usize::try_from(end).unwrap_or(usize::MAX)))
+    }
+
+    /// Scan the whole file for byte ranges that look like IFDs
+    ///
+    /// A recovery tool for files whose header or IFD chain is damaged but
+    /// whose directories are intact, or for several TIFFs concatenated
+    /// together. Every word-aligned offset is tested with heuristics: an
+    /// entry count of 1 to 1000 that fits in the file, strictly ascending
+    /// tags, known field types, and a next-IFD offset that is 0 or inside
+    /// the file. Directories of the main chain are left out.
+    ///
+    /// This is heuristic: random data can pass the checks and damaged
+    /// IFDs can fail them. It reads the file in full, so it is slow on
+    /// large files.
+    ///
+    /// # Returns
+    /// Candidate IFD offsets in ascending order
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` for BigTIFF files, whose IFD layout
+    /// the heuristics don't cover
+    pub fn scan_for_ifds(&self) -> Result<Vec<usize>> {
+        if self.header.is_bigtiff() {
+            return Err(TiffError::UnsupportedFeature {
+                feature: "scanning BigTIFF files for IFDs".to_string(),
+            });
+        }
+        let endian = self.endianness();
+        let data = self.reader.read_bytes_at(0, self.reader.len())?;
+
+        let mut chain = BTreeSet::new();
+        let mut offset = self.header.ifd_offset as usize;
+        for ifd in &self.ifds {
+            chain.insert(offset);
+            offset = ifd.next_ifd_offset;
+        }
+
+        let u16_at = |pos: usize| endian.read_u16([data[pos], data[pos + 1]]);
+        let u32_at = |pos: usize| endian.read_u32([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        let looks_like_ifd = |offset: usize| {
+            let count = u16_at(offset) as usize;
+            let end = offset + 2 + count * 12 + 4;
+            if !(1..=1000).contains(&count) || end > data.len() {
+                return false;
+            }
+            let mut previous_tag = None;
+            for entry in 0..count {
+                let pos = offset + 2 + entry * 12;
+                let tag = u16_at(pos);
+                if previous_tag.is_some_and(|previous| tag <= previous)
+                    || FieldType::from_u16(u16_at(pos + 2)).is_err()
+                {
+                    return false;
+                }
+                previous_tag = Some(tag);
+            }
+            (u32_at(end - 4) as usize) < data.len()
+        };
+
+        Ok((TiffHeader::SIZE..data.len().saturating_sub(1))
+            .step_by(2)
+            .filter(|offset| !chain.contains(offset) && looks_like_ifd(*offset))
+            .collect())
+    }
+
+    /// Render the whole file structure as deterministic, diff-friendly text
+    ///
+    /// Lists the header and every IFD from `walk_all_ifds` (labelled with
+    /// its path), each with its entries sorted by tag as
+    /// `tag Name Type[count] = value`. Values are formatted with
+    /// `TagValue::to_display_string`, truncated after 64 items.
+    ///
+    /// File offsets are left out so that files with the same structure
+    /// but a different layout produce identical text: IFD offsets aren't
+    /// printed, and the values of StripOffsets, TileOffsets and the
+    /// sub-IFD pointer tags are replaced by `<offsets>`. Values that can't
+    /// be read are shown as `<unreadable: error>` rather than failing.
+    pub fn to_canonical_text(&self) -> Result<String> {
+        use core::fmt::Write;
+
+        const OFFSET_TAGS: [u16; 6] = [
+            tags::tags::STRIP_OFFSETS,
+            tags::tags::TILE_OFFSETS,
+            tags::tags::SUB_IFDS,
+            tags::tags::EXIF_IFD,
+            tags::tags::GPS_IFD,
+            tags::tags::INTEROPERABILITY_IFD,
+        ];
+
+        let endian = self.endianness();
+        let mut text = format!("{}\n", self.file_info());
+        for path in self.walk_all_ifds()? {
+            let _ = writeln!(text, "IFD {path}");
+            let mut entries: Vec<&IfdEntry> = path.ifd.entries.iter().collect();
+            entries.sort_by_key(|entry| entry.tag);
+            for entry in entries {
+                let field_type = match FieldType::from_u16(entry.field_type) {
+                    Ok(field_type) => format!("{field_type:?}"),
+                    Err(_) => format!("Type{}", entry.field_type),
+                };
+                let value = if OFFSET_TAGS.contains(&entry.tag) {
+                    "<offsets>".to_string()
+                } else {
+                    match self.reader.parse_tag_value(entry, endian) {
+                        Ok(value) => value.to_display_string(64),
+                        Err(e) => format!("<unreadable: {e}>"),
+                    }
+                };
+                let _ = writeln!(
+                    text,
+                    "  {} {} {field_type}[{}] = {value}",
+                    entry.tag,
+                    tag_name(entry.tag),
+                    entry.count
+                );
+            }
+        }
+        Ok(text)
+    }
+
+    /// Start editing this file's tags
+    ///
+    /// Returns a `TiffEditor` that queues tag changes and writes a new
+    /// file on `save`, copying the image data verbatim.
+    pub fn edit(&self) -> TiffEditor<'_, T> {
+        TiffEditor::new(self)
+    }
+
+    /// Get the byte offset of each IFD in the main chain
+    fn ifd_offsets(&self) -> Vec<usize> {
+        core::iter::once(self.header.ifd_offset as usize)
+            .chain(self.ifds.iter().map(|ifd| ifd.next_ifd_offset))
+            .take(self.ifds.len())
+            .collect()
+    }
+
+    /// Visit every IFD in the file, including SubIFDs, EXIF and GPS directories
+    ///
+    /// Walks the main chain and, depth first, every directory reachable
+    /// through the SubIFDs (330), EXIF (34665), GPS (34853) and
+    /// Interoperability (40965) pointer tags. Each result records how it
+    /// was reached, e.g. `[0]`, `[0, SubIFD, 1]`, `[0, Exif]`.
+    ///
+    /// Only the main chain's next-IFD links are followed. A directory that
+    /// has already been visited is skipped, so pointer cycles terminate.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if pointers nest more than
+    /// [`Self::MAX_SUB_IFD_DEPTH`] deep or reach more than
+    /// [`Self::DEFAULT_MAX_IFDS`] directories, or an error if a pointed-to
+    /// directory can't be read
+    pub fn walk_all_ifds(&self) -> Result<Vec<IfdPath>> {
+        let offsets = self.ifd_offsets();
+        let mut guard = IfdChainGuard::new(Self::DEFAULT_MAX_IFDS).with_max_depth(Self::MAX_SUB_IFD_DEPTH);
+        for &offset in &offsets {
+            guard.visit(offset)?;
+        }
+        let mut paths = Vec::new();
+        for (index, (ifd, offset)) in self.ifds.iter().zip(offsets).enumerate() {
+            paths.push(IfdPath {
+                segments: vec![IfdPathSegment::Index(index)],
+                offset,
+                ifd: ifd.clone(),
+            });
+            self.walk_sub_ifds(paths.len() - 1, &mut guard, &mut paths)?;
+        }
+        Ok(paths)
+    }
+
+    /// Append the directories reachable from `paths[root]`, depth first
+    ///
+    /// Uses an explicit stack rather than recursion, so a deeply nested
+    /// file can't overflow the call stack before the depth limit is hit.
+    fn walk_sub_ifds(&self, root: usize, guard: &mut IfdChainGuard, paths: &mut Vec<IfdPath>) -> Result<()> {
+        let endian = self.endianness();
+        let mut pending = Vec::new();
+        self.push_sub_ifds(root, 1, paths, &mut pending)?;
+        while let Some(child) = pending.pop() {
+            if guard.is_visited(child.offset) {
+                continue;
+            }
+            guard.visit_nested(child.offset, child.depth)?;
+            let mut segments = paths[child.parent].segments.clone();
+            segments.extend(child.steps);
+            paths.push(IfdPath {
+                segments,
+                offset: child.offset,
+                ifd: self.reader.read_ifd_at(child.offset, endian)?,
+            });
+            self.push_sub_ifds(paths.len() - 1, child.depth + 1, paths, &mut pending)?;
+        }
+        Ok(())
+    }
+
+    /// Queue the directories `paths[parent]` points to
+    ///
+    /// They're pushed in reverse so they pop off `pending` in pointer order.
+    fn push_sub_ifds(&self, parent: usize, depth: usize, paths: &[IfdPath], pending: &mut Vec<PendingIfd>) -> Result<()> {
+        const POINTERS: [(u16, IfdPathSegment); 4] = [
+            (tags::tags::SUB_IFDS, IfdPathSegment::SubIfd),
+            (tags::tags::EXIF_IFD, IfdPathSegment::Exif),
+            (tags::tags::GPS_IFD, IfdPathSegment::Gps),
+            (tags::tags::INTEROPERABILITY_IFD, IfdPathSegment::Interop),
+        ];
+
+        let start = pending.len();
+        for (tag, segment) in POINTERS {
+            let offsets = paths[parent].ifd.ifd_pointer_offsets(tag, &self.reader, self.endianness())?;
+            for (index, offset) in offsets.into_iter().enumerate() {
+                if offset == 0 {
+                    continue;
+                }
+                let mut steps = vec![segment];
+                if segment == IfdPathSegment::SubIfd {
+                    steps.push(IfdPathSegment::Index(index));
+                }
+                pending.push(PendingIfd { parent, depth, steps, offset });
+            }
+        }
+        pending[start..].reverse();
+        Ok(())
+    }
+
+    /// Run the structural checks behind `from_bytes_validated`
+    fn validate_structure(&self) -> Result<()> {
+        let endian = self.endianness();
+        let mut ranges = Vec::new();
+
+        for (index, ifd) in self.ifds.iter().enumerate() {
+            if !ifd.is_valid_tiff(&self.reader, endian)? {
+                return Err(TiffError::MalformedFile {
+                    reason: format!(
+                        "IFD {index} is missing ImageWidth, ImageLength or its strip/tile locations"
+                    ),
+                });
+            }
+
+            for entry in &ifd.entries {
+                let Ok(field_type) = FieldType::from_u16(entry.field_type) else {
+                    continue;
+                };
+                if matches!(entry.tag, tags::tags::X_RESOLUTION | tags::tags::Y_RESOLUTION)
+                    && field_type != FieldType::Rational
+                {
+                    return Err(TiffError::MalformedFile {
+                        reason: format!(
+                            "{} in IFD {index} is {field_type:?}; the spec requires RATIONAL",
+                            tag_name(entry.tag)
+                        ),
+                    });
+                }
+            }
+            ifd.validate_offsets(&self.reader, endian).map_err(|e| match e {
+                TiffError::MalformedFile { reason } => TiffError::MalformedFile {
+                    reason: format!("IFD {index}: {reason}"),
+                },
+                e => e,
+            })?;
+
+            for (offset, count) in ifd.image_data_ranges(&self.reader, endian)? {
+                if count > 0 {
+                    let end = offset.checked_add(count).ok_or_else(|| TiffError::MalformedFile {
+                        reason: format!("IFD {index}: image data at offset {offset} ({count} bytes) overflows"),
+                    })?;
+                    ranges.push((offset, end));
+                }
+            }
+        }
+
+        // Identical ranges are allowed: writers may share one block between
+        // several empty tiles. Partial overlaps mean corrupt offsets.
+        ranges.sort_unstable();
+        ranges.dedup();
+        if let Some(pair) = ranges.windows(2).find(|pair| pair[1].0 < pair[0].1) {
+            return Err(TiffError::MalformedFile {
+                reason: format!(
+                    "image data blocks {}..{} and {}..{} overlap",
+                    pair[0].0, pair[0].1, pair[1].0, pair[1].1
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Heuristically check whether the file follows Cloud-Optimized GeoTIFF layout
+    ///
+    /// A COG is laid out so a client can fetch the directories with one
+    /// small range request and then individual tiles on demand. This checks:
+    /// - every image is tiled
+    /// - images are ordered by non-increasing size (full resolution first)
+    /// - all IFDs are stored before any tile data
+    /// - tiles within each image are stored in increasing offset order
+    /// - lower-resolution levels store their tile data before higher ones
+    ///
+    /// This is a structural heuristic, not a full COG validation, and it
+    /// doesn't decode any pixel data.
+    pub fn is_cog_layout(&self) -> Result<bool> {
+        if self.ifds.is_empty() {
+            return Ok(false);
+        }
+
+        let endian = self.endianness();
+        let mut levels = Vec::with_capacity(self.ifds.len());
+        for ifd in &self.ifds {
+            if !ifd.is_tiled(&self.reader, endian)? {
+                return Ok(false);
+            }
+            let width = ifd.image_width(&self.reader, endian)?.unwrap_or(0);
+            let height = ifd.image_height(&self.reader, endian)?.unwrap_or(0);
+            let ranges = ifd.image_data_ranges(&self.reader, endian)?;
+            if ranges.windows(2).any(|pair| pair[1].0 < pair[0].0) {
+                return Ok(false);
+            }
+            levels.push((width as u64 * height as u64, ranges));
+        }
+
+        let last_ifd = self.ifd_offsets().into_iter().max().unwrap_or(0);
+        let first_data = levels
+            .iter()
+            .flat_map(|(_, ranges)| ranges.iter().map(|&(offset, _)| offset))
+            .min();
+        if first_data.is_some_and(|first| first < last_ifd) {
+            return Ok(false);
+        }
+
+        for pair in levels.windows(2) {
+            let (larger, smaller) = (&pair[0], &pair[1]);
+            if smaller.0 > larger.0 {
+                return Ok(false);
+            }
+            if smaller.0 < larger.0
+                && let (Some(big), Some(small)) = (larger.1.first(), smaller.1.first())
+                && small.0 > big.0
+            {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
 }
 
 impl TiffFile<InMemorySource> {
@@ -147,4 +982,630 @@ impl TiffFile<InMemorySource> {
         let reader = TiffReader::new(source);
         Self::from_reader(reader)
     }
-}
\ No newline at end of file
+
+    /// Create from in-memory data, validating the whole structure up front
+    ///
+    /// This is the loader for untrusted input. Where `from_bytes` is lenient
+    /// and leaves problems to surface when data is read, this returns the
+    /// first structural error found by these checks, in order:
+    /// 1. The main IFD chain must not loop back on itself or hold more
+    ///    than [`Self::DEFAULT_MAX_IFDS`] directories (checked before the
+    ///    IFDs are parsed).
+    /// 2. Every IFD must have ImageWidth, ImageLength and a complete set
+    ///    of strip or tile offsets and byte counts.
+    /// 3. Every out-of-line tag value must lie within the file, and
+    ///    XResolution/YResolution must be RATIONAL (not SRATIONAL).
+    /// 4. Every strip and tile must lie within the file.
+    /// 5. No two strips or tiles may partially overlap (blocks that share
+    ///    the exact same range are allowed).
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` describing the first failed check, or any
+    /// error `from_bytes` would return
+    pub fn from_bytes_validated(data: Vec<u8>) -> Result<Self> {
+        let mut reader = TiffReader::new(InMemorySource::new(data));
+        let header = reader.read_header()?;
+        reader.seek(0)?;
+
+        let mut guard = IfdChainGuard::new(Self::DEFAULT_MAX_IFDS);
+        let mut offset = header.ifd_offset as usize;
+        while offset != 0 {
+            guard.visit(offset)?;
+            offset = reader.read_ifd_next_offset(offset, header.endianness())?;
+        }
+
+        let file = Self::from_reader(reader)?;
+        file.validate_structure()?;
+        Ok(file)
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl TiffFile<GzipSource> {
+    /// Create from a gzip-compressed TIFF file (`.tif.gz`)
+    ///
+    /// The whole file is decompressed into memory up front.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the gzip wrapper is invalid, or any error
+    /// `from_bytes` would return for the decompressed data
+    pub fn from_gzip(data: &[u8]) -> Result<Self> {
+        Self::from_reader(TiffReader::new(GzipSource::new(data)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tags::tags;
+    use crate::testutil::{TestTiff, raw_le_ifd};
+
+    /// Build a one-strip 4x2 grayscale file whose strip claims `byte_count` bytes
+    fn single_strip_file(byte_count: u32) -> Vec<u8> {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .short(tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[byte_count]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_complete_file_is_not_truncated() {
+        let tiff = TiffFile::from_bytes(single_strip_file(8)).unwrap();
+        assert_eq!(tiff.image_count(), 1);
+        assert!(tiff.is_valid().unwrap());
+        assert!(!tiff.is_truncated().unwrap());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_from_gzip() {
+        let plain = single_strip_file(8);
+        let tiff = TiffFile::from_gzip(&crate::codec::gzip::gzip_stored(&plain)).unwrap();
+        assert_eq!(tiff.image_count(), 1);
+        assert!(tiff.is_valid().unwrap());
+        assert_eq!(tiff.reader.len(), plain.len());
+
+        assert!(matches!(TiffFile::from_gzip(&plain), Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_strip_past_end_is_truncated() {
+        let tiff = TiffFile::from_bytes(single_strip_file(100_000)).unwrap();
+        assert!(tiff.is_truncated().unwrap());
+    }
+
+    /// Build a two-level tiled pyramid: 32x32 full resolution and a 16x16 overview
+    fn pyramid_file(ifds_first: bool) -> Vec<u8> {
+        let mut tiff = TestTiff::new(Endian::Little);
+        if ifds_first {
+            tiff.reserve_ifd_area(256);
+        }
+        let overview = tiff.push_data(&[1; 256]);
+        let full = [
+            tiff.push_data(&[2; 256]),
+            tiff.push_data(&[3; 256]),
+            tiff.push_data(&[4; 256]),
+            tiff.push_data(&[5; 256]),
+        ];
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[32])
+            .short(tags::IMAGE_LENGTH, &[32])
+            .short(tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::TILE_WIDTH, &[16])
+            .short(tags::TILE_LENGTH, &[16])
+            .long(tags::TILE_OFFSETS, &full)
+            .long(tags::TILE_BYTE_COUNTS, &[256; 4]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[16])
+            .short(tags::IMAGE_LENGTH, &[16])
+            .short(tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::TILE_WIDTH, &[16])
+            .short(tags::TILE_LENGTH, &[16])
+            .long(tags::TILE_OFFSETS, &[overview])
+            .long(tags::TILE_BYTE_COUNTS, &[256]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_cog_layout_detected() {
+        let tiff = TiffFile::from_bytes(pyramid_file(true)).unwrap();
+        assert_eq!(tiff.image_count(), 2);
+        assert!(tiff.is_cog_layout().unwrap());
+    }
+
+    #[test]
+    fn test_ifds_after_data_is_not_cog() {
+        let tiff = TiffFile::from_bytes(pyramid_file(false)).unwrap();
+        assert!(!tiff.is_cog_layout().unwrap());
+    }
+
+    #[test]
+    fn test_stripped_file_is_not_cog() {
+        let tiff = TiffFile::from_bytes(single_strip_file(8)).unwrap();
+        assert!(!tiff.is_cog_layout().unwrap());
+    }
+
+    #[test]
+    fn test_field_types_used() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd()
+            .long(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .rational(tags::X_RESOLUTION, &[(72, 1)])
+            .raw(60000, 99, 1, vec![0; 4]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .double(tags::MODEL_PIXEL_SCALE, &[1.0, 1.0, 0.0]);
+        let tiff = TiffFile::from_bytes(tiff.build()).unwrap();
+
+        assert_eq!(
+            tiff.field_types_used(),
+            vec![FieldType::Short, FieldType::Long, FieldType::Rational, FieldType::Double]
+        );
+    }
+
+    #[test]
+    fn test_from_reader_first_only() {
+        let data = pyramid_file(false);
+        let full = TiffFile::from_bytes(data.clone()).unwrap();
+        assert_eq!(full.image_count(), 2);
+
+        let first = TiffFile::from_reader_first_only(TiffReader::new(InMemorySource::new(data))).unwrap();
+        assert_eq!(first.image_count(), 1);
+        let info = first.main_image_info().unwrap().unwrap();
+        assert_eq!((info.width, info.height), (32, 32));
+    }
+
+    #[test]
+    fn test_self_referencing_ifd_is_rejected() {
+        let mut data = single_strip_file(8);
+        let endian = Endian::Little;
+        let ifd_offset = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+        let entry_count = u16::from_le_bytes([data[ifd_offset], data[ifd_offset + 1]]) as usize;
+        let link = ifd_offset + 2 + entry_count * 12;
+        data[link..link + 4].copy_from_slice(&crate::testutil::u32_bytes(endian, ifd_offset as u32));
+
+        match TiffFile::from_bytes(data) {
+            Err(TiffError::MalformedFile { reason }) => assert_eq!(reason, "IFD points to itself"),
+            other => panic!("Expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_ifd_cycle_is_rejected() {
+        // Two pages whose second IFD links back to the first
+        let mut data = pyramid_file(false);
+        let file = TiffFile::from_bytes(data.clone()).unwrap();
+        let offsets = file.ifd_offsets();
+        let last = *offsets.last().unwrap();
+        let entry_count = u16::from_le_bytes([data[last], data[last + 1]]) as usize;
+        let link = last + 2 + entry_count * 12;
+        data[link..link + 4].copy_from_slice(&(offsets[0] as u32).to_le_bytes());
+
+        match TiffFile::from_bytes(data) {
+            Err(TiffError::MalformedFile { reason }) => {
+                assert_eq!(reason, format!("IFD chain loops back to offset {}", offsets[0]));
+            }
+            other => panic!("Expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_from_reader_lenient() {
+        let mut data = b"II\x2A\x00\x08\x00\x00\x00".to_vec();
+        data.extend(raw_le_ifd(&[
+            (tags::IMAGE_WIDTH, 3, 1, 5),
+            (40000, 99, 1, 0),
+            (tags::IMAGE_DESCRIPTION, 2, 20, 10_000),
+        ]));
+        // Point the IFD's next link back at itself
+        let link = data.len() - 4;
+        data[link..].copy_from_slice(&8u32.to_le_bytes());
+
+        assert!(TiffFile::from_bytes(data.clone()).is_err());
+        let (file, warnings) = TiffFile::from_reader_lenient(TiffReader::new(InMemorySource::new(data))).unwrap();
+        assert_eq!(file.image_count(), 1);
+        let ifd = file.main_ifd().unwrap();
+        assert_eq!(ifd.len(), 1);
+        assert_eq!(ifd.image_width(&file.reader, file.endianness()).unwrap(), Some(5));
+
+        assert_eq!(warnings.len(), 3);
+        assert!(matches!(warnings[0], TiffError::InvalidFieldType { found: 99 }));
+        assert!(matches!(&warnings[1], TiffError::MalformedFile { reason } if reason.contains("past the end")));
+        assert!(matches!(&warnings[2], TiffError::MalformedFile { reason } if reason.contains("loops back")));
+
+        // A clean file reads the same as with from_reader, without warnings
+        let data = pyramid_file(false);
+        let strict = TiffFile::from_bytes(data.clone()).unwrap();
+        let (file, warnings) = TiffFile::from_reader_lenient(TiffReader::new(InMemorySource::new(data))).unwrap();
+        assert_eq!(file.image_count(), strict.image_count());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_ifds_lazy() {
+        let data = pyramid_file(false);
+        let eager = TiffFile::from_bytes(data.clone()).unwrap();
+        let file = TiffFile::from_reader_first_only(TiffReader::new(InMemorySource::new(data.clone()))).unwrap();
+        assert_eq!(file.image_count(), 1);
+
+        let mut lazy = file.ifds_lazy();
+        let first = lazy.next().unwrap().unwrap();
+        assert_eq!(first.entry_descriptors(), eager.ifds[0].entry_descriptors());
+        let rest: Vec<_> = lazy.collect::<Result<_>>().unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].entry_descriptors(), eager.ifds[1].entry_descriptors());
+
+        // Same looping chain as test_ifd_cycle_is_rejected
+        let mut data = data;
+        let offsets = eager.ifd_offsets();
+        let last = *offsets.last().unwrap();
+        let entry_count = u16::from_le_bytes([data[last], data[last + 1]]) as usize;
+        let link = last + 2 + entry_count * 12;
+        data[link..link + 4].copy_from_slice(&(offsets[0] as u32).to_le_bytes());
+        let file = TiffFile::from_reader_first_only(TiffReader::new(InMemorySource::new(data))).unwrap();
+        let results: Vec<_> = file.ifds_lazy().collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(&results[2], Err(TiffError::MalformedFile { .. })));
+    }
+
+    #[test]
+    fn test_ifd_chain_length_limit() {
+        let data = pyramid_file(false);
+        let reader = || TiffReader::new(InMemorySource::new(data.clone()));
+        assert_eq!(TiffFile::from_reader_with_max_ifds(reader(), 2).unwrap().image_count(), 2);
+        match TiffFile::from_reader_with_max_ifds(reader(), 1) {
+            Err(TiffError::MalformedFile { reason }) => {
+                assert_eq!(reason, "IFD chain exceeds the limit of 1 IFDs");
+            }
+            other => panic!("Expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_file_info() {
+        let tiff = TiffFile::from_bytes(pyramid_file(false)).unwrap();
+        let info = tiff.file_info();
+        assert_eq!(info.endian, Endian::Little);
+        assert!(!info.is_bigtiff);
+        assert_eq!(info.ifd_count, 2);
+        assert_eq!(info.first_ifd_offset, tiff.header.ifd_offset);
+        assert_eq!(info.to_string(), "Little-endian classic TIFF, 2 images");
+
+        let single = TiffFile::from_bytes(single_strip_file(8)).unwrap();
+        assert_eq!(single.file_info().to_string(), "Little-endian classic TIFF, 1 image");
+    }
+
+    #[test]
+    fn test_pages_sorted_by_number() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).short(tags::PAGE_NUMBER, &[2, 3]);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).short(tags::PAGE_NUMBER, &[0, 3]);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[1]).short(tags::PAGE_NUMBER, &[1, 3]);
+        let tiff = TiffFile::from_bytes(tiff.build()).unwrap();
+
+        assert_eq!(tiff.get_ifd(2).unwrap().page_number(&tiff.reader, Endian::Little).unwrap(), Some((0, 3)));
+        assert_eq!(tiff.pages_sorted_by_number().unwrap(), vec![2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn test_pages_without_page_numbers_keep_file_order() {
+        let tiff = TiffFile::from_bytes(pyramid_file(false)).unwrap();
+        assert_eq!(tiff.pages_sorted_by_number().unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_dry_run_decode() {
+        assert!(TiffFile::from_bytes(single_strip_file(8)).unwrap().dry_run_decode(0).is_ok());
+
+        let short_strip = TiffFile::from_bytes(single_strip_file(4)).unwrap();
+        assert!(matches!(short_strip.dry_run_decode(0), Err(TiffError::InsufficientData { .. })));
+
+        let truncated = TiffFile::from_bytes(single_strip_file(100_000)).unwrap();
+        assert!(truncated.dry_run_decode(0).is_err());
+        assert!(matches!(truncated.dry_run_decode(1), Err(TiffError::OutOfBounds { index: 1, max: 1 })));
+    }
+
+    #[test]
+    fn test_dry_run_decode_unsupported_compression() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .short(tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::COMPRESSION, &[34712])
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[8]);
+        let file = TiffFile::from_bytes(tiff.build()).unwrap();
+        assert!(matches!(file.dry_run_decode(0), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_walk_all_ifds() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let interop = tiff.push_data(&raw_le_ifd(&[(1, 2, 4, u32::from_le_bytes(*b"R98\0"))]));
+        let exif = tiff.push_data(&raw_le_ifd(&[(tags::INTEROPERABILITY_IFD, 13, 1, interop)]));
+        let sub0 = tiff.push_data(&raw_le_ifd(&[(tags::IMAGE_WIDTH, 3, 1, 8)]));
+        // The second SubIFD points back at the EXIF IFD; it must not be visited twice
+        let sub1 = tiff.push_data(&raw_le_ifd(&[(tags::EXIF_IFD, 4, 1, exif)]));
+        let gps = tiff.push_data(&raw_le_ifd(&[(0, 1, 4, 0x0000_0202)]));
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[16])
+            .long(tags::SUB_IFDS, &[sub0, sub1])
+            .long(tags::EXIF_IFD, &[exif]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .raw(tags::GPS_IFD, 13, 1, gps.to_le_bytes().to_vec());
+        let file = TiffFile::from_bytes(tiff.build()).unwrap();
+
+        let paths = file.walk_all_ifds().unwrap();
+        let names: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "[0]",
+                "[0, SubIFD, 0]",
+                "[0, SubIFD, 1]",
+                "[0, SubIFD, 1, Exif]",
+                "[0, SubIFD, 1, Exif, Interop]",
+                "[1]",
+                "[1, GPS]",
+            ]
+        );
+        assert_eq!(paths[1].offset, sub0 as usize);
+        assert_eq!(paths[6].ifd.len(), 1);
+    }
+
+    #[test]
+    fn test_walk_all_ifds_limits_nesting() {
+        /// A main IFD with a chain of `depth` EXIF pointers below it
+        fn nested_file(depth: usize) -> Vec<u8> {
+            let mut tiff = TestTiff::new(Endian::Little);
+            let mut innermost = tiff.push_data(&raw_le_ifd(&[(tags::IMAGE_WIDTH, 3, 1, 1)]));
+            for _ in 1..depth {
+                innermost = tiff.push_data(&raw_le_ifd(&[(tags::EXIF_IFD, 4, 1, innermost)]));
+            }
+            tiff.add_ifd().short(tags::IMAGE_WIDTH, &[4]).long(tags::EXIF_IFD, &[innermost]);
+            tiff.build()
+        }
+
+        let depth = TiffFile::<InMemorySource>::MAX_SUB_IFD_DEPTH;
+        let file = TiffFile::from_bytes(nested_file(depth)).unwrap();
+        let paths = file.walk_all_ifds().unwrap();
+        assert_eq!(paths.len(), depth + 1);
+        assert_eq!(paths[depth].segments.len(), depth + 1);
+
+        let file = TiffFile::from_bytes(nested_file(depth + 1)).unwrap();
+        assert!(matches!(file.walk_all_ifds(), Err(TiffError::MalformedFile { .. })));
+
+        // Deep enough to overflow the stack if the walk recursed
+        let file = TiffFile::from_bytes(nested_file(20_000)).unwrap();
+        match file.walk_all_ifds() {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.contains("nested deeper")),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_walk_all_ifds_rejects_bad_pointer_type() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[4]).short(tags::EXIF_IFD, &[100]);
+        let file = TiffFile::from_bytes(tiff.build()).unwrap();
+        assert!(matches!(file.walk_all_ifds(), Err(TiffError::InvalidTag { tag: 34665, .. })));
+    }
+
+    #[test]
+    fn test_from_bytes_validated_accepts_good_file() {
+        assert!(TiffFile::from_bytes_validated(single_strip_file(8)).is_ok());
+        assert!(TiffFile::from_bytes_validated(pyramid_file(true)).is_ok());
+    }
+
+    #[test]
+    fn test_from_bytes_validated_rejects_structural_errors() {
+        let reason = |data| match TiffFile::from_bytes_validated(data) {
+            Err(TiffError::MalformedFile { reason }) => reason,
+            other => panic!("expected MalformedFile, got {other:?}"),
+        };
+
+        assert!(reason(single_strip_file(100_000)).contains("past the end of the file"));
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        tiff.add_ifd().short(tags::IMAGE_WIDTH, &[4]);
+        assert!(reason(tiff.build()).contains("IFD 0 is missing"));
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .long(tags::STRIP_OFFSETS, &[strip, strip + 2])
+            .long(tags::STRIP_BYTE_COUNTS, &[4, 4]);
+        assert!(reason(tiff.build()).contains("overlap"));
+
+        // An IFD whose next link points back at itself
+        let mut data = single_strip_file(8);
+        let first = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+        let count = u16::from_le_bytes([data[first], data[first + 1]]) as usize;
+        let link = first + 2 + count * 12;
+        data[link..link + 4].copy_from_slice(&(first as u32).to_le_bytes());
+        assert!(reason(data).contains("loops back"));
+
+        // One more empty IFD than the chain limit, packed 6 bytes apart
+        let mut data = b"II*\0".to_vec();
+        data.extend_from_slice(&8u32.to_le_bytes());
+        let ifds = TiffFile::<InMemorySource>::DEFAULT_MAX_IFDS + 1;
+        for index in 1..=ifds {
+            let next = if index == ifds { 0 } else { 8 + index * 6 };
+            data.extend_from_slice(&0u16.to_le_bytes());
+            data.extend_from_slice(&(next as u32).to_le_bytes());
+        }
+        assert_eq!(reason(data), "IFD chain exceeds the limit of 65536 IFDs");
+    }
+
+    #[test]
+    fn test_srational_resolution_lenient_and_validated() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .raw(tags::X_RESOLUTION, 10, 1, [72i32.to_le_bytes(), 1i32.to_le_bytes()].concat())
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[8]);
+        let data = tiff.build();
+
+        let file = TiffFile::from_bytes(data.clone()).unwrap();
+        let ifd = file.main_ifd().unwrap();
+        assert_eq!(ifd.x_resolution(&file.reader, file.endianness()).unwrap(), Some(72.0));
+
+        match TiffFile::from_bytes_validated(data) {
+            Err(TiffError::MalformedFile { reason }) => {
+                assert_eq!(reason, "XResolution in IFD 0 is SRational; the spec requires RATIONAL");
+            }
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_detect_endian_anomalies() {
+        assert!(TiffFile::from_bytes(single_strip_file(8)).unwrap().detect_endian_anomalies().unwrap().is_empty());
+
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .raw(tags::IMAGE_WIDTH, 4, 1, 4u32.to_be_bytes().to_vec())
+            .short(tags::IMAGE_LENGTH, &[2])
+            .raw(tags::BITS_PER_SAMPLE, 3, 1, 8u16.to_be_bytes().to_vec())
+            .short(tags::COMPRESSION, &[1])
+            .raw(tags::X_RESOLUTION, 5, 1, 16u32.to_be_bytes().to_vec())
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[8]);
+        let file = TiffFile::from_bytes(tiff.build()).unwrap();
+
+        let anomalies = file.detect_endian_anomalies().unwrap();
+        assert_eq!(anomalies.len(), 3, "{anomalies:?}");
+        assert_eq!(anomalies[0], "IFD 0: ImageWidth is 67108864, but 4 if byte-swapped");
+        assert_eq!(anomalies[1], "IFD 0: BitsPerSample is 2048, but 8 if byte-swapped");
+        assert!(anomalies[2].starts_with("IFD 0: XResolution (tag 282) value offset"));
+    }
+
+    #[test]
+    fn test_data_extent() {
+        let data = single_strip_file(8);
+        let len = data.len();
+        let file = TiffFile::from_bytes(data.clone()).unwrap();
+        assert_eq!(file.data_extent().unwrap(), (0, len));
+
+        // Appended bytes are outside the extent
+        let mut padded = data;
+        padded.extend_from_slice(&[0xAA; 100]);
+        let file = TiffFile::from_bytes(padded).unwrap();
+        assert_eq!(file.data_extent().unwrap(), (0, len));
+
+        // A strip that runs past the end extends the range beyond the file
+        let file = TiffFile::from_bytes(single_strip_file(100_000)).unwrap();
+        assert!(file.data_extent().unwrap().1 > file.reader.len());
+    }
+
+    #[test]
+    fn test_scan_for_ifds_finds_orphaned_directory() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0u8; 8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[4])
+            .short(tags::IMAGE_LENGTH, &[2])
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[8]);
+        tiff.add_ifd()
+            .short(tags::IMAGE_WIDTH, &[2])
+            .short(tags::IMAGE_LENGTH, &[1])
+            .long(tags::STRIP_OFFSETS, &[strip])
+            .long(tags::STRIP_BYTE_COUNTS, &[2]);
+        let mut data = tiff.build();
+
+        let file = TiffFile::from_bytes(data.clone()).unwrap();
+        let first = file.header.ifd_offset as usize;
+        let second = file.ifds[0].next_ifd_offset;
+        assert!(file.scan_for_ifds().unwrap().is_empty());
+
+        // Cut the chain after the first IFD: the second becomes an orphan
+        let link = first + 2 + 4 * 12;
+        data[link..link + 4].copy_from_slice(&0u32.to_le_bytes());
+        let file = TiffFile::from_bytes(data).unwrap();
+        assert_eq!(file.image_count(), 1);
+        assert_eq!(file.scan_for_ifds().unwrap(), vec![second]);
+    }
+
+    /// Build a little-endian BigTIFF with one 4x2 grayscale strip
+    fn bigtiff_file() -> Vec<u8> {
+        let entry = |tag: u16, field_type: u16, value: u64| {
+            let mut bytes = tag.to_le_bytes().to_vec();
+            bytes.extend_from_slice(&field_type.to_le_bytes());
+            bytes.extend_from_slice(&1u64.to_le_bytes());
+            bytes.extend_from_slice(&value.to_le_bytes());
+            bytes
+        };
+        // 16-byte header, then 8 + 5 * 20 + 8 bytes of IFD
+        let strip = 16 + 116;
+        let mut data = vec![b'I', b'I', 43, 0, 8, 0, 0, 0];
+        data.extend_from_slice(&16u64.to_le_bytes());
+        data.extend_from_slice(&5u64.to_le_bytes());
+        data.extend(entry(tags::IMAGE_WIDTH, 3, 4));
+        data.extend(entry(tags::IMAGE_LENGTH, 3, 2));
+        data.extend(entry(tags::BITS_PER_SAMPLE, 3, 8));
+        data.extend(entry(tags::STRIP_OFFSETS, 16, strip));
+        data.extend(entry(tags::STRIP_BYTE_COUNTS, 4, 8));
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data
+    }
+
+    #[test]
+    fn test_bigtiff_file() {
+        let file = TiffFile::from_bytes(bigtiff_file()).unwrap();
+        assert_eq!(file.file_info().to_string(), "Little-endian BigTIFF, 1 image");
+        assert_eq!(file.file_info().first_ifd_offset, 16);
+
+        let ifd = file.main_ifd().unwrap();
+        assert_eq!(ifd.strip_offsets(&file.reader, Endian::Little).unwrap(), Some(vec![132]));
+        assert_eq!(ifd.read_image(&file.reader, Endian::Little).unwrap(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(file.is_valid().unwrap());
+        assert!(!file.is_truncated().unwrap());
+        assert_eq!(file.data_extent().unwrap().1, 140);
+        assert_eq!(file.walk_all_ifds().unwrap().len(), 1);
+
+        assert!(TiffFile::from_bytes_validated(bigtiff_file()).is_ok());
+        assert!(matches!(file.scan_for_ifds(), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_canonical_text() {
+        let file = TiffFile::from_bytes(single_strip_file(8)).unwrap();
+        assert_eq!(
+            file.to_canonical_text().unwrap(),
+            "Little-endian classic TIFF, 1 image\n\
+             IFD [0]\n\
+             \x20 256 ImageWidth Short[1] = 4\n\
+             \x20 257 ImageLength Short[1] = 2\n\
+             \x20 258 BitsPerSample Short[1] = 8\n\
+             \x20 273 StripOffsets Long[1] = <offsets>\n\
+             \x20 279 StripByteCounts Long[1] = 8\n"
+        );
+    }
+
+    #[test]
+    fn test_canonical_text_ignores_layout() {
+        // The same structure with IFDs before and after the data
+        let before = TiffFile::from_bytes(pyramid_file(true)).unwrap();
+        let after = TiffFile::from_bytes(pyramid_file(false)).unwrap();
+        assert_ne!(before.ifd_offsets(), after.ifd_offsets());
+        assert_eq!(before.to_canonical_text().unwrap(), after.to_canonical_text().unwrap());
+    }
+}
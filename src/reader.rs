@@ -7,13 +7,19 @@
 //! Architecture:
 //! - TiffDataSource: Trait for pluggable data sources (memory, mmap, network, etc.)
 //! - InMemorySource: Simple data source for small files loaded into memory  
+//! - FileSource: Files read on demand with seek + read
+//! - MmapSource: Memory-mapped files (`memmap2` feature)
 //! - TiffReader: Generic reader that works with any data source
-//! - TiffImageReader: (Future) Higher-level reader with automatic decompression
+//! - TiffImageReader: Higher-level reader that decodes an IFD's image data
 
 use crate::{
+    codec::{CodecRegistry, CompressionCodec},
     error::{Result, TiffError},
     header::{Endian, TiffHeader},
+    ifd::ImageFileDirectory,
+    tags::{self, Compression, FillOrder, Orientation, PhotometricInterpretation, PlanarConfiguration, SampleFormat},
 };
+use crate::prelude::*;
 
 /// Trait for TIFF data sources - abstracts where the data comes from
 ///
@@ -40,7 +46,7 @@ pub trait TiffDataSource {
     /// Vector containing the read bytes
     ///
     /// # Errors
-    /// Returns error if offset + count exceeds data bounds
+    /// Returns error if offset + count exceeds data bounds or overflows
     fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>>;
 
     /// Read a single byte at a specific offset
@@ -68,6 +74,24 @@ pub trait TiffDataSource {
     }
 }
 
+/// Check that `count` bytes at `offset` lie within `len` bytes of data
+///
+/// # Returns
+/// The byte range to read
+///
+/// # Errors
+/// Returns `OutOfBounds` if the range ends past `len`, including when
+/// `offset + count` overflows (reported as `usize::MAX`)
+fn checked_range(offset: usize, count: usize, len: usize) -> Result<core::ops::Range<usize>> {
+    match offset.checked_add(count) {
+        Some(end) if end <= len => Ok(offset..end),
+        end => Err(TiffError::OutOfBounds {
+            index: end.unwrap_or(usize::MAX),
+            max: len,
+        }),
+    }
+}
+
 /// In-memory data source - holds data in a `Vec<u8>`
 ///
 /// This is the simplest data source, suitable for small to medium files
@@ -101,54 +125,202 @@ impl TiffDataSource for InMemorySource {
     }
 
     fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
-        if offset + count > self.data.len() {
-            return Err(TiffError::OutOfBounds {
-                index: offset + count,
-                max: self.data.len(),
-            });
-        }
-
-        Ok(self.data[offset..offset + count].to_vec())
+        let range = checked_range(offset, count, self.data.len())?;
+        Ok(self.data[range].to_vec())
     }
 
     // Optimized implementations for primitives (avoid allocation where possible)
     fn read_u8_at(&self, offset: usize) -> Result<u8> {
-        if offset + 1 > self.data.len() {
-            return Err(TiffError::OutOfBounds {
-                index: offset + 1,
-                max: self.data.len(),
-            });
-        }
-        Ok(self.data[offset])
+        let range = checked_range(offset, 1, self.data.len())?;
+        Ok(self.data[range.start])
     }
 
     fn read_u16_at(&self, offset: usize, endian: Endian) -> Result<u16> {
-        if offset + 2 > self.data.len() {
-            return Err(TiffError::OutOfBounds {
-                index: offset + 2,
-                max: self.data.len(),
-            });
-        }
-        let bytes = [self.data[offset], self.data[offset + 1]];
-        Ok(endian.read_u16(bytes))
+        let b = &self.data[checked_range(offset, 2, self.data.len())?];
+        Ok(endian.read_u16([b[0], b[1]]))
     }
 
     fn read_u32_at(&self, offset: usize, endian: Endian) -> Result<u32> {
-        if offset + 4 > self.data.len() {
-            return Err(TiffError::OutOfBounds {
-                index: offset + 4,
-                max: self.data.len(),
-            });
-        }
+        let b = &self.data[checked_range(offset, 4, self.data.len())?];
+        Ok(endian.read_u32([b[0], b[1], b[2], b[3]]))
+    }
+}
 
-        let bytes = [
-            self.data[offset],
-            self.data[offset + 1],
-            self.data[offset + 2],
-            self.data[offset + 3],
-        ];
+/// Gzip-wrapped data source - a `.tif.gz` file decompressed into memory
+///
+/// The whole file is inflated when the source is created; after that it
+/// behaves exactly like `InMemorySource`.
+#[cfg(feature = "gzip")]
+#[derive(Debug, Clone)]
+pub struct GzipSource {
+    inner: InMemorySource,
+}
+
+#[cfg(feature = "gzip")]
+impl GzipSource {
+    /// Decompress gzip data into a new source
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the data isn't a valid gzip file
+    pub fn new(compressed: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: InMemorySource::new(crate::codec::decompress_gzip(compressed)?),
+        })
+    }
+
+    /// Get a reference to the decompressed data
+    pub fn as_slice(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl TiffDataSource for GzipSource {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        self.inner.read_bytes_at(offset, count)
+    }
+
+    fn read_u8_at(&self, offset: usize) -> Result<u8> {
+        self.inner.read_u8_at(offset)
+    }
+
+    fn read_u16_at(&self, offset: usize, endian: Endian) -> Result<u16> {
+        self.inner.read_u16_at(offset, endian)
+    }
+
+    fn read_u32_at(&self, offset: usize, endian: Endian) -> Result<u32> {
+        self.inner.read_u32_at(offset, endian)
+    }
+}
+
+/// File-backed data source that seeks and reads on demand
+///
+/// Only the bytes asked for are read, so memory use stays small no matter
+/// how large the file is. Every read is a seek plus a read syscall, so
+/// wrap the file in `InMemorySource` or use `MmapSource` when many small
+/// reads make that too slow.
+///
+/// # Thread safety
+/// `TiffDataSource` reads through `&self`, but seeking a `File` needs
+/// `&mut`, so the file sits behind a `Mutex`. `FileSource` is `Send` and
+/// `Sync`; concurrent reads from several threads are safe but take turns
+/// on the lock.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FileSource {
+    file: std::sync::Mutex<std::fs::File>,
+    len: usize,
+}
+
+#[cfg(feature = "std")]
+impl FileSource {
+    /// Open a file for reading
+    ///
+    /// # Errors
+    /// Returns `Io` if the file can't be opened or its size read
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_file(std::fs::File::open(path)?)
+    }
+
+    /// Wrap an already-open file
+    ///
+    /// The file's length is read once here; later growth isn't seen.
+    ///
+    /// # Errors
+    /// Returns `Io` if the file's size can't be read
+    pub fn from_file(file: std::fs::File) -> Result<Self> {
+        let len = file.metadata()?.len() as usize;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+            len,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl TiffDataSource for FileSource {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        checked_range(offset, count, self.len)?;
+        // A poisoned lock only means another reader panicked mid-read;
+        // every read seeks first, so the file is still usable
+        let mut file = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        file.seek(SeekFrom::Start(offset as u64))?;
+        let mut buf = vec![0; count];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Memory-mapped data source for files too large to load into memory
+///
+/// The file is mapped read-only and `read_bytes_at` copies only the
+/// requested range out of the mapping; pages are loaded by the OS on
+/// demand.
+#[cfg(feature = "memmap2")]
+#[derive(Debug)]
+pub struct MmapSource {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "memmap2")]
+impl MmapSource {
+    /// Map a file read-only
+    ///
+    /// # Errors
+    /// Returns `Io` if the file can't be opened or mapped
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read-only. As with any mmap, another
+        // process truncating or rewriting the file while it is mapped can
+        // change or invalidate the bytes we see; callers reading files
+        // that may change underneath them should use a copying source.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self { map })
+    }
+
+    /// Get the mapped bytes
+    pub fn as_slice(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Borrow `count` bytes at `offset`, bounds-checked like `InMemorySource`
+    fn slice(&self, offset: usize, count: usize) -> Result<&[u8]> {
+        Ok(&self.map[checked_range(offset, count, self.map.len())?])
+    }
+}
+
+#[cfg(feature = "memmap2")]
+impl TiffDataSource for MmapSource {
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
+        Ok(self.slice(offset, count)?.to_vec())
+    }
+
+    fn read_u8_at(&self, offset: usize) -> Result<u8> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn read_u16_at(&self, offset: usize, endian: Endian) -> Result<u16> {
+        let b = self.slice(offset, 2)?;
+        Ok(endian.read_u16([b[0], b[1]]))
+    }
 
-        Ok(endian.read_u32(bytes))
+    fn read_u32_at(&self, offset: usize, endian: Endian) -> Result<u32> {
+        let b = self.slice(offset, 4)?;
+        Ok(endian.read_u32([b[0], b[1], b[2], b[3]]))
     }
 }
 
@@ -159,22 +331,71 @@ impl TiffDataSource for InMemorySource {
 /// the TiffDataSource trait.
 ///
 /// This reader handles the basic TIFF structure (headers, IFDs, tags) but
-/// does not handle image decompression. For automatic decompression, use
-/// TiffImageReader (future implementation).
+/// does not handle image decompression. For decoded pixel data, use
+/// TiffImageReader.
 #[derive(Debug)]
 pub struct TiffReader<T: TiffDataSource> {
     /// The data source (in-memory, memory-mapped, network, etc.)
     source: T,
     /// Current reading position for stateful operations
     position: usize,
+    /// Whether `read_header` found a BigTIFF header, which switches IFD
+    /// parsing to 8-byte counts and offsets
+    bigtiff: bool,
+    /// Whether non-UTF-8 ASCII text is an error instead of being decoded
+    /// as Latin-1
+    strict_ascii: bool,
+    /// Unique per reader, so IFD caches can tell readers apart
+    id: usize,
+}
+
+/// Source of `TiffReader::id` values
+static NEXT_READER_ID: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+/// Get an id no other reader has
+fn next_reader_id() -> usize {
+    NEXT_READER_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
 }
 
 impl<T: TiffDataSource> TiffReader<T> {
+    /// Largest tag value, in bytes, that value parsing will read
+    ///
+    /// Generous enough for large ICC profiles, XMP packets and offset
+    /// arrays, while keeping a crafted count from triggering a huge read.
+    pub const MAX_TAG_VALUE_BYTES: usize = 256 * 1024 * 1024;
+
     /// Create a new reader with the given data source
     pub fn new(source: T) -> Self {
         Self {
             source,
             position: 0,
+            bigtiff: false,
+            strict_ascii: false,
+            id: next_reader_id(),
+        }
+    }
+
+    /// Reject ASCII text that isn't valid UTF-8
+    ///
+    /// By default, ASCII tag values and strings that aren't valid UTF-8 are
+    /// decoded as Latin-1, one character per byte, since older software
+    /// often wrote accented characters that way. In strict mode they fail
+    /// with `InvalidString` instead.
+    pub fn with_strict_ascii(mut self, strict: bool) -> Self {
+        self.strict_ascii = strict;
+        self
+    }
+
+    /// Create a reader that parses IFDs in the given layout without
+    /// reading a header first
+    #[cfg(feature = "tokio")]
+    pub(crate) fn with_bigtiff(source: T, bigtiff: bool) -> Self {
+        Self {
+            source,
+            position: 0,
+            bigtiff,
+            strict_ascii: false,
+            id: next_reader_id(),
         }
     }
 
@@ -183,6 +404,12 @@ impl<T: TiffDataSource> TiffReader<T> {
         self.source.len()
     }
 
+    /// Identify the values this reader parses: which reader, and whether
+    /// ASCII is strict
+    pub(crate) fn cache_key(&self) -> (usize, bool) {
+        (self.id, self.strict_ascii)
+    }
+
     /// Check if the data is empty
     pub fn is_empty(&self) -> bool {
         self.source.is_empty()
@@ -213,7 +440,10 @@ impl<T: TiffDataSource> TiffReader<T> {
 
     /// Skip ahead by `count` bytes
     pub fn skip(&mut self, count: usize) -> Result<()> {
-        self.seek(self.position + count)
+        self.seek(self.position.checked_add(count).ok_or(TiffError::OutOfBounds {
+            index: usize::MAX,
+            max: self.source.len(),
+        })?)
     }
 
     /// Get remaining bytes from current position
@@ -251,6 +481,13 @@ impl<T: TiffDataSource> TiffReader<T> {
         Ok(value)
     }
 
+    /// Read a u64 and advance position
+    pub fn read_u64(&mut self, endian: Endian) -> Result<u64> {
+        let value = self.read_u64_at(self.position, endian)?;
+        self.position += 8;
+        Ok(value)
+    }
+
     /// Read exactly `count` bytes and advance position
     pub fn read_bytes(&mut self, count: usize) -> Result<Vec<u8>> {
         let value = self.source.read_bytes_at(self.position, count)?;
@@ -258,6 +495,18 @@ impl<T: TiffDataSource> TiffReader<T> {
         Ok(value)
     }
 
+    /// Read exactly `count` bytes and advance position, refusing large reads
+    ///
+    /// Use this when `count` comes from the file, so a crafted length
+    /// can't force an enormous allocation.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if `count` exceeds `max`, before reading anything
+    pub fn read_bytes_limited(&mut self, count: usize, max: usize) -> Result<Vec<u8>> {
+        check_read_limit(count, max)?;
+        self.read_bytes(count)
+    }
+
     // =============================================================================
     // Stateless reading methods (don't change position) - delegate to source
     // =============================================================================
@@ -277,6 +526,12 @@ impl<T: TiffDataSource> TiffReader<T> {
         self.source.read_u32_at(offset, endian)
     }
 
+    /// Read a u64 at a specific offset without changing position
+    pub fn read_u64_at(&self, offset: usize, endian: Endian) -> Result<u64> {
+        let b = self.source.read_bytes_at(offset, 8)?;
+        Ok(endian.read_u64([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
     /// Read bytes at a specific offset without changing position
     pub fn read_bytes_at(&self, offset: usize, count: usize) -> Result<Vec<u8>> {
         self.source.read_bytes_at(offset, count)
@@ -311,11 +566,8 @@ impl<T: TiffDataSource> TiffReader<T> {
         count: usize,
         endian: Endian,
     ) -> Result<Vec<u16>> {
-        let mut result = Vec::with_capacity(count);
-        for i in 0..count {
-            result.push(self.source.read_u16_at(offset + i * 2, endian)?);
-        }
-        Ok(result)
+        let bytes = self.read_bytes_at(offset, count.saturating_mul(2))?;
+        Ok(bytes.chunks_exact(2).map(|b| endian.read_u16([b[0], b[1]])).collect())
     }
 
     /// Read an array of u32s at a specific offset
@@ -325,11 +577,8 @@ impl<T: TiffDataSource> TiffReader<T> {
         count: usize,
         endian: Endian,
     ) -> Result<Vec<u32>> {
-        let mut result = Vec::with_capacity(count);
-        for i in 0..count {
-            result.push(self.source.read_u32_at(offset + i * 4, endian)?);
-        }
-        Ok(result)
+        let bytes = self.read_bytes_at(offset, count.saturating_mul(4))?;
+        Ok(bytes.chunks_exact(4).map(|b| endian.read_u32([b[0], b[1], b[2], b[3]])).collect())
     }
 
     // =============================================================================
@@ -337,9 +586,31 @@ impl<T: TiffDataSource> TiffReader<T> {
     // =============================================================================
 
     /// Read a TIFF header from the current position and advance
+    ///
+    /// A BigTIFF header switches this reader into BigTIFF mode, so later
+    /// IFDs are parsed with 8-byte counts and offsets.
     pub fn read_header(&mut self) -> Result<TiffHeader> {
-        let header_bytes = self.read_bytes(TiffHeader::SIZE)?;
-        TiffHeader::parse(&header_bytes)
+        let available = self.source.len().saturating_sub(self.position);
+        let header_bytes = self.read_bytes_at(self.position, available.min(TiffHeader::BIGTIFF_SIZE))?;
+        let header = TiffHeader::parse(&header_bytes)?;
+        self.position += header.size();
+        self.bigtiff = header.is_bigtiff();
+        Ok(header)
+    }
+
+    /// Check if this reader parses IFDs in BigTIFF layout
+    ///
+    /// Set by `read_header`; a reader that hasn't read a header assumes
+    /// classic TIFF.
+    pub fn is_bigtiff(&self) -> bool {
+        self.bigtiff
+    }
+
+    /// Check if this reader rejects ASCII text that isn't valid UTF-8
+    ///
+    /// Set with `with_strict_ascii`.
+    pub fn is_strict_ascii(&self) -> bool {
+        self.strict_ascii
     }
 
     /// Read a null-terminated ASCII string and advance position
@@ -361,9 +632,21 @@ impl<T: TiffDataSource> TiffReader<T> {
             bytes.push(byte);
         }
 
-        String::from_utf8(bytes).map_err(|_| TiffError::InvalidString {
-            context: "ASCII string".to_string(),
-        })
+        self.decode_ascii(bytes, "ASCII string")
+    }
+
+    /// Decode ASCII bytes as UTF-8, falling back to Latin-1 unless strict
+    ///
+    /// # Errors
+    /// Returns `InvalidString` with `context` for non-UTF-8 bytes in strict mode
+    pub(crate) fn decode_ascii(&self, bytes: Vec<u8>, context: &str) -> Result<String> {
+        match String::from_utf8(bytes) {
+            Ok(string) => Ok(string),
+            Err(_) if self.strict_ascii => Err(TiffError::InvalidString {
+                context: context.to_string(),
+            }),
+            Err(e) => Ok(e.as_bytes().iter().map(|&b| b as char).collect()),
+        }
     }
 
     /// Get access to the underlying data source (for advanced usage)
@@ -372,123 +655,1601 @@ impl<T: TiffDataSource> TiffReader<T> {
     }
 }
 
+/// Check a file-supplied read length against a cap
+pub(crate) fn check_read_limit(count: usize, max: usize) -> Result<()> {
+    if count > max {
+        return Err(TiffError::MalformedFile {
+            reason: format!("refusing to read {count} bytes, more than the {max}-byte limit"),
+        });
+    }
+    Ok(())
+}
+
 // =============================================================================
-// Future: Image decompression layer
+// Image decoding layer
 // =============================================================================
 
-// TODO: Add these when ready for decompression support
-//
-// pub trait Decompressor {
-//     fn decompress(&self, data: &[u8]) -> Result<Vec<u8>>;
-//     fn name(&self) -> &'static str;
-// }
-//
-// pub struct TiffImageReader<T: TiffDataSource> {
-//     reader: TiffReader<T>,
-//     decompressor: Box<dyn Decompressor>,
-//     compression: Compression,
-//     raw_data_mode: bool,
-//     // ... layout info
-// }
-//
-// impl<T: TiffDataSource> TiffImageReader<T> {
-//     pub fn new(reader: TiffReader<T>, ifd: &ImageFileDirectory) -> Result<Self> {
-//         // Automatically detect compression from IFD tags
-//         // Create appropriate decompressor
-//         // Extract image layout info
-//     }
-//
-//     pub fn with_raw_data(mut self, raw: bool) -> Self { ... }
-//     pub fn with_decompressor(mut self, decompressor: Box<dyn Decompressor>) -> Self { ... }
-//     pub fn read_strip(&self, strip_index: usize) -> Result<Vec<u8>> { ... }
-//     pub fn read_tile(&self, tile_x: u32, tile_y: u32) -> Result<Vec<u8>> { ... }
-// }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn create_test_data() -> Vec<u8> {
-        vec![
-            // TIFF header (8 bytes)
-            0x49, 0x49, // "II" - little endian
-            0x2A, 0x00, // Magic number 42
-            0x08, 0x00, 0x00, 0x00, // IFD offset 8
-            // Extra data for testing
-            0x12, 0x34, 0x56, 0x78, // 4 more bytes
-        ]
+/// Expand 8-bit palette indices to 8-bit RGB triples
+///
+/// `map` is a ColorMap as returned by `ImageFileDirectory::color_map`; its
+/// 16-bit entries are scaled down to 8 bits. Indices of fewer than 8 bits
+/// must be unpacked to one byte each first.
+///
+/// # Returns
+/// Three bytes (red, green, blue) per index
+///
+/// # Errors
+/// Returns `InvalidTag` if the map doesn't have 2, 4, 16 or 256 entries
+/// (one per value of a 1, 2, 4 or 8-bit index), or `OutOfBounds` if an
+/// index has no entry
+pub fn expand_palette(indices: &[u8], map: &[(u16, u16, u16)]) -> Result<Vec<u8>> {
+    if !matches!(map.len(), 2 | 4 | 16 | 256) {
+        return Err(TiffError::InvalidTag {
+            tag: tags::tags::COLORMAP,
+            reason: format!("{} entries doesn't match a 1, 2, 4 or 8-bit index", map.len()),
+        });
     }
+    let mut rgb = Vec::with_capacity(indices.len() * 3);
+    for &index in indices {
+        let &(r, g, b) = map.get(index as usize).ok_or(TiffError::OutOfBounds {
+            index: index as usize,
+            max: map.len(),
+        })?;
+        rgb.extend_from_slice(&[(r >> 8) as u8, (g >> 8) as u8, (b >> 8) as u8]);
+    }
+    Ok(rgb)
+}
 
-    #[test]
-    fn test_in_memory_source_creation() {
-        let data = create_test_data();
-        let source = InMemorySource::new(data.clone());
-
-        assert_eq!(source.len(), data.len());
-        assert!(!source.is_empty());
-        assert_eq!(source.as_slice(), &data[..]);
+/// Expand packed 1-, 2- or 4-bit samples to one byte per sample
+///
+/// Samples are read MSB-first, and each row of `width` pixels starts on a
+/// fresh byte, so the padding bits at the end of a row are skipped. Values
+/// keep their stored range (0-1, 0-3 or 0-15); they aren't scaled to 8
+/// bits. 8-bit data is returned unchanged. A trailing partial row is
+/// ignored. Data with FillOrder 2 must be bit-reversed first (see
+/// `codec::reverse_bits_if_needed`).
+///
+/// # Returns
+/// `width * samples_per_pixel` bytes per row
+///
+/// # Errors
+/// Returns `UnsupportedFeature` for any other bit depth; 12- and 16-bit
+/// samples need `unpack_bits_u16`
+pub fn unpack_bits(data: &[u8], width: u32, bits_per_sample: u8, samples_per_pixel: u32) -> Result<Vec<u8>> {
+    if bits_per_sample == 8 {
+        return Ok(data.to_vec());
+    }
+    if !matches!(bits_per_sample, 1 | 2 | 4) {
+        return Err(TiffError::UnsupportedFeature {
+            feature: format!("unpacking {bits_per_sample}-bit samples to bytes"),
+        });
     }
 
-    #[test]
-    fn test_in_memory_source_bounds_checking() {
-        let data = vec![0x01, 0x02];
-        let source = InMemorySource::new(data);
+    let bits = bits_per_sample as usize;
+    let samples_per_row = width as usize * samples_per_pixel as usize;
+    let row_bytes = (samples_per_row * bits).div_ceil(8);
+    if row_bytes == 0 {
+        return Ok(Vec::new());
+    }
+    let mask = (1u8 << bits) - 1;
+    let mut out = Vec::with_capacity(data.len() / row_bytes * samples_per_row);
+    for row in data.chunks_exact(row_bytes) {
+        for i in 0..samples_per_row {
+            let bit = i * bits;
+            out.push((row[bit / 8] >> (8 - bits - bit % 8)) & mask);
+        }
+    }
+    Ok(out)
+}
 
-        // These should fail due to insufficient data
-        assert!(source.read_bytes_at(0, 10).is_err());
-        assert!(source.read_u16_at(1, Endian::Little).is_err());
-        assert!(source.read_u32_at(0, Endian::Little).is_err());
+/// Expand packed 12-bit or 16-bit samples to one `u16` per sample
+///
+/// 16-bit samples are whole byte pairs in the file's byte order. 12-bit
+/// samples are packed two to three bytes as an MSB-first bit stream, the
+/// same in II and MM files, so `endian` doesn't affect them; each row
+/// starts on a fresh byte. A trailing partial row is ignored.
+///
+/// # Returns
+/// `width * samples_per_pixel` values per row
+///
+/// # Errors
+/// Returns `UnsupportedFeature` for any other bit depth
+pub fn unpack_bits_u16(
+    data: &[u8],
+    width: u32,
+    bits_per_sample: u8,
+    samples_per_pixel: u32,
+    endian: Endian,
+) -> Result<Vec<u16>> {
+    if bits_per_sample == 16 {
+        return Ok(data.chunks_exact(2).map(|pair| endian.read_u16([pair[0], pair[1]])).collect());
+    }
+    if bits_per_sample != 12 {
+        return Err(TiffError::UnsupportedFeature {
+            feature: format!("unpacking {bits_per_sample}-bit samples to u16"),
+        });
     }
 
-    #[test]
-    fn test_in_memory_source_reading() {
-        let data = create_test_data();
-        let source = InMemorySource::new(data.clone());
+    let samples_per_row = width as usize * samples_per_pixel as usize;
+    let row_bytes = (samples_per_row * 12).div_ceil(8);
+    if row_bytes == 0 {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::with_capacity(data.len() / row_bytes * samples_per_row);
+    for row in data.chunks_exact(row_bytes) {
+        for i in 0..samples_per_row {
+            let byte = i * 3 / 2;
+            let value = if i % 2 == 0 {
+                (row[byte] as u16) << 4 | (row[byte + 1] >> 4) as u16
+            } else {
+                ((row[byte] & 0x0F) as u16) << 8 | row[byte + 1] as u16
+            };
+            out.push(value);
+        }
+    }
+    Ok(out)
+}
 
-        // Test reading bytes
-        let bytes = source.read_bytes_at(0, 4).unwrap();
-        assert_eq!(bytes, &data[0..4]);
+/// Convert an IEEE 754 half-precision value to `f32`
+///
+/// 16-bit float samples (SampleFormat 3 with BitsPerSample 16) are stored
+/// this way. Every half value is exactly representable as an `f32`, so the
+/// conversion is lossless: subnormals are normalised, infinities keep their
+/// sign, and NaNs keep their payload bits.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1F) as u32;
+    let mantissa = (bits & 0x3FF) as u32;
+    match exponent {
+        0 => {
+            // Zero or subnormal: mantissa * 2^-24
+            let magnitude = mantissa as f32 * f32::from_bits(103 << 23);
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        0x1F => f32::from_bits(sign | 0x7F80_0000 | mantissa << 13),
+        _ => f32::from_bits(sign | (exponent + 112) << 23 | mantissa << 13),
+    }
+}
 
-        // Test reading primitives
-        assert_eq!(source.read_u8_at(0).unwrap(), 0x49);
-        assert_eq!(source.read_u16_at(0, Endian::Little).unwrap(), 0x4949);
-        assert_eq!(source.read_u32_at(4, Endian::Little).unwrap(), 0x00000008);
+/// Reorient a decoded image so it displays upright
+///
+/// `buf` holds `height` rows of `width` pixels, each `bytes_per_pixel`
+/// bytes, as stored in the file. The pixels are moved so that row 0 is
+/// the top and column 0 the left of the displayed image.
+///
+/// # Returns
+/// The reoriented pixels with their width and height. `LeftTop` through
+/// `LeftBottom` (5-8) swap the width and height.
+///
+/// # Errors
+/// Returns `InsufficientData` if `buf` is shorter than the image
+pub fn apply_orientation(
+    buf: &[u8],
+    width: u32,
+    height: u32,
+    bytes_per_pixel: usize,
+    orientation: Orientation,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let (w, h) = (width as usize, height as usize);
+    let needed = w * h * bytes_per_pixel;
+    if buf.len() < needed {
+        return Err(TiffError::InsufficientData {
+            operation: "reorienting image",
+            needed,
+            available: buf.len(),
+        });
     }
+    let (out_width, out_height) = if orientation.swaps_dimensions() { (h, w) } else { (w, h) };
+
+    let mut out = Vec::with_capacity(needed);
+    for dy in 0..out_height {
+        for dx in 0..out_width {
+            // The stored pixel that lands at display position (dx, dy)
+            let (sx, sy) = match orientation {
+                Orientation::TopLeft => (dx, dy),
+                Orientation::TopRight => (w - 1 - dx, dy),
+                Orientation::BottomRight => (w - 1 - dx, h - 1 - dy),
+                Orientation::BottomLeft => (dx, h - 1 - dy),
+                Orientation::LeftTop => (dy, dx),
+                Orientation::RightTop => (dy, h - 1 - dx),
+                Orientation::RightBottom => (w - 1 - dy, h - 1 - dx),
+                Orientation::LeftBottom => (w - 1 - dy, dx),
+            };
+            let start = (sy * w + sx) * bytes_per_pixel;
+            out.extend_from_slice(&buf[start..start + bytes_per_pixel]);
+        }
+    }
+    Ok((out, out_width as u32, out_height as u32))
+}
 
-    #[test]
-    fn test_reader_creation() {
-        let data = create_test_data();
-        let source = InMemorySource::new(data.clone());
-        let reader = TiffReader::new(source);
+/// Order in which to visit the tiles of a tiled image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TileOrder {
+    /// Left to right, then top to bottom (the order tiles are stored in)
+    #[default]
+    RowMajor,
+    /// Top to bottom, then left to right
+    ColumnMajor,
+    /// Z-order curve, which keeps nearby tiles close together
+    Morton,
+}
 
-        assert_eq!(reader.len(), data.len());
-        assert_eq!(reader.position(), 0);
-        assert!(!reader.is_empty());
-        assert!(!reader.is_at_end());
+/// Interleave the bits of x and y into a Z-order (Morton) code
+fn morton_code(x: u32, y: u32) -> u64 {
+    fn spread(value: u32) -> u64 {
+        let mut v = value as u64;
+        v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+        v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+        v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+        v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+        v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+        v
     }
+    spread(x) | (spread(y) << 1)
+}
 
-    #[test]
-    fn test_seeking() {
-        let data = create_test_data();
-        let source = InMemorySource::new(data);
-        let mut reader = TiffReader::new(source);
+/// Get the bytes in a row of `pixels` pixels of `bits` bits, padded to a whole byte
+///
+/// # Errors
+/// Returns `InvalidTag` for `width_tag` if the size overflows a usize
+fn packed_row_bytes(pixels: u32, bits: usize, width_tag: u16) -> Result<usize> {
+    (pixels as usize)
+        .checked_mul(bits)
+        .map(|total| total.div_ceil(8))
+        .ok_or_else(|| TiffError::InvalidTag {
+            tag: width_tag,
+            reason: format!("{pixels} pixels of {bits} bits overflow the row size"),
+        })
+}
 
-        assert!(reader.seek(5).is_ok());
-        assert_eq!(reader.position(), 5);
+/// Get the decoded size of a strip or tile of `rows` rows
+///
+/// # Errors
+/// Returns `InvalidTag` for `rows_tag` if the size overflows a usize
+fn block_bytes(row_bytes: usize, rows: u32, rows_tag: u16) -> Result<usize> {
+    row_bytes.checked_mul(rows as usize).ok_or_else(|| TiffError::InvalidTag {
+        tag: rows_tag,
+        reason: format!("{rows} rows of {row_bytes} bytes overflow the block size"),
+    })
+}
 
-        // Seeking past end should fail
-        assert!(reader.seek(1000).is_err());
+/// Adapts a `with_jpeg_decoder` callback to the codec registry
+struct JpegDecoder<F>(F);
 
-        // Seeking to end should work
-        assert!(reader.seek(reader.len()).is_ok());
-        assert_eq!(reader.position(), reader.len());
+impl<F> CompressionCodec for JpegDecoder<F>
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync,
+{
+    fn decompress(&self, data: &[u8], _expected_len: usize) -> Result<Vec<u8>> {
+        (self.0)(data)
     }
+}
 
-    #[test]
-    fn test_stateful_reading() {
-        let data = create_test_data();
-        let source = InMemorySource::new(data);
-        let mut reader = TiffReader::new(source);
+/// Higher-level reader that decodes an IFD's image data
+///
+/// Borrows a `TiffReader` and one of its IFDs, caching the layout tags
+/// (dimensions, sample layout, compression, strip locations) up front so
+/// each strip read doesn't re-parse them.
+#[derive(Debug)]
+pub struct TiffImageReader<'a, T: TiffDataSource> {
+    reader: &'a TiffReader<T>,
+    endian: Endian,
+    width: u32,
+    height: u32,
+    samples_per_pixel: u32,
+    bits_per_sample: Vec<u32>,
+    sample_format: SampleFormat,
+    compression: u32,
+    photometric: Option<PhotometricInterpretation>,
+    fill_order: FillOrder,
+    planar: PlanarConfiguration,
+    predictor: u32,
+    rows_per_strip: u32,
+    tile_size: Option<(u32, u32)>,
+    data_ranges: Vec<(usize, usize)>,
+    jpeg_tables: Option<Vec<u8>>,
+    color_map: Option<Vec<(u16, u16, u16)>>,
+    max_decode_bytes: Option<u64>,
+    raw_data: bool,
+    codecs: CodecRegistry,
+}
+
+impl<'a, T: TiffDataSource> TiffImageReader<'a, T> {
+    /// Create an image reader for the given IFD
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the image dimensions are missing
+    pub fn new(reader: &'a TiffReader<T>, ifd: &'a ImageFileDirectory, endian: Endian) -> Result<Self> {
+        let width = ifd.image_width(reader, endian)?.ok_or(TiffError::InvalidTag {
+            tag: tags::tags::IMAGE_WIDTH,
+            reason: "required tag is missing".to_string(),
+        })?;
+        let height = ifd.image_height(reader, endian)?.ok_or(TiffError::InvalidTag {
+            tag: tags::tags::IMAGE_LENGTH,
+            reason: "required tag is missing".to_string(),
+        })?;
+        let samples_per_pixel = ifd.samples_per_pixel_or_default(reader, endian)?;
+        let bits_per_sample = ifd.bits_per_sample(reader, endian)?
+            .unwrap_or_else(|| vec![1; samples_per_pixel as usize]);
+        let compression = ifd.get_tag_value(tags::tags::COMPRESSION, reader, endian)?
+            .and_then(|v| v.as_u32())
+            .unwrap_or(Compression::None as u32);
+        let rows_per_strip = ifd.rows_per_strip_or_default(reader, endian)?;
+        let photometric = ifd.photometric_interpretation(reader, endian)?;
+        let tile_size = match (ifd.tile_width(reader, endian)?, ifd.tile_height(reader, endian)?) {
+            (Some(0), _) | (_, Some(0)) => {
+                return Err(TiffError::MalformedFile {
+                    reason: "tile dimensions must be non-zero".to_string(),
+                });
+            }
+            (Some(tile_width), Some(tile_height)) => Some((tile_width, tile_height)),
+            (Some(_), None) => {
+                return Err(TiffError::InvalidTag {
+                    tag: tags::tags::TILE_LENGTH,
+                    reason: "TileWidth is present but TileLength is missing".to_string(),
+                });
+            }
+            (None, _) => None,
+        };
+
+        Ok(Self {
+            reader,
+            endian,
+            width,
+            height,
+            samples_per_pixel,
+            bits_per_sample,
+            sample_format: ifd.sample_format(reader, endian)?.unwrap_or(SampleFormat::UInt),
+            compression,
+            photometric,
+            fill_order: ifd.fill_order(reader, endian)?.unwrap_or(FillOrder::MsbToLsb),
+            planar: ifd.planar_configuration(reader, endian)?.unwrap_or(PlanarConfiguration::Chunky),
+            predictor: ifd.predictor(reader, endian)?.unwrap_or(1),
+            rows_per_strip,
+            tile_size,
+            data_ranges: ifd.image_data_ranges(reader, endian)?,
+            jpeg_tables: if compression == Compression::Jpeg as u32 {
+                ifd.jpeg_tables(reader, endian)?
+            } else {
+                None
+            },
+            color_map: if photometric == Some(PhotometricInterpretation::Palette) {
+                ifd.color_map(reader, endian)?
+            } else {
+                None
+            },
+            max_decode_bytes: None,
+            raw_data: false,
+            codecs: CodecRegistry::default(),
+        })
+    }
+
+    /// Refuse to decode images whose uncompressed size exceeds `limit` bytes
+    ///
+    /// Guards against decompression bombs: a tiny file can claim enormous
+    /// dimensions. Once set, every strip and tile read and every
+    /// whole-image decode (`read_image`, `read_band`, `read_bilevel`, RGBA)
+    /// first checks `decode_memory_estimate` against the limit, before
+    /// allocating anything. The limit applies to the whole image, even
+    /// when only part of it is read.
+    pub fn with_max_decode_bytes(mut self, limit: u64) -> Self {
+        self.max_decode_bytes = Some(limit);
+        self
+    }
+
+    /// Return strips and tiles exactly as stored, skipping decompression
+    ///
+    /// With raw data mode on, `read_strip` and `read_tile` hand back the
+    /// bytes from the file verbatim, with no codec or predictor applied.
+    /// This works for any Compression value, including ones this crate
+    /// can't decode. Methods that interpret pixels still expect decoded
+    /// data, so leave it off for those.
+    pub fn with_raw_data(mut self, raw: bool) -> Self {
+        self.raw_data = raw;
+        self
+    }
+
+    /// Decode strips and tiles with Compression value `compression` using `codec`
+    ///
+    /// Registered codecs are consulted before the built-in ones, so this can
+    /// add schemes the crate doesn't decode (ZSTD, LERC, WebP, ...) or
+    /// replace a built-in decoder. Registering a second codec for the same
+    /// value replaces the first. A codec for JPEG (7) receives each block
+    /// already spliced with JPEGTables, as from `read_jpeg_stream`.
+    pub fn with_codec(mut self, compression: u32, codec: impl CompressionCodec + 'static) -> Self {
+        self.codecs.insert(compression, Box::new(codec));
+        self
+    }
+
+    /// Decode JPEG-compressed (Compression 7) strips and tiles with `decoder`
+    ///
+    /// This crate has no JPEG decoder, so `read_strip` and `read_tile` hand
+    /// each block to `decoder` instead. Blocks are usually abbreviated
+    /// streams without quantization and Huffman tables. Those tables are
+    /// stored once, in the JPEGTables tag (347), as their own SOI...EOI
+    /// stream. Before `decoder` is called, the tables are spliced in front
+    /// of the block: the tables' trailing EOI and the block's leading SOI
+    /// are dropped, giving `SOI, tables, scan data, EOI`. That is a complete
+    /// interchange stream any baseline decoder accepts.
+    ///
+    /// `decoder` must return the block's pixels in the layout `read_strip`
+    /// would: chunky rows of 8-bit samples. For YCbCr images that usually
+    /// means asking the decoder for RGB output.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use tiff_core::{InMemorySource, TiffImageReader, TiffReader};
+    ///
+    /// # fn decode_with_my_library(stream: &[u8]) -> tiff_core::Result<Vec<u8>> { Ok(stream.to_vec()) }
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut reader = TiffReader::new(InMemorySource::new(std::fs::read("jpeg.tif")?));
+    /// let header = reader.read_header()?;
+    /// let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness())?;
+    /// let image = TiffImageReader::new(&reader, &ifd, header.endianness())?
+    ///     .with_jpeg_decoder(decode_with_my_library);
+    /// let pixels = image.read_strip(0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_jpeg_decoder<F>(self, decoder: F) -> Self
+    where
+        F: Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.with_codec(Compression::Jpeg as u32, JpegDecoder(decoder))
+    }
+
+    /// Estimate the bytes needed to hold the fully decompressed image
+    ///
+    /// This is the uncompressed size of the image data: rows padded to a
+    /// whole byte for stripped images, and every tile at full tile size
+    /// (including edge padding) for tiled ones. Output conversions such as
+    /// RGBA need their own buffer on top of this.
+    ///
+    /// # Errors
+    /// Returns `MalformedFile` if the size doesn't fit in a u64
+    pub fn decode_memory_estimate(&self) -> Result<u64> {
+        let overflow = || TiffError::MalformedFile {
+            reason: "uncompressed image size overflows 64 bits".to_string(),
+        };
+        let (row_width, rows, blocks) = match (self.tile_size, self.tile_grid()) {
+            (Some((tile_width, tile_height)), Some((across, down))) => {
+                (tile_width as u64, tile_height as u64, across as u64 * down as u64)
+            }
+            _ => (self.width as u64, self.height as u64, 1),
+        };
+        // Each plane's rows are padded to a whole byte separately
+        let mut row_bytes: u64 = 0;
+        for plane in 0..self.plane_count() {
+            let bits = row_width.checked_mul(self.plane_bits(plane) as u64).ok_or_else(overflow)?;
+            row_bytes = row_bytes.checked_add(bits.div_ceil(8)).ok_or_else(overflow)?;
+        }
+        row_bytes
+            .checked_mul(rows)
+            .and_then(|bytes| bytes.checked_mul(blocks))
+            .ok_or_else(overflow)
+    }
+
+    /// Check the image against the configured decode memory limit
+    fn check_decode_limit(&self) -> Result<()> {
+        if let Some(limit) = self.max_decode_bytes
+            && self.decode_memory_estimate()? > limit
+        {
+            return Err(TiffError::UnsupportedFeature {
+                feature: "image exceeds configured decode memory limit".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Get the image dimensions as (width, height)
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Get the number of sample planes: 1 for chunky data, one per sample for planar
+    fn plane_count(&self) -> usize {
+        match self.planar {
+            PlanarConfiguration::Chunky => 1,
+            PlanarConfiguration::Planar => self.samples_per_pixel.max(1) as usize,
+        }
+    }
+
+    /// Get the bits of one sample
+    ///
+    /// BitsPerSample may hold a single value for all samples, so a missing
+    /// entry repeats the first one.
+    fn sample_bits(&self, sample: usize) -> u32 {
+        self.bits_per_sample.get(sample)
+            .or(self.bits_per_sample.first())
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// Get the bits each pixel occupies within a plane
+    fn plane_bits(&self, plane: usize) -> usize {
+        match self.planar {
+            PlanarConfiguration::Chunky => {
+                let samples = (self.samples_per_pixel as usize).max(self.bits_per_sample.len());
+                (0..samples).fold(0usize, |sum, sample| sum.saturating_add(self.sample_bits(sample) as usize))
+            }
+            PlanarConfiguration::Planar => self.sample_bits(plane) as usize,
+        }
+    }
+
+    /// Get the number of bytes in one row of a plane
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the row size overflows a usize
+    fn plane_row_bytes(&self, plane: usize) -> Result<usize> {
+        packed_row_bytes(self.width, self.plane_bits(plane), tags::tags::IMAGE_WIDTH)
+    }
+
+    /// Get the number of bytes in one row of a tile in a plane
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if the row size overflows a usize
+    fn tile_row_bytes(&self, tile_width: u32, plane: usize) -> Result<usize> {
+        packed_row_bytes(tile_width, self.plane_bits(plane), tags::tags::TILE_WIDTH)
+    }
+
+    /// Get the number of bytes in one row of chunky pixel data (or of the first plane)
+    fn row_bytes(&self) -> Result<usize> {
+        self.plane_row_bytes(0)
+    }
+
+    /// Get the number of strips in the image
+    pub fn strip_count(&self) -> usize {
+        if self.is_tiled() { 0 } else { self.data_ranges.len() }
+    }
+
+    /// Get the number of strips the layout calls for, from RowsPerStrip and the height
+    ///
+    /// Unlike `strip_count`, this doesn't depend on how many offsets the
+    /// file actually lists. Returns 0 for tiled images.
+    pub fn strips_per_image(&self) -> usize {
+        if self.is_tiled() {
+            0
+        } else {
+            self.height.div_ceil(self.rows_per_strip) as usize
+        }
+    }
+
+    /// Get the rows covered by a strip as (first row, row count)
+    ///
+    /// The last strip is clamped to the image height. Returns None for
+    /// tiled images or an index past `strips_per_image`.
+    pub fn strip_rows(&self, index: usize) -> Option<(u32, u32)> {
+        if index >= self.strips_per_image() {
+            return None;
+        }
+        let first_row = index as u32 * self.rows_per_strip;
+        Some((first_row, self.rows_per_strip.min(self.height - first_row)))
+    }
+
+    /// Check if the image uses tiled layout
+    pub fn is_tiled(&self) -> bool {
+        self.tile_size.is_some()
+    }
+
+    /// Get the tile grid as (tiles across, tiles down), or None for stripped images
+    pub fn tile_grid(&self) -> Option<(u32, u32)> {
+        self.tile_size.map(|(tile_width, tile_height)| {
+            (self.width.div_ceil(tile_width), self.height.div_ceil(tile_height))
+        })
+    }
+
+    /// Read the tile at the given tile column and row and return its decompressed bytes
+    ///
+    /// Edge tiles are returned at full tile size, including the padding
+    /// that extends past the image.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for coordinates outside the tile grid and
+    /// `UnsupportedFeature` for stripped images or unsupported compression
+    /// (unless `with_raw_data` is on)
+    pub fn read_tile(&self, tile_x: u32, tile_y: u32) -> Result<Vec<u8>> {
+        self.read_tile_in_plane(0, tile_x, tile_y)
+    }
+
+    /// Read a tile from the given sample plane
+    ///
+    /// Planar images store every tile of plane 0, then every tile of
+    /// plane 1, and so on. Chunky images only have plane 0.
+    fn read_tile_in_plane(&self, plane: usize, tile_x: u32, tile_y: u32) -> Result<Vec<u8>> {
+        let Some((across, down)) = self.tile_grid() else {
+            return Err(TiffError::UnsupportedFeature {
+                feature: "reading tiles from a stripped image".to_string(),
+            });
+        };
+        if tile_x >= across || tile_y >= down {
+            return Err(TiffError::OutOfBounds {
+                index: tile_y as usize * across as usize + tile_x as usize,
+                max: across as usize * down as usize,
+            });
+        }
+
+        self.check_decode_limit()?;
+
+        let tiles_per_plane = across as usize * down as usize;
+        let index = plane * tiles_per_plane + tile_y as usize * across as usize + tile_x as usize;
+        let &(offset, count) = self.data_ranges.get(index).ok_or(TiffError::OutOfBounds {
+            index,
+            max: self.data_ranges.len(),
+        })?;
+        let data = self.reader.read_bytes_at(offset, count)?;
+        if self.raw_data {
+            return Ok(data);
+        }
+        let (tile_width, tile_height) = self.tile_size.unwrap_or((0, 0));
+        let tile_bytes = block_bytes(self.tile_row_bytes(tile_width, plane)?, tile_height, tags::tags::TILE_LENGTH)?;
+        let mut tile = self.decompress(data, tile_bytes)?;
+        self.unpredict(&mut tile, tile_width, plane)?;
+        Ok(tile)
+    }
+
+    /// Iterate over all tiles in the given traversal order
+    ///
+    /// Yields `(tile_x, tile_y, bytes)` for each tile, reading each one
+    /// lazily as the iterator advances. Stripped images yield nothing.
+    pub fn tiles_in_order(&self, order: TileOrder) -> impl Iterator<Item = Result<(u32, u32, Vec<u8>)>> + '_ {
+        let (across, down) = self.tile_grid().unwrap_or((0, 0));
+        let mut coordinates: Vec<(u32, u32)> = (0..down)
+            .flat_map(|y| (0..across).map(move |x| (x, y)))
+            .collect();
+        match order {
+            TileOrder::RowMajor => {}
+            TileOrder::ColumnMajor => coordinates.sort_by_key(|&(x, y)| (x, y)),
+            TileOrder::Morton => coordinates.sort_by_key(|&(x, y)| morton_code(x, y)),
+        }
+
+        coordinates
+            .into_iter()
+            .map(move |(x, y)| self.read_tile(x, y).map(|bytes| (x, y, bytes)))
+    }
+
+    /// Read a strip and return its decompressed bytes
+    ///
+    /// For planar images the strip index runs across all planes: strips
+    /// `0..strips_per_image()` hold the first sample, the next run the
+    /// second, and so on.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for an invalid strip index and
+    /// `UnsupportedFeature` for tiled images or unsupported compression
+    /// (unless `with_raw_data` is on)
+    pub fn read_strip(&self, index: usize) -> Result<Vec<u8>> {
+        if self.is_tiled() {
+            return Err(TiffError::UnsupportedFeature {
+                feature: "reading strips from a tiled image".to_string(),
+            });
+        }
+        self.check_decode_limit()?;
+
+        let &(offset, count) = self.data_ranges.get(index).ok_or(TiffError::OutOfBounds {
+            index,
+            max: self.data_ranges.len(),
+        })?;
+
+        let data = self.reader.read_bytes_at(offset, count)?;
+        if self.raw_data {
+            return Ok(data);
+        }
+        let strips_per_plane = self.strips_per_image().max(1);
+        let plane = (index / strips_per_plane).min(self.plane_count() - 1);
+        let rows = self.strip_rows(index - plane * strips_per_plane).map_or(self.rows_per_strip, |(_, rows)| rows);
+        let strip_bytes = block_bytes(self.plane_row_bytes(plane)?, rows, tags::tags::ROWS_PER_STRIP)?;
+        let mut strip = self.decompress(data, strip_bytes)?;
+        self.unpredict(&mut strip, self.width, plane)?;
+        Ok(strip)
+    }
+
+    /// Read a strip of 8-bit unsigned samples
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` unless every sample is an 8-bit unsigned integer
+    pub fn read_strip_as_u8(&self, index: usize) -> Result<Vec<u8>> {
+        self.require_samples(8, SampleFormat::UInt)?;
+        self.read_strip(index)
+    }
+
+    /// Read a strip of 16-bit unsigned samples
+    ///
+    /// Sample bytes are interpreted using the file's byte order.
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` unless every sample is a 16-bit unsigned integer
+    pub fn read_strip_as_u16(&self, index: usize) -> Result<Vec<u16>> {
+        self.require_samples(16, SampleFormat::UInt)?;
+        let data = self.read_strip(index)?;
+        Ok(data
+            .chunks_exact(2)
+            .map(|b| self.endian.read_u16([b[0], b[1]]))
+            .collect())
+    }
+
+    /// Read a strip of 16-bit signed samples (SampleFormat = Int)
+    ///
+    /// Sample bytes are interpreted using the file's byte order.
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` unless every sample is a 16-bit signed integer
+    pub fn read_strip_as_i16(&self, index: usize) -> Result<Vec<i16>> {
+        self.require_samples(16, SampleFormat::Int)?;
+        let data = self.read_strip(index)?;
+        Ok(data
+            .chunks_exact(2)
+            .map(|b| self.endian.read_u16([b[0], b[1]]) as i16)
+            .collect())
+    }
+
+    /// Read a strip of 16-bit or 32-bit floating point samples
+    ///
+    /// Sample bytes are interpreted using the file's byte order. Half-precision
+    /// samples are widened with [`f16_to_f32`].
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` unless every sample is a 16-bit or 32-bit
+    /// IEEE float
+    pub fn read_strip_as_f32(&self, index: usize) -> Result<Vec<f32>> {
+        if self.require_samples(16, SampleFormat::Float).is_ok() {
+            let data = self.read_strip(index)?;
+            return Ok(data
+                .chunks_exact(2)
+                .map(|b| f16_to_f32(self.endian.read_u16([b[0], b[1]])))
+                .collect());
+        }
+        self.require_samples(32, SampleFormat::Float)?;
+        let data = self.read_strip(index)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|b| f32::from_bits(self.endian.read_u32([b[0], b[1], b[2], b[3]])))
+            .collect())
+    }
+
+    /// Read a JPEG-compressed strip or tile as a complete JPEG stream
+    ///
+    /// The block is spliced with the JPEGTables stream, if any, so it can be
+    /// handed directly to an external JPEG decoder. Blocks are numbered in
+    /// file order (row-major for tiles).
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` unless Compression is 7 (JPEG),
+    /// `OutOfBounds` for an invalid block index, or `MalformedFile` if the
+    /// block or tables lack their SOI/EOI markers
+    pub fn read_jpeg_stream(&self, index: usize) -> Result<Vec<u8>> {
+        if self.compression != Compression::Jpeg as u32 {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!("reading a JPEG stream from an image with compression {}", self.compression),
+            });
+        }
+        let &(offset, count) = self.data_ranges.get(index).ok_or(TiffError::OutOfBounds {
+            index,
+            max: self.data_ranges.len(),
+        })?;
+        let block = self.reader.read_bytes_at(offset, count)?;
+        crate::codec::splice_jpeg_tables(self.jpeg_tables.as_deref(), &block)
+    }
+
+    /// Check that every JPEG strip or tile forms a valid-looking JPEG stream
+    ///
+    /// Each block is spliced with JPEGTables and must start with SOI. This
+    /// doesn't decode the JPEG data, it only confirms the streams are
+    /// assembled correctly for an external decoder.
+    ///
+    /// # Errors
+    /// Returns the first error from `read_jpeg_stream`
+    pub fn validate_jpeg_blocks(&self) -> Result<()> {
+        for index in 0..self.data_ranges.len() {
+            self.read_jpeg_stream(index)?;
+        }
+        Ok(())
+    }
+
+    /// Check that every sample has the given bit depth and sample format
+    fn require_samples(&self, bits: u32, format: SampleFormat) -> Result<()> {
+        if self.sample_format != format || self.bits_per_sample.iter().any(|&b| b != bits) {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!(
+                    "reading {:?}-bit {:?} samples as {bits}-bit {format:?}",
+                    self.bits_per_sample, self.sample_format
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Decompress raw strip or tile bytes according to the Compression tag
+    ///
+    /// `expected_len` is the decoded size of the strip or tile, which
+    /// codecs use to know when to stop. Uncompressed data is returned as-is.
+    /// Codecs registered with `with_codec` take priority over the built-ins.
+    fn decompress(&self, data: Vec<u8>, expected_len: usize) -> Result<Vec<u8>> {
+        if let Some(codec) = self.codecs.get(self.compression) {
+            if self.compression == Compression::Jpeg as u32 {
+                let stream = crate::codec::splice_jpeg_tables(self.jpeg_tables.as_deref(), &data)?;
+                return codec.decompress(&stream, expected_len);
+            }
+            return codec.decompress(&data, expected_len);
+        }
+        match Compression::from_u32(self.compression) {
+            Some(Compression::None) => Ok(data),
+            Some(Compression::PackBits) => crate::codec::decompress_packbits(&data, expected_len),
+            Some(Compression::Lzw) => crate::codec::decompress_lzw(&data, expected_len),
+            Some(Compression::Deflate | Compression::AdobeDeflate) => {
+                crate::codec::decompress_deflate(&data, expected_len)
+            }
+            Some(other) => Err(TiffError::UnsupportedFeature {
+                feature: format!("{other:?} compression"),
+            }),
+            None => Err(TiffError::UnsupportedFeature {
+                feature: format!("compression scheme {}", self.compression),
+            }),
+        }
+    }
+
+    /// Undo the Predictor tag's differencing on decompressed rows of `width` pixels
+    fn unpredict(&self, buf: &mut [u8], width: u32, plane: usize) -> Result<()> {
+        if self.predictor == 1 {
+            return Ok(());
+        }
+        let (samples, bits) = match self.planar {
+            PlanarConfiguration::Planar => (1, self.plane_bits(plane) as u32),
+            PlanarConfiguration::Chunky => {
+                let bits = self.bits_per_sample.first().copied().unwrap_or(1);
+                if self.bits_per_sample.iter().any(|&b| b != bits) {
+                    return Err(TiffError::UnsupportedFeature {
+                        feature: format!("predictor with mixed sample sizes {:?}", self.bits_per_sample),
+                    });
+                }
+                (self.samples_per_pixel as usize, bits)
+            }
+        };
+        match self.predictor {
+            2 => crate::codec::unpredict_horizontal(buf, width as usize, samples, bits, self.endian),
+            3 => crate::codec::unpredict_float(buf, width as usize, samples, bits as usize / 8, self.endian),
+            other => Err(TiffError::UnsupportedFeature {
+                feature: format!("predictor {other}"),
+            }),
+        }
+    }
+
+    /// Read all strips and join them into one buffer of `height` rows
+    fn read_strip_rows(&self) -> Result<Vec<u8>> {
+        let row_bytes = self.row_bytes()?;
+        let total = block_bytes(row_bytes, self.height, tags::tags::IMAGE_LENGTH)?;
+        let mut image = Vec::with_capacity(self.decoded_capacity(total));
+
+        for index in 0..self.strip_count() {
+            let Some((_, rows)) = self.strip_rows(index) else {
+                break;
+            };
+            let strip = self.read_strip(index)?;
+            let needed = rows as usize * row_bytes;
+            if strip.len() < needed {
+                return Err(TiffError::InsufficientData {
+                    operation: "decoding strip",
+                    needed,
+                    available: strip.len(),
+                });
+            }
+            image.extend_from_slice(&strip[..needed]);
+        }
+
+        if image.len() < total {
+            return Err(TiffError::InsufficientData {
+                operation: "assembling image from strips",
+                needed: total,
+                available: image.len(),
+            });
+        }
+        Ok(image)
+    }
+
+    /// Decode a bilevel (1-bit) image
+    ///
+    /// The result is normalized so that 1 bits (or 255 bytes) are white,
+    /// regardless of WhiteIsZero/BlackIsZero, and bits are MSB-first
+    /// regardless of FillOrder. A missing PhotometricInterpretation is
+    /// treated as BlackIsZero.
+    ///
+    /// # Arguments
+    /// * `unpack` - If false, return packed rows of `ceil(width / 8)` bytes;
+    ///   if true, return one byte per pixel (0 = black, 255 = white)
+    ///
+    /// # Returns
+    /// `(width, height, pixels)`
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` if the image is not 1 sample of 1 bit
+    pub fn read_bilevel(&self, unpack: bool) -> Result<(u32, u32, Vec<u8>)> {
+        if self.samples_per_pixel != 1 || self.bits_per_sample.first() != Some(&1) {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!(
+                    "bilevel decode of a {}-sample {:?}-bit image",
+                    self.samples_per_pixel, self.bits_per_sample
+                ),
+            });
+        }
+        self.check_decode_limit()?;
+
+        let mut packed = self.read_strip_rows()?;
+
+        if self.fill_order == FillOrder::LsbToMsb {
+            for byte in packed.iter_mut() {
+                *byte = byte.reverse_bits();
+            }
+        }
+        if self.photometric == Some(PhotometricInterpretation::WhiteIsZero) {
+            for byte in packed.iter_mut() {
+                *byte = !*byte;
+            }
+        }
+
+        if !unpack {
+            return Ok((self.width, self.height, packed));
+        }
+
+        let row_bytes = self.row_bytes()?;
+        let mut pixels = Vec::with_capacity(packed.len() / row_bytes.max(1) * self.width as usize);
+        for row in packed.chunks_exact(row_bytes) {
+            for x in 0..self.width as usize {
+                let bit = (row[x / 8] >> (7 - x % 8)) & 1;
+                pixels.push(if bit == 1 { 255 } else { 0 });
+            }
+        }
+        Ok((self.width, self.height, pixels))
+    }
+
+    /// Decode the image to 8-bit RGBA
+    ///
+    /// Supports bilevel images, 8-bit grayscale (BlackIsZero or
+    /// WhiteIsZero, optionally with alpha), 8-bit RGB (optionally with
+    /// alpha) and 1-, 2-, 4- or 8-bit palette images. Images without
+    /// PhotometricInterpretation are treated as grayscale.
+    ///
+    /// # Returns
+    /// `(width, height, pixels)` with 4 bytes per pixel
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` for any other pixel layout
+    pub fn read_rgba8(&self) -> Result<(u32, u32, Vec<u8>)> {
+        self.read_rgba8_downsampled(1)
+    }
+
+    /// Decode a nearest-neighbor preview of the image to 8-bit RGBA
+    ///
+    /// Takes every `factor`th pixel of every `factor`th row, producing a
+    /// `width / factor` by `height / factor` image (at least 1x1). Only the
+    /// strips or tile rows that contain a sampled row are read and
+    /// decompressed, but each of those is decoded in full, so the savings
+    /// come from the rows that are skipped.
+    ///
+    /// # Arguments
+    /// * `factor` - Downsampling factor; 1 decodes at full resolution
+    ///
+    /// # Returns
+    /// `(width, height, pixels)` of the preview with 4 bytes per pixel
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` for a zero factor or an unsupported
+    /// pixel layout (see `read_rgba8`)
+    pub fn read_rgba8_downsampled(&self, factor: u32) -> Result<(u32, u32, Vec<u8>)> {
+        if factor == 0 {
+            return Err(TiffError::UnsupportedFeature {
+                feature: "downsampling by a factor of 0".to_string(),
+            });
+        }
+        self.require_rgba_layout()?;
+        self.check_decode_limit()?;
+
+        let out_width = (self.width / factor).max(1);
+        let out_height = (self.height / factor).max(1);
+        let row_bytes = self.row_bytes()?;
+        let block_rows = self.tile_size.map_or(self.rows_per_strip, |(_, tile_height)| tile_height) as usize;
+
+        let out_bytes = (out_width as usize)
+            .checked_mul(out_height as usize)
+            .and_then(|pixels| pixels.checked_mul(4))
+            .ok_or_else(|| TiffError::InvalidTag {
+                tag: tags::tags::IMAGE_LENGTH,
+                reason: format!("{out_width}x{out_height} RGBA pixels overflow the buffer size"),
+            })?;
+        let mut pixels = Vec::with_capacity(self.decoded_capacity(out_bytes));
+        let mut cached: Option<(usize, Vec<u8>)> = None;
+        for out_y in 0..out_height as usize {
+            let y = out_y * factor as usize;
+            let block = y / block_rows;
+            if cached.as_ref().is_none_or(|(index, _)| *index != block) {
+                cached = Some((block, self.read_row_block(block, 0)?));
+            }
+            let (_, rows) = cached.as_ref().expect("block was just cached");
+
+            let start = (y % block_rows) * row_bytes;
+            let row = rows.get(start..start + row_bytes).ok_or(TiffError::InsufficientData {
+                operation: "decoding row",
+                needed: start + row_bytes,
+                available: rows.len(),
+            })?;
+            for out_x in 0..out_width as usize {
+                pixels.extend_from_slice(&self.pixel_rgba(row, out_x * factor as usize));
+            }
+        }
+        Ok((out_width, out_height, pixels))
+    }
+
+    /// Decode a palette (indexed color) image to 8-bit RGBA
+    ///
+    /// Indices of 1, 2, 4 or 8 bits are unpacked (honoring FillOrder) and
+    /// looked up in the ColorMap, whose 16-bit entries are scaled down to
+    /// 8 bits. Alpha is always 255.
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` if the image isn't a single-sample
+    /// palette image, or `InvalidTag` if the ColorMap is missing or its
+    /// size doesn't match the bit depth
+    pub fn palette_rgba8(&self) -> Result<(u32, u32, Vec<u8>)> {
+        if self.photometric != Some(PhotometricInterpretation::Palette) {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!("palette decode of {:?} image", self.photometric),
+            });
+        }
+        self.read_rgba8()
+    }
+
+    /// Check that a palette image has a usable index depth and ColorMap
+    fn require_palette_layout(&self) -> Result<()> {
+        let bits = match self.bits_per_sample[..] {
+            [bits @ (1 | 2 | 4 | 8)] if self.samples_per_pixel == 1 => bits,
+            _ => {
+                return Err(TiffError::UnsupportedFeature {
+                    feature: format!(
+                        "palette images with {} samples of {:?} bits",
+                        self.samples_per_pixel, self.bits_per_sample
+                    ),
+                });
+            }
+        };
+        let Some(map) = &self.color_map else {
+            return Err(TiffError::InvalidTag {
+                tag: tags::tags::COLORMAP,
+                reason: "required tag is missing".to_string(),
+            });
+        };
+        if map.len() != 1 << bits {
+            return Err(TiffError::InvalidTag {
+                tag: tags::tags::COLORMAP,
+                reason: format!("expected {} entries for {bits}-bit indices, found {}", 1 << bits, map.len()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the pixel layout is one `pixel_rgba` can convert
+    fn require_rgba_layout(&self) -> Result<()> {
+        if self.photometric == Some(PhotometricInterpretation::Palette) {
+            return self.require_palette_layout();
+        }
+        let spp = self.samples_per_pixel;
+        let bilevel = spp == 1 && self.bits_per_sample == [1];
+        let eight_bit = self.sample_format == SampleFormat::UInt
+            && self.bits_per_sample.len() == spp as usize
+            && self.bits_per_sample.iter().all(|&b| b == 8);
+        let interleaved = spp == 1 || self.planar == PlanarConfiguration::Chunky;
+        let supported = interleaved && bilevel
+            || interleaved && eight_bit
+                && match self.photometric {
+                    Some(PhotometricInterpretation::Rgb) => spp == 3 || spp == 4,
+                    Some(PhotometricInterpretation::WhiteIsZero | PhotometricInterpretation::BlackIsZero) | None => {
+                        spp == 1 || spp == 2
+                    }
+                    _ => false,
+                };
+        if !supported {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!(
+                    "RGBA decode of {:?} with {spp} samples of {:?} bits",
+                    self.photometric, self.bits_per_sample
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Convert the pixel at column `x` of a decoded row to RGBA
+    fn pixel_rgba(&self, row: &[u8], x: usize) -> [u8; 4] {
+        if let Some(map) = &self.color_map {
+            let bits = self.bits_per_sample[0] as usize;
+            let bit = x * bits;
+            let mut byte = row[bit / 8];
+            if self.fill_order == FillOrder::LsbToMsb {
+                byte = byte.reverse_bits();
+            }
+            let index = (byte >> (8 - bits - bit % 8)) as usize & ((1 << bits) - 1);
+            let (r, g, b) = map[index];
+            return [(r >> 8) as u8, (g >> 8) as u8, (b >> 8) as u8, 255];
+        }
+
+        let white_is_zero = self.photometric == Some(PhotometricInterpretation::WhiteIsZero);
+        if self.bits_per_sample[0] == 1 {
+            let mut byte = row[x / 8];
+            if self.fill_order == FillOrder::LsbToMsb {
+                byte = byte.reverse_bits();
+            }
+            let set = (byte >> (7 - x % 8)) & 1 == 1;
+            let value = if set != white_is_zero { 255 } else { 0 };
+            return [value, value, value, 255];
+        }
+
+        let spp = self.samples_per_pixel as usize;
+        let p = &row[x * spp..(x + 1) * spp];
+        match (self.photometric, spp) {
+            (Some(PhotometricInterpretation::Rgb), 3) => [p[0], p[1], p[2], 255],
+            (Some(PhotometricInterpretation::Rgb), _) => [p[0], p[1], p[2], p[3]],
+            _ => {
+                let gray = if white_is_zero { 255 - p[0] } else { p[0] };
+                [gray, gray, gray, p.get(1).copied().unwrap_or(255)]
+            }
+        }
+    }
+
+    /// Decode every strip or tile row and discard the result
+    ///
+    /// Checks that each block decompresses to at least as many bytes as
+    /// its rows need, returning the first error encountered. Only one
+    /// block is held in memory at a time.
+    pub(crate) fn decode_all_blocks(&self) -> Result<()> {
+        let block_rows = self.block_rows();
+        if block_rows == 0 {
+            return Ok(());
+        }
+        let blocks = (self.height as usize).div_ceil(block_rows);
+        for plane in 0..self.plane_count() {
+            let row_bytes = self.plane_row_bytes(plane)?;
+            for block in 0..blocks {
+                let rows = block_rows.min(self.height as usize - block * block_rows);
+                let decoded = self.read_row_block(block, plane)?;
+                if decoded.len() < rows * row_bytes {
+                    return Err(TiffError::InsufficientData {
+                        operation: "decoding strip",
+                        needed: rows * row_bytes,
+                        available: decoded.len(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the number of image rows in each strip or tile row
+    fn block_rows(&self) -> usize {
+        self.tile_size.map_or(self.rows_per_strip, |(_, tile_height)| tile_height) as usize
+    }
+
+    /// Initial capacity for a buffer that will hold `expected_len` decoded bytes
+    ///
+    /// `expected_len` comes from the layout tags, so like
+    /// `codec::output_capacity` the reservation is bounded by the encoded
+    /// data the file actually holds; the buffer grows past that only as
+    /// blocks decode.
+    fn decoded_capacity(&self, expected_len: usize) -> usize {
+        let encoded = self.data_ranges.iter().fold(0usize, |sum, &(_, count)| sum.saturating_add(count));
+        crate::codec::output_capacity(encoded.min(self.reader.len()), expected_len)
+    }
+
+    /// Read every row of one plane into a single buffer
+    fn read_plane(&self, plane: usize) -> Result<Vec<u8>> {
+        let row_bytes = self.plane_row_bytes(plane)?;
+        let total = block_bytes(row_bytes, self.height, tags::tags::IMAGE_LENGTH)?;
+        let block_rows = self.block_rows();
+        let mut image = Vec::with_capacity(self.decoded_capacity(total));
+        for block in 0..(self.height as usize).div_ceil(block_rows.max(1)) {
+            let rows = block_rows.min(self.height as usize - block * block_rows);
+            let decoded = self.read_row_block(block, plane)?;
+            let needed = rows * row_bytes;
+            if decoded.len() < needed {
+                return Err(TiffError::InsufficientData {
+                    operation: "decoding strip",
+                    needed,
+                    available: decoded.len(),
+                });
+            }
+            image.extend_from_slice(&decoded[..needed]);
+        }
+        Ok(image)
+    }
+
+    /// Decode the whole image into one contiguous row-major buffer
+    ///
+    /// Every strip or tile is decompressed and has its predictor undone.
+    /// Tiles are cropped to the image and stitched into place, so the
+    /// result holds exactly `height` rows of `width` pixels, each row
+    /// padded to a whole byte. Multi-byte samples keep the file's byte
+    /// order.
+    ///
+    /// Planar images (PlanarConfiguration = 2) are decoded plane by plane
+    /// and interleaved, so the result is always chunky.
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` for compression schemes without a
+    /// decoder or planar samples that aren't whole bytes, or
+    /// `InsufficientData` if a strip or tile decodes short
+    pub fn read_image(&self) -> Result<Vec<u8>> {
+        self.check_decode_limit()?;
+        if self.plane_count() == 1 {
+            return self.read_plane(0);
+        }
+
+        let sample_bytes: Vec<usize> = (0..self.plane_count())
+            .map(|plane| {
+                let bits = self.plane_bits(plane);
+                if bits == 0 || !bits.is_multiple_of(8) {
+                    return Err(TiffError::UnsupportedFeature {
+                        feature: format!("interleaving {bits}-bit planar samples"),
+                    });
+                }
+                Ok(bits / 8)
+            })
+            .collect::<Result<_>>()?;
+        let planes = (0..self.plane_count())
+            .map(|plane| self.read_plane(plane))
+            .collect::<Result<Vec<_>>>()?;
+
+        let pixels = self.width as usize * self.height as usize;
+        let mut image = Vec::with_capacity(planes.iter().map(Vec::len).sum());
+        for pixel in 0..pixels {
+            for (plane, &len) in planes.iter().zip(&sample_bytes) {
+                image.extend_from_slice(&plane[pixel * len..][..len]);
+            }
+        }
+        Ok(image)
+    }
+
+    /// Read a single sample (channel) as its own buffer
+    ///
+    /// Returns `(width, height, bytes)` where `bytes` holds just that
+    /// sample for every pixel, row by row, in the file's byte order. For
+    /// planar images only the strips or tiles of that plane are read;
+    /// chunky images are decoded in full and de-interleaved.
+    ///
+    /// # Arguments
+    /// * `band` - Zero-based sample index, below `SamplesPerPixel`
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for a band past the last sample and
+    /// `UnsupportedFeature` if a chunky sample doesn't start and end on a
+    /// byte boundary
+    pub fn read_band(&self, band: u32) -> Result<(u32, u32, Vec<u8>)> {
+        if band >= self.samples_per_pixel {
+            return Err(TiffError::OutOfBounds {
+                index: band as usize,
+                max: self.samples_per_pixel as usize,
+            });
+        }
+        self.check_decode_limit()?;
+        if self.planar == PlanarConfiguration::Planar {
+            return Ok((self.width, self.height, self.read_plane(band as usize)?));
+        }
+
+        let bits = |index: usize| self.bits_per_sample.get(index).copied().unwrap_or(0) as usize;
+        let start_bits: usize = (0..band as usize).map(bits).sum();
+        let band_bits = bits(band as usize);
+        let pixel_bits = self.plane_bits(0);
+        if band_bits == 0 || !band_bits.is_multiple_of(8) || !start_bits.is_multiple_of(8) || !pixel_bits.is_multiple_of(8) {
+            return Err(TiffError::UnsupportedFeature {
+                feature: format!("extracting sample {band} from {:?}-bit chunky pixels", self.bits_per_sample),
+            });
+        }
+
+        let (start, len, pixel_bytes) = (start_bits / 8, band_bits / 8, pixel_bits / 8);
+        let pixels = self.read_plane(0)?;
+        let band = pixels
+            .chunks_exact(pixel_bytes)
+            .flat_map(|pixel| &pixel[start..start + len])
+            .copied()
+            .collect();
+        Ok((self.width, self.height, band))
+    }
+
+    /// Read the sample values of a single pixel
+    ///
+    /// Only the strip or tile holding the pixel is decoded (one per plane
+    /// for planar images), which makes this much cheaper than a full
+    /// decode for point queries. Samples are converted to `f64` according
+    /// to BitsPerSample and SampleFormat.
+    ///
+    /// # Arguments
+    /// * `x` - Column, below the image width
+    /// * `y` - Row, below the image height
+    ///
+    /// # Returns
+    /// One value per sample, in `SamplesPerPixel` order
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` for a pixel outside the image and
+    /// `UnsupportedFeature` for sample layouts that can't be converted
+    pub fn read_pixel(&self, x: u32, y: u32) -> Result<Vec<f64>> {
+        if x >= self.width || y >= self.height {
+            return Err(TiffError::OutOfBounds {
+                index: y as usize * self.width as usize + x as usize,
+                max: self.width as usize * self.height as usize,
+            });
+        }
+
+        let mut values = Vec::with_capacity(self.samples_per_pixel as usize);
+        for plane in 0..self.plane_count() {
+            let bits_per_pixel = self.plane_bits(plane);
+            let (block, row_bytes, row, column) = match self.tile_size {
+                Some((tile_width, tile_height)) => (
+                    self.read_tile_in_plane(plane, x / tile_width, y / tile_height)?,
+                    self.tile_row_bytes(tile_width, plane)?,
+                    (y % tile_height) as usize,
+                    (x % tile_width) as usize,
+                ),
+                None => (
+                    self.read_strip(plane * self.strips_per_image() + (y / self.rows_per_strip) as usize)?,
+                    self.plane_row_bytes(plane)?,
+                    (y % self.rows_per_strip) as usize,
+                    x as usize,
+                ),
+            };
+            let row_start = row * row_bytes;
+            let pixel = block.get(row_start..row_start + row_bytes).ok_or(TiffError::InsufficientData {
+                operation: "reading pixel",
+                needed: row_start + row_bytes,
+                available: block.len(),
+            })?;
+
+            let mut bit = column * bits_per_pixel;
+            let samples = match self.planar {
+                PlanarConfiguration::Chunky => self.samples_per_pixel as usize,
+                PlanarConfiguration::Planar => 1,
+            };
+            for sample in 0..samples {
+                let bits = match self.planar {
+                    PlanarConfiguration::Chunky => self.sample_bits(sample),
+                    PlanarConfiguration::Planar => bits_per_pixel as u32,
+                };
+                values.push(self.sample_value(pixel, bit, bits)?);
+                bit += bits as usize;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Convert the `bits`-wide sample starting at bit `bit` of a decoded row to f64
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if the sample runs past the end of `row`
+    fn sample_value(&self, row: &[u8], bit: usize, bits: u32) -> Result<f64> {
+        let unsupported = || TiffError::UnsupportedFeature {
+            feature: format!("reading {bits}-bit {:?} samples", self.sample_format),
+        };
+        if bits == 0 || bits > 64 {
+            return Err(unsupported());
+        }
+
+        if !bit.is_multiple_of(8) || !bits.is_multiple_of(8) {
+            // Sub-byte samples are packed MSB-first within each byte
+            let mut raw = 0u64;
+            for i in bit..bit + bits as usize {
+                let mut byte = *row.get(i / 8).ok_or(TiffError::OutOfBounds {
+                    index: i / 8,
+                    max: row.len(),
+                })?;
+                if self.fill_order == FillOrder::LsbToMsb {
+                    byte = byte.reverse_bits();
+                }
+                raw = (raw << 1) | ((byte >> (7 - i % 8)) & 1) as u64;
+            }
+            return match self.sample_format {
+                SampleFormat::Int => Ok(((raw << (64 - bits)) as i64 >> (64 - bits)) as f64),
+                SampleFormat::Float => Err(unsupported()),
+                _ => Ok(raw as f64),
+            };
+        }
+
+        let end = bit / 8 + bits as usize / 8;
+        let bytes = row.get(bit / 8..end).ok_or(TiffError::OutOfBounds {
+            index: end - 1,
+            max: row.len(),
+        })?;
+        let raw = match bytes.len() {
+            1 => bytes[0] as u64,
+            2 => self.endian.read_u16([bytes[0], bytes[1]]) as u64,
+            4 => self.endian.read_u32([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64,
+            8 => self.endian.read_u64([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]),
+            _ => return Err(unsupported()),
+        };
+        Ok(match (self.sample_format, bits) {
+            (SampleFormat::Float, 16) => f16_to_f32(raw as u16) as f64,
+            (SampleFormat::Float, 32) => f32::from_bits(raw as u32) as f64,
+            (SampleFormat::Float, 64) => f64::from_bits(raw),
+            (SampleFormat::Float, _) => return Err(unsupported()),
+            (SampleFormat::Int, _) => ((raw << (64 - bits)) as i64 >> (64 - bits)) as f64,
+            _ => raw as f64,
+        })
+    }
+
+    /// Read one strip, or one row of tiles, as contiguous decoded rows
+    ///
+    /// Tile rows are stitched together and cropped to the image width.
+    fn read_row_block(&self, block: usize, plane: usize) -> Result<Vec<u8>> {
+        let Some((tile_width, tile_height)) = self.tile_size else {
+            return self.read_strip(plane * self.strips_per_image() + block);
+        };
+
+        let (across, _) = self.tile_grid().unwrap_or((0, 0));
+        let bits_per_pixel = self.plane_bits(plane);
+        let row_bytes = self.plane_row_bytes(plane)?;
+        let tile_row_bytes = self.tile_row_bytes(tile_width, plane)?;
+        let first_row = block * tile_height as usize;
+        let rows = (tile_height as usize).min((self.height as usize).saturating_sub(first_row));
+
+        let mut band = vec![0; rows * row_bytes];
+        for tile_x in 0..across {
+            let x_bits = tile_x as usize * tile_width as usize * bits_per_pixel;
+            if !x_bits.is_multiple_of(8) {
+                return Err(TiffError::UnsupportedFeature {
+                    feature: "tiles that start mid-byte".to_string(),
+                });
+            }
+            let start = x_bits / 8;
+            let len = tile_row_bytes.min(row_bytes - start);
+            let tile = self.read_tile_in_plane(plane, tile_x, block as u32)?;
+            let needed = rows.saturating_sub(1) * tile_row_bytes + len;
+            if tile.len() < needed {
+                return Err(TiffError::InsufficientData {
+                    operation: "decoding tile",
+                    needed,
+                    available: tile.len(),
+                });
+            }
+            for row in 0..rows {
+                band[row * row_bytes + start..][..len]
+                    .copy_from_slice(&tile[row * tile_row_bytes..][..len]);
+            }
+        }
+        Ok(band)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_data() -> Vec<u8> {
+        vec![
+            // TIFF header (8 bytes)
+            0x49, 0x49, // "II" - little endian
+            0x2A, 0x00, // Magic number 42
+            0x08, 0x00, 0x00, 0x00, // IFD offset 8
+            // Extra data for testing
+            0x12, 0x34, 0x56, 0x78, // 4 more bytes
+        ]
+    }
+
+    #[test]
+    fn test_in_memory_source_creation() {
+        let data = create_test_data();
+        let source = InMemorySource::new(data.clone());
+
+        assert_eq!(source.len(), data.len());
+        assert!(!source.is_empty());
+        assert_eq!(source.as_slice(), &data[..]);
+    }
+
+    #[test]
+    fn test_in_memory_source_bounds_checking() {
+        let data = vec![0x01, 0x02];
+        let source = InMemorySource::new(data);
+
+        // These should fail due to insufficient data
+        assert!(source.read_bytes_at(0, 10).is_err());
+        assert!(source.read_u16_at(1, Endian::Little).is_err());
+        assert!(source.read_u32_at(0, Endian::Little).is_err());
+
+        // offset + count wraps around usize
+        assert!(matches!(
+            source.read_bytes_at(usize::MAX, 2),
+            Err(TiffError::OutOfBounds { index: usize::MAX, max: 2 })
+        ));
+        assert!(source.read_bytes_at(1, usize::MAX).is_err());
+        assert!(source.read_u8_at(usize::MAX).is_err());
+        assert!(source.read_u16_at(usize::MAX - 1, Endian::Little).is_err());
+        assert!(source.read_u32_at(usize::MAX - 2, Endian::Big).is_err());
+    }
+
+    #[cfg(feature = "memmap2")]
+    #[test]
+    fn test_mmap_source() {
+        let path = std::env::temp_dir().join(format!("tiff-core-mmap-{}.tif", std::process::id()));
+        std::fs::write(&path, create_test_data()).unwrap();
+        let source = MmapSource::open(&path).unwrap();
+
+        assert_eq!(source.as_slice(), &create_test_data()[..]);
+        assert_eq!(source.read_u16_at(2, Endian::Little).unwrap(), 42);
+        assert_eq!(source.read_u32_at(8, Endian::Big).unwrap(), 0x1234_5678);
+        assert_eq!(source.read_bytes_at(10, 2).unwrap(), vec![0x56, 0x78]);
+        assert!(matches!(source.read_bytes_at(10, 3), Err(TiffError::OutOfBounds { index: 13, max: 12 })));
+        assert!(source.read_u32_at(9, Endian::Little).is_err());
+
+        let mut reader = TiffReader::new(source);
+        assert!(reader.read_header().unwrap().is_little_endian());
+        drop(reader);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_source() {
+        let path = std::env::temp_dir().join(format!("tiff-core-file-{}.tif", std::process::id()));
+        std::fs::write(&path, create_test_data()).unwrap();
+        let source = FileSource::open(&path).unwrap();
+
+        assert_eq!(source.len(), 12);
+        assert_eq!(source.read_u16_at(2, Endian::Little).unwrap(), 42);
+        assert_eq!(source.read_u32_at(8, Endian::Big).unwrap(), 0x1234_5678);
+        assert_eq!(source.read_bytes_at(10, 2).unwrap(), vec![0x56, 0x78]);
+        assert!(matches!(source.read_bytes_at(10, 3), Err(TiffError::OutOfBounds { index: 13, max: 12 })));
+
+        let mut reader = TiffReader::new(source);
+        assert!(reader.read_header().unwrap().is_little_endian());
+        drop(reader);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(FileSource::open(&path), Err(TiffError::Io(_))));
+    }
+
+    #[test]
+    fn test_in_memory_source_reading() {
+        let data = create_test_data();
+        let source = InMemorySource::new(data.clone());
+
+        // Test reading bytes
+        let bytes = source.read_bytes_at(0, 4).unwrap();
+        assert_eq!(bytes, &data[0..4]);
+
+        // Test reading primitives
+        assert_eq!(source.read_u8_at(0).unwrap(), 0x49);
+        assert_eq!(source.read_u16_at(0, Endian::Little).unwrap(), 0x4949);
+        assert_eq!(source.read_u32_at(4, Endian::Little).unwrap(), 0x00000008);
+    }
+
+    #[test]
+    fn test_reader_creation() {
+        let data = create_test_data();
+        let source = InMemorySource::new(data.clone());
+        let reader = TiffReader::new(source);
+
+        assert_eq!(reader.len(), data.len());
+        assert_eq!(reader.position(), 0);
+        assert!(!reader.is_empty());
+        assert!(!reader.is_at_end());
+    }
+
+    #[test]
+    fn test_seeking() {
+        let data = create_test_data();
+        let source = InMemorySource::new(data);
+        let mut reader = TiffReader::new(source);
+
+        assert!(reader.seek(5).is_ok());
+        assert_eq!(reader.position(), 5);
+
+        // Seeking past end should fail
+        assert!(reader.seek(1000).is_err());
+
+        // Seeking to end should work
+        assert!(reader.seek(reader.len()).is_ok());
+        assert_eq!(reader.position(), reader.len());
+    }
+
+    #[test]
+    fn test_stateful_reading() {
+        let data = create_test_data();
+        let source = InMemorySource::new(data);
+        let mut reader = TiffReader::new(source);
 
         // Read header components
         assert_eq!(reader.read_u8().unwrap(), 0x49);
@@ -576,6 +2337,9 @@ mod tests {
 
         // Skip past end should fail
         assert!(reader.skip(1000).is_err());
+        assert!(reader.skip(usize::MAX).is_err());
+        assert_eq!(reader.position(), 5);
+        assert!(reader.read_u16_array_at(2, usize::MAX, Endian::Little).is_err());
     }
 
     #[test]
@@ -608,4 +2372,1053 @@ mod tests {
         let value = reader.read_u32_array_at(2, 1, Endian::Big).unwrap();
         assert_eq!(value, vec![0x12345678]);
     }
+
+    /// Build an 10x2 bilevel file: row 0 is "1010101010", row 1 is all ones
+    fn bilevel_file(photometric: u16, fill_order: u16) -> Vec<u8> {
+        let mut rows = [0b1010_1010, 0b1000_0000, 0b1111_1111, 0b1100_0000];
+        if fill_order == 2 {
+            for byte in rows.iter_mut() {
+                *byte = u8::reverse_bits(*byte);
+            }
+        }
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&rows);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[10])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[1])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[photometric])
+            .short(tags::tags::FILL_ORDER, &[fill_order])
+            .long(tags::tags::ROWS_PER_STRIP, &[1])
+            .long(tags::tags::STRIP_OFFSETS, &[strip, strip + 2])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[2, 2]);
+        tiff.build()
+    }
+
+    fn read_bilevel_file(data: Vec<u8>, unpack: bool) -> (u32, u32, Vec<u8>) {
+        let mut reader = TiffReader::new(InMemorySource::new(data));
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+        let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+        image.read_bilevel(unpack).unwrap()
+    }
+
+    #[test]
+    fn test_read_bilevel_packed() {
+        let (width, height, packed) = read_bilevel_file(bilevel_file(1, 1), false);
+        assert_eq!((width, height), (10, 2));
+        assert_eq!(packed, vec![0b1010_1010, 0b1000_0000, 0b1111_1111, 0b1100_0000]);
+    }
+
+    #[test]
+    fn test_read_bilevel_unpacked() {
+        let (_, _, pixels) = read_bilevel_file(bilevel_file(1, 1), true);
+        assert_eq!(pixels.len(), 20);
+        assert_eq!(&pixels[..10], &[255, 0, 255, 0, 255, 0, 255, 0, 255, 0]);
+        assert_eq!(&pixels[10..], &[255; 10]);
+    }
+
+    #[test]
+    fn test_read_bilevel_white_is_zero_inverts() {
+        let (_, _, pixels) = read_bilevel_file(bilevel_file(0, 1), true);
+        assert_eq!(&pixels[..10], &[0, 255, 0, 255, 0, 255, 0, 255, 0, 255]);
+        assert_eq!(&pixels[10..], &[0; 10]);
+    }
+
+    #[test]
+    fn test_read_bilevel_lsb_fill_order() {
+        let msb = read_bilevel_file(bilevel_file(1, 1), true);
+        let lsb = read_bilevel_file(bilevel_file(1, 2), true);
+        assert_eq!(msb.2, lsb.2);
+    }
+
+    #[test]
+    fn test_read_bilevel_rejects_8_bit() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+        let mut reader = TiffReader::new(InMemorySource::new(tiff.build()));
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+        let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+        assert!(matches!(image.read_bilevel(false), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    /// Build a single-strip grayscale file with the given sample layout
+    fn sample_file(endian: Endian, bits: u16, format: u16, width: u16, strip: &[u8]) -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(endian);
+        let offset = tiff.push_data(strip);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[width])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[bits])
+            .short(tags::tags::SAMPLE_FORMAT, &[format])
+            .long(tags::tags::STRIP_OFFSETS, &[offset])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[strip.len() as u32]);
+        tiff.build()
+    }
+
+    /// Parse the first IFD of `data` and hand an image reader for it to `f`
+    fn with_image<R>(data: Vec<u8>, f: impl FnOnce(&TiffImageReader<'_, InMemorySource>) -> R) -> R {
+        let mut reader = TiffReader::new(InMemorySource::new(data));
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+        let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+        f(&image)
+    }
+
+    #[test]
+    fn test_read_strip_as_u16_big_endian() {
+        let data = sample_file(Endian::Big, 16, 1, 3, &[0x12, 0x34, 0x00, 0x01, 0xFF, 0xFE]);
+        let samples = with_image(data, |image| image.read_strip_as_u16(0).unwrap());
+        assert_eq!(samples, vec![0x1234, 0x0001, 0xFFFE]);
+    }
+
+    #[test]
+    fn test_read_strip_as_u16_little_endian() {
+        let data = sample_file(Endian::Little, 16, 1, 3, &[0x12, 0x34, 0x00, 0x01, 0xFF, 0xFE]);
+        let samples = with_image(data, |image| image.read_strip_as_u16(0).unwrap());
+        assert_eq!(samples, vec![0x3412, 0x0100, 0xFEFF]);
+    }
+
+    #[test]
+    fn test_read_strip_as_f32_big_endian() {
+        let strip: Vec<u8> = [1.5f32, -2.25].iter().flat_map(|v| v.to_be_bytes()).collect();
+        let data = sample_file(Endian::Big, 32, 3, 2, &strip);
+        let samples = with_image(data, |image| image.read_strip_as_f32(0).unwrap());
+        assert_eq!(samples, vec![1.5, -2.25]);
+    }
+
+    #[test]
+    fn test_read_strip_as_f32_half_precision() {
+        let data = sample_file(Endian::Little, 16, 3, 3, &[0x00, 0x3E, 0x00, 0xFC, 0x01, 0x00]);
+        let samples = with_image(data, |image| image.read_strip_as_f32(0).unwrap());
+        assert_eq!(samples, vec![1.5, f32::NEG_INFINITY, 5.960_464_5e-8]);
+    }
+
+    #[test]
+    fn test_read_strip_as_i16_negative_values() {
+        let values: [i16; 4] = [-1, -32768, 32767, -300];
+        for endian in [Endian::Little, Endian::Big] {
+            let strip: Vec<u8> = values
+                .iter()
+                .flat_map(|&v| crate::testutil::u16_bytes(endian, v as u16))
+                .collect();
+            let data = sample_file(endian, 16, 2, 4, &strip);
+            with_image(data, |image| {
+                assert_eq!(image.read_strip_as_i16(0).unwrap(), values.to_vec());
+                // A signed image must not be readable as unsigned
+                assert!(matches!(image.read_strip_as_u16(0), Err(TiffError::UnsupportedFeature { .. })));
+            });
+        }
+    }
+
+    #[test]
+    fn test_oversized_layout_is_rejected_before_decoding() {
+        /// A one-block image whose decoded size is set by the given tags
+        fn huge_file(tiled: bool, bits: &[u32]) -> Vec<u8> {
+            let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+            let block = tiff.push_data(&[0x00, 0x00]);
+            let ifd = tiff.add_ifd()
+                .long(tags::tags::IMAGE_WIDTH, &[u32::MAX])
+                .long(tags::tags::IMAGE_LENGTH, &[u32::MAX])
+                .long(tags::tags::BITS_PER_SAMPLE, bits)
+                .short(tags::tags::SAMPLES_PER_PIXEL, &[bits.len() as u16])
+                .short(tags::tags::COMPRESSION, &[32773]);
+            if tiled {
+                ifd.long(tags::tags::TILE_WIDTH, &[0xFFFF_FFF0])
+                    .long(tags::tags::TILE_LENGTH, &[0xFFFF_FFF0])
+                    .long(tags::tags::TILE_OFFSETS, &[block])
+                    .long(tags::tags::TILE_BYTE_COUNTS, &[2]);
+            } else {
+                ifd.long(tags::tags::STRIP_OFFSETS, &[block])
+                    .long(tags::tags::STRIP_BYTE_COUNTS, &[2]);
+            }
+            tiff.build()
+        }
+
+        let overflow_tag = |data: Vec<u8>, tiled: bool| {
+            with_image(data, |image| {
+                let result = if tiled { image.read_tile(0, 0) } else { image.read_strip(0) };
+                match result {
+                    Err(TiffError::InvalidTag { tag, .. }) => tag,
+                    other => panic!("expected InvalidTag, got {other:?}"),
+                }
+            })
+        };
+        assert_eq!(overflow_tag(huge_file(false, &[u32::MAX, u32::MAX]), false), tags::tags::IMAGE_WIDTH);
+        assert_eq!(overflow_tag(huge_file(false, &[8, 8]), false), tags::tags::ROWS_PER_STRIP);
+        assert_eq!(overflow_tag(huge_file(true, &[u32::MAX, u32::MAX]), true), tags::tags::TILE_WIDTH);
+        assert_eq!(overflow_tag(huge_file(true, &[16]), true), tags::tags::TILE_LENGTH);
+    }
+
+    #[test]
+    fn test_read_strip_as_u8() {
+        let data = sample_file(Endian::Little, 8, 1, 3, &[1, 2, 255]);
+        with_image(data, |image| assert_eq!(image.read_strip_as_u8(0).unwrap(), vec![1, 2, 255]));
+    }
+
+    #[test]
+    fn test_typed_read_rejects_wrong_depth() {
+        let data = sample_file(Endian::Little, 8, 1, 2, &[1, 2]);
+        with_image(data, |image| {
+            assert!(matches!(image.read_strip_as_u16(0), Err(TiffError::UnsupportedFeature { .. })));
+            assert!(matches!(image.read_strip_as_f32(0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    /// Build a 3x2-tile image of 2x2 one-byte tiles, each filled with its index
+    fn tiled_file() -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let offsets: Vec<u32> = (0..6u8).map(|i| tiff.push_data(&[i; 4])).collect();
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[5])
+            .short(tags::tags::IMAGE_LENGTH, &[4])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &offsets)
+            .long(tags::tags::TILE_BYTE_COUNTS, &[4; 6]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_read_tile() {
+        with_image(tiled_file(), |image| {
+            assert_eq!(image.tile_grid(), Some((3, 2)));
+            assert_eq!(image.read_tile(0, 0).unwrap(), vec![0; 4]);
+            assert_eq!(image.read_tile(2, 1).unwrap(), vec![5; 4]);
+            assert!(matches!(image.read_tile(3, 0), Err(TiffError::OutOfBounds { .. })));
+            assert!(matches!(image.read_strip(0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_tiles_in_order() {
+        with_image(tiled_file(), |image| {
+            let visit = |order| -> Vec<(u32, u32, u8)> {
+                image
+                    .tiles_in_order(order)
+                    .map(|tile| tile.map(|(x, y, bytes)| (x, y, bytes[0])).unwrap())
+                    .collect()
+            };
+
+            let row_major = visit(TileOrder::RowMajor);
+            assert_eq!(
+                row_major,
+                vec![(0, 0, 0), (1, 0, 1), (2, 0, 2), (0, 1, 3), (1, 1, 4), (2, 1, 5)]
+            );
+            assert_eq!(visit(TileOrder::default()), row_major);
+
+            let column_major: Vec<(u32, u32)> = visit(TileOrder::ColumnMajor).iter().map(|t| (t.0, t.1)).collect();
+            assert_eq!(column_major, vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+
+            let morton: Vec<(u32, u32)> = visit(TileOrder::Morton).iter().map(|t| (t.0, t.1)).collect();
+            assert_eq!(morton, vec![(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (2, 1)]);
+        });
+    }
+
+    #[test]
+    fn test_morton_code() {
+        assert_eq!(morton_code(0, 0), 0);
+        assert_eq!(morton_code(1, 0), 1);
+        assert_eq!(morton_code(0, 1), 2);
+        assert_eq!(morton_code(3, 3), 15);
+        assert_eq!(morton_code(4, 0), 16);
+    }
+
+    /// 4x4 RGB image with one strip per row; strips 1 and 3 point past the end of the file
+    fn rgb_preview_file() -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let row = |y: u8| -> Vec<u8> { (0..4u8).flat_map(|x| [x, y, 7]).collect() };
+        let row0 = tiff.push_data(&row(0));
+        let row2 = tiff.push_data(&row(2));
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[4])
+            .short(tags::tags::IMAGE_LENGTH, &[4])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .long(tags::tags::ROWS_PER_STRIP, &[1])
+            .long(tags::tags::STRIP_OFFSETS, &[row0, 100_000, row2, 100_000])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[12; 4]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_read_rgba8_downsampled_skips_unneeded_strips() {
+        with_image(rgb_preview_file(), |image| {
+            let (width, height, pixels) = image.read_rgba8_downsampled(2).unwrap();
+            assert_eq!((width, height), (2, 2));
+            assert_eq!(pixels, vec![0, 0, 7, 255, 2, 0, 7, 255, 0, 2, 7, 255, 2, 2, 7, 255]);
+            // Full resolution needs the missing strips
+            assert!(image.read_rgba8().is_err());
+            assert!(matches!(image.read_rgba8_downsampled(0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_band_chunky() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let pixels: Vec<u8> = (0..4u8).flat_map(|i| [i, 10 + i, 20 + i]).collect();
+        let strip = tiff.push_data(&pixels);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[12]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_band(0).unwrap(), (2, 2, vec![0, 1, 2, 3]));
+            assert_eq!(image.read_band(1).unwrap(), (2, 2, vec![10, 11, 12, 13]));
+            assert_eq!(image.read_band(2).unwrap(), (2, 2, vec![20, 21, 22, 23]));
+            assert!(matches!(image.read_band(3), Err(TiffError::OutOfBounds { index: 3, max: 3 })));
+        });
+    }
+
+    #[test]
+    fn test_read_band_planar_reads_only_that_plane() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let green_top = tiff.push_data(&[10, 11]);
+        let green_bottom = tiff.push_data(&[12, 13]);
+        // Red and blue strips point past the end of the file
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .short(tags::tags::PLANAR_CONFIGURATION, &[2])
+            .long(tags::tags::ROWS_PER_STRIP, &[1])
+            .long(tags::tags::STRIP_OFFSETS, &[100_000, 100_000, green_top, green_bottom, 100_000, 100_000])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[2; 6]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_band(1).unwrap(), (2, 2, vec![10, 11, 12, 13]));
+            assert!(image.read_band(0).is_err());
+            assert!(matches!(image.read_rgba8(), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_band_planar_tiled() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let offsets: Vec<u32> = (0..4u8).map(|i| tiff.push_data(&[i; 4])).collect();
+        // 3x2 image, 2x2 tiles, two planes of two tiles each
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[2])
+            .short(tags::tags::PLANAR_CONFIGURATION, &[2])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &offsets)
+            .long(tags::tags::TILE_BYTE_COUNTS, &[4; 4]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_band(0).unwrap(), (3, 2, vec![0, 0, 1, 0, 0, 1]));
+            assert_eq!(image.read_band(1).unwrap(), (3, 2, vec![2, 2, 3, 2, 2, 3]));
+            assert_eq!(image.decode_memory_estimate().unwrap(), 16);
+        });
+    }
+
+    #[test]
+    fn test_read_pixel_tiled_and_planar() {
+        with_image(tiled_file(), |image| {
+            assert_eq!(image.read_pixel(0, 0).unwrap(), vec![0.0]);
+            assert_eq!(image.read_pixel(4, 3).unwrap(), vec![5.0]);
+            assert_eq!(image.read_pixel(2, 1).unwrap(), vec![1.0]);
+            assert!(matches!(image.read_pixel(5, 0), Err(TiffError::OutOfBounds { .. })));
+            assert!(matches!(image.read_pixel(0, 4), Err(TiffError::OutOfBounds { .. })));
+        });
+
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Big);
+        // 2x2 planar image, one strip per row, two 16-bit signed samples
+        let offsets: Vec<u32> = [[0, 1, 0, 2], [0, 3, 0, 4], [0xFF, 0xFF, 0, 5], [0, 6, 0x80, 0]]
+            .iter()
+            .map(|strip| tiff.push_data(strip))
+            .collect();
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[16, 16])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[2])
+            .short(tags::tags::SAMPLE_FORMAT, &[2, 2])
+            .short(tags::tags::PLANAR_CONFIGURATION, &[2])
+            .short(tags::tags::ROWS_PER_STRIP, &[1])
+            .long(tags::tags::STRIP_OFFSETS, &offsets)
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4; 4]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_pixel(0, 0).unwrap(), vec![1.0, -1.0]);
+            assert_eq!(image.read_pixel(1, 1).unwrap(), vec![4.0, -32768.0]);
+        });
+    }
+
+    #[test]
+    fn test_read_pixel_sample_formats() {
+        let data = sample_file(Endian::Little, 32, 3, 2, &[0, 0, 0x80, 0x3F, 0, 0, 0x20, 0xC1]);
+        with_image(data, |image| assert_eq!(image.read_pixel(1, 0).unwrap(), vec![-10.0]));
+
+        // 4-bit unsigned samples packed two to a byte
+        let data = sample_file(Endian::Big, 4, 1, 3, &[0x3C, 0x70]);
+        with_image(data, |image| {
+            let row: Vec<f64> = (0..3).map(|x| image.read_pixel(x, 0).unwrap()[0]).collect();
+            assert_eq!(row, vec![3.0, 12.0, 7.0]);
+        });
+
+        // 16-bit floats are half precision
+        let data = sample_file(Endian::Big, 16, 3, 1, &[0x3C, 0x00]);
+        with_image(data, |image| assert_eq!(image.read_pixel(0, 0).unwrap(), vec![1.0]));
+
+        // Other float widths aren't supported
+        let data = sample_file(Endian::Big, 8, 3, 1, &[0x3C]);
+        with_image(data, |image| {
+            assert!(matches!(image.read_pixel(0, 0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_pixel_repeats_single_bits_per_sample() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[1, 2, 3, 4, 5, 6]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[6]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_pixel(1, 0).unwrap(), vec![4.0, 5.0, 6.0]);
+            assert!(matches!(image.sample_value(&[1], 8, 8), Err(TiffError::OutOfBounds { .. })));
+            assert!(matches!(image.sample_value(&[1], 4, 8), Err(TiffError::OutOfBounds { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_band_rejects_sub_byte_chunky_samples() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0xAB]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[1])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[4, 4])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[1]);
+        with_image(tiff.build(), |image| {
+            assert!(matches!(image.read_band(1), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    fn palette_file(bits: u16, fill_order: u16, rows: &[&[u8]], map_entries: usize) -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&rows.concat());
+        let entries = 0..map_entries as u16;
+        let map: Vec<u16> = entries.clone().map(|i| i * 0x0101)
+            .chain(entries.clone().map(|i| (255 - i) << 8))
+            .chain(entries.map(|_| 0xFFFF))
+            .collect();
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[rows.len() as u16])
+            .short(tags::tags::BITS_PER_SAMPLE, &[bits])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[3])
+            .short(tags::tags::FILL_ORDER, &[fill_order])
+            .short(tags::tags::COLORMAP, &map)
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[rows.concat().len() as u32]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_palette_rgba8_four_bit() {
+        let expected_indices = [1, 2, 3, 15, 0, 7];
+        let check = |data| {
+            let (width, height, pixels) = with_image(data, |image| image.palette_rgba8().unwrap());
+            assert_eq!((width, height), (3, 2));
+            let expected: Vec<u8> = expected_indices
+                .iter()
+                .flat_map(|&i: &u8| [i, 255 - i, 0xFF, 0xFF])
+                .collect();
+            assert_eq!(pixels, expected);
+        };
+        check(palette_file(4, 1, &[&[0x12, 0x30], &[0xF0, 0x70]], 16));
+        // The same indices stored least-significant bit first
+        check(palette_file(4, 2, &[&[0x48, 0x0C], &[0x0F, 0x0E]], 16));
+    }
+
+    #[test]
+    fn test_palette_rgba8_other_depths() {
+        let first_channel = |data| -> Vec<u8> {
+            with_image(data, |image| image.palette_rgba8().unwrap()).2.chunks(4).map(|p| p[0]).collect()
+        };
+        assert_eq!(first_channel(palette_file(1, 1, &[&[0b1010_0000]], 2)), vec![1, 0, 1]);
+        assert_eq!(first_channel(palette_file(2, 1, &[&[0b1100_1000]], 4)), vec![3, 0, 2]);
+        assert_eq!(first_channel(palette_file(8, 1, &[&[200, 0, 255]], 256)), vec![200, 0, 255]);
+    }
+
+    #[test]
+    fn test_apply_orientation_all_codes() {
+        // Stored 2x3 image:
+        //   1 2
+        //   3 4
+        //   5 6
+        let stored = [1, 2, 3, 4, 5, 6];
+        let cases: [(u32, [u8; 6], u32, u32); 8] = [
+            (1, [1, 2, 3, 4, 5, 6], 2, 3),
+            (2, [2, 1, 4, 3, 6, 5], 2, 3),
+            (3, [6, 5, 4, 3, 2, 1], 2, 3),
+            (4, [5, 6, 3, 4, 1, 2], 2, 3),
+            (5, [1, 3, 5, 2, 4, 6], 3, 2),
+            (6, [5, 3, 1, 6, 4, 2], 3, 2),
+            (7, [6, 4, 2, 5, 3, 1], 3, 2),
+            (8, [2, 4, 6, 1, 3, 5], 3, 2),
+        ];
+        for (code, expected, width, height) in cases {
+            let orientation = Orientation::from_u32(code).unwrap();
+            let result = apply_orientation(&stored, 2, 3, 1, orientation).unwrap();
+            assert_eq!(result, (expected.to_vec(), width, height), "orientation {code}");
+        }
+        assert_eq!(Orientation::from_u32(9), None);
+    }
+
+    #[test]
+    fn test_apply_orientation_multi_byte_pixels() {
+        // 3x1 RGB turned a quarter clockwise becomes 1x3
+        let stored = [1, 1, 1, 2, 2, 2, 3, 3, 3];
+        let (pixels, width, height) = apply_orientation(&stored, 3, 1, 3, Orientation::RightTop).unwrap();
+        assert_eq!((width, height), (1, 3));
+        assert_eq!(pixels, stored.to_vec());
+        let (pixels, _, _) = apply_orientation(&stored, 3, 1, 3, Orientation::LeftBottom).unwrap();
+        assert_eq!(pixels, vec![3, 3, 3, 2, 2, 2, 1, 1, 1]);
+
+        assert!(matches!(
+            apply_orientation(&stored[..8], 3, 1, 3, Orientation::TopLeft),
+            Err(TiffError::InsufficientData { needed: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unpack_bits() {
+        // 12 one-bit pixels take 2 bytes per row, the last 4 bits padding
+        let rows = [0b1010_0000, 0b1111_1111, 0b0000_0001, 0b1000_0000];
+        assert_eq!(
+            unpack_bits(&rows, 12, 1, 1).unwrap(),
+            vec![1, 0, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0]
+        );
+
+        // Two 2-bit pixels of 3 samples each: 12 bits, padded to 2 bytes
+        assert_eq!(unpack_bits(&[0b0001_1011, 0b1110_0000], 2, 2, 3).unwrap(), vec![0, 1, 2, 3, 3, 2]);
+        assert_eq!(unpack_bits(&[0x12, 0x3F, 0xAB, 0xC0], 3, 4, 1).unwrap(), vec![1, 2, 3, 10, 11, 12]);
+        // The trailing partial row is ignored
+        assert_eq!(unpack_bits(&[0x12, 0x3F, 0xAB], 3, 4, 1).unwrap(), vec![1, 2, 3]);
+        assert_eq!(unpack_bits(&[7, 8, 9], 3, 8, 1).unwrap(), vec![7, 8, 9]);
+        assert!(matches!(unpack_bits(&[0; 4], 2, 3, 1), Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_unpack_bits_u16() {
+        /// Pack 12-bit values MSB-first, padding each row to a byte
+        fn pack_12(rows: &[&[u16]]) -> Vec<u8> {
+            let mut out = Vec::new();
+            for row in rows {
+                for pair in row.chunks(2) {
+                    out.push((pair[0] >> 4) as u8);
+                    match pair.get(1) {
+                        Some(&second) => out.extend_from_slice(&[((pair[0] & 0xF) << 4 | second >> 8) as u8, second as u8]),
+                        None => out.push(((pair[0] & 0xF) << 4) as u8),
+                    }
+                }
+            }
+            out
+        }
+
+        let rows: [&[u16]; 2] = [&[0x000, 0xFFF, 0x123], &[0xABC, 0x800, 0x07F]];
+        let packed = pack_12(&rows);
+        assert_eq!(packed.len(), 10);
+        assert_eq!(&packed[..5], &[0x00, 0x0F, 0xFF, 0x12, 0x30]);
+        for endian in [Endian::Little, Endian::Big] {
+            assert_eq!(unpack_bits_u16(&packed, 3, 12, 1, endian).unwrap(), rows.concat());
+        }
+
+        assert_eq!(unpack_bits_u16(&[0x34, 0x12, 0xFF, 0x00], 2, 16, 1, Endian::Little).unwrap(), vec![0x1234, 0xFF]);
+        assert_eq!(unpack_bits_u16(&[0x12, 0x34], 1, 16, 1, Endian::Big).unwrap(), vec![0x1234]);
+        assert!(matches!(
+            unpack_bits_u16(&[0; 4], 2, 10, 1, Endian::Little),
+            Err(TiffError::UnsupportedFeature { .. })
+        ));
+    }
+
+    #[test]
+    fn test_f16_to_f32() {
+        let cases: [(u16, f32); 12] = [
+            (0x0000, 0.0),
+            (0x3C00, 1.0),
+            (0xC000, -2.0),
+            (0x3555, 0.333_251_95),
+            (0x7BFF, 65504.0),
+            (0x0400, 6.103_515_6e-5),
+            (0x0001, 5.960_464_5e-8),
+            (0x03FF, 6.097_555e-5),
+            (0x8001, -5.960_464_5e-8),
+            (0x7C00, f32::INFINITY),
+            (0xFC00, f32::NEG_INFINITY),
+            (0x3800, 0.5),
+        ];
+        for (bits, expected) in cases {
+            assert_eq!(f16_to_f32(bits), expected, "half bits {bits:#06x}");
+        }
+
+        assert_eq!(f16_to_f32(0x8000).to_bits(), (-0.0f32).to_bits());
+        for nan in [0x7E00, 0x7C01, 0xFE00] {
+            assert!(f16_to_f32(nan).is_nan(), "half bits {nan:#06x}");
+        }
+        assert_eq!(f16_to_f32(0x7E00).to_bits(), 0x7FC0_0000);
+    }
+
+    #[test]
+    fn test_expand_palette() {
+        let map: Vec<(u16, u16, u16)> = (0..4u16).map(|i| (i * 0x5555, 0xFFFF - i * 0x5555, 0x1234)).collect();
+        assert_eq!(
+            expand_palette(&[0, 3, 1], &map).unwrap(),
+            vec![0x00, 0xFF, 0x12, 0xFF, 0x00, 0x12, 0x55, 0xAA, 0x12]
+        );
+        assert!(expand_palette(&[], &map).unwrap().is_empty());
+
+        assert!(matches!(expand_palette(&[4], &map), Err(TiffError::OutOfBounds { index: 4, max: 4 })));
+        assert!(matches!(expand_palette(&[0], &map[..3]), Err(TiffError::InvalidTag { tag: 320, .. })));
+    }
+
+    #[test]
+    fn test_palette_rgba8_errors() {
+        // 4-bit indices need 16 entries
+        let data = palette_file(4, 1, &[&[0x12, 0x30]], 8);
+        with_image(data, |image| {
+            assert!(matches!(image.palette_rgba8(), Err(TiffError::InvalidTag { tag: 320, .. })));
+        });
+        with_image(rgb_preview_file(), |image| {
+            assert!(matches!(image.palette_rgba8(), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_rgba8_downsampled_tiled() {
+        with_image(tiled_file(), |image| {
+            let (width, height, pixels) = image.read_rgba8_downsampled(2).unwrap();
+            assert_eq!((width, height), (2, 2));
+            let gray: Vec<u8> = pixels.chunks(4).map(|p| p[0]).collect();
+            assert_eq!(gray, vec![0, 1, 3, 4]);
+
+            let (width, height, pixels) = image.read_rgba8().unwrap();
+            assert_eq!((width, height), (5, 4));
+            assert_eq!(pixels[4 * 4..4 * 5], [2, 2, 2, 255]);
+        });
+    }
+
+    #[test]
+    fn test_read_rgba8_bilevel_and_unsupported() {
+        let (width, height, pixels) = with_image(bilevel_file(0, 1), |image| image.read_rgba8().unwrap());
+        assert_eq!((width, height), (10, 2));
+        // WhiteIsZero: a set bit is black
+        assert_eq!(pixels[..8], [0, 0, 0, 255, 255, 255, 255, 255]);
+
+        let data = sample_file(Endian::Little, 16, 1, 1, &[0, 0]);
+        let result = with_image(data, |image| image.read_rgba8());
+        assert!(matches!(result, Err(TiffError::UnsupportedFeature { .. })));
+    }
+
+    #[test]
+    fn test_decode_memory_estimate() {
+        with_image(rgb_preview_file(), |image| assert_eq!(image.decode_memory_estimate().unwrap(), 48));
+        // 3x2 grid of 2x2 8-bit tiles, padding included
+        with_image(tiled_file(), |image| assert_eq!(image.decode_memory_estimate().unwrap(), 24));
+        with_image(bilevel_file(1, 1), |image| assert_eq!(image.decode_memory_estimate().unwrap(), 4));
+    }
+
+    /// Like `with_image`, but with a decode memory limit set
+    fn with_limited_image<R>(data: Vec<u8>, limit: u64, f: impl FnOnce(&TiffImageReader<'_, InMemorySource>) -> R) -> R {
+        let mut reader = TiffReader::new(InMemorySource::new(data));
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+        let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+        f(&image.with_max_decode_bytes(limit))
+    }
+
+    #[test]
+    fn test_decode_memory_limit() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 16]);
+        tiff.add_ifd()
+            .long(tags::tags::IMAGE_WIDTH, &[100_000])
+            .long(tags::tags::IMAGE_LENGTH, &[100_000])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[16]);
+        let data = tiff.build();
+
+        // Without a limit the (short) strip is read as-is
+        with_image(data.clone(), |image| {
+            assert_eq!(image.decode_memory_estimate().unwrap(), 10_000_000_000);
+            assert_eq!(image.read_strip(0).unwrap().len(), 16);
+        });
+        with_limited_image(data, 1 << 30, |image| {
+            for result in [image.read_strip(0).map(|_| ()), image.read_rgba8().map(|_| ())] {
+                match result {
+                    Err(TiffError::UnsupportedFeature { feature }) => {
+                        assert_eq!(feature, "image exceeds configured decode memory limit")
+                    }
+                    other => panic!("expected the decode limit error, got {other:?}"),
+                }
+            }
+        });
+
+        with_limited_image(rgb_preview_file(), 48, |image| {
+            assert!(image.read_rgba8_downsampled(2).is_ok());
+        });
+    }
+
+    /// A one-strip file of a few hundred bytes that claims 1,000,000 x 1,000,000 pixels
+    fn huge_claimed_file(bits: u16) -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 16]);
+        tiff.add_ifd()
+            .long(tags::tags::IMAGE_WIDTH, &[1_000_000])
+            .long(tags::tags::IMAGE_LENGTH, &[1_000_000])
+            .short(tags::tags::BITS_PER_SAMPLE, &[bits])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[16]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_decode_limit_guards_whole_image_reads() {
+        with_limited_image(huge_claimed_file(8), 1 << 20, |image| {
+            let results = [
+                image.read_image().map(|_| ()),
+                image.read_band(0).map(|_| ()),
+                image.read_rgba8().map(|_| ()),
+            ];
+            for result in results {
+                match result {
+                    Err(TiffError::UnsupportedFeature { feature }) => {
+                        assert_eq!(feature, "image exceeds configured decode memory limit")
+                    }
+                    other => panic!("expected the decode limit error, got {other:?}"),
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_read_image_of_huge_claimed_size() {
+        // The output isn't reserved from the claimed size, so the short strip is reported
+        let result = with_image(huge_claimed_file(8), |image| image.read_image());
+        assert!(matches!(result, Err(TiffError::InsufficientData { .. })), "{result:?}");
+
+        let result = with_image(huge_claimed_file(1), |image| image.read_bilevel(true));
+        assert!(matches!(result, Err(TiffError::InsufficientData { .. })), "{result:?}");
+        let result = with_limited_image(huge_claimed_file(1), 1 << 20, |image| image.read_bilevel(true));
+        assert!(matches!(result, Err(TiffError::UnsupportedFeature { .. })), "{result:?}");
+
+        let result = with_image(huge_claimed_file(8), |image| image.read_rgba8());
+        assert!(matches!(result, Err(TiffError::InsufficientData { .. })), "{result:?}");
+    }
+
+    #[test]
+    fn test_read_bytes_limited() {
+        let mut reader = TiffReader::new(InMemorySource::new(create_test_data()));
+        assert_eq!(reader.read_bytes_limited(2, 2).unwrap(), vec![0x49, 0x49]);
+        assert_eq!(reader.position(), 2);
+
+        match reader.read_bytes_limited(usize::MAX, 1024) {
+            Err(TiffError::MalformedFile { reason }) => assert!(reason.contains("1024-byte limit")),
+            other => panic!("expected MalformedFile, got {other:?}"),
+        }
+        assert_eq!(reader.position(), 2);
+    }
+
+    fn jpeg_file(tables: Option<&[u8]>, blocks: &[&[u8]]) -> Vec<u8> {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let offsets: Vec<u32> = blocks.iter().map(|block| tiff.push_data(block)).collect();
+        let counts: Vec<u32> = blocks.iter().map(|block| block.len() as u32).collect();
+        let ifd = tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[8])
+            .short(tags::tags::IMAGE_LENGTH, &[8 * blocks.len() as u16])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::COMPRESSION, &[7])
+            .long(tags::tags::ROWS_PER_STRIP, &[8])
+            .long(tags::tags::STRIP_OFFSETS, &offsets)
+            .long(tags::tags::STRIP_BYTE_COUNTS, &counts);
+        if let Some(tables) = tables {
+            ifd.raw(tags::tags::JPEG_TABLES, 7, tables.len() as u32, tables.to_vec());
+        }
+        tiff.build()
+    }
+
+    #[test]
+    fn test_read_jpeg_stream_splices_tables() {
+        let tables = [0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x02, 0xFF, 0xD9];
+        let block = [0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9];
+        with_image(jpeg_file(Some(&tables), &[&block, &block]), |image| {
+            let stream = image.read_jpeg_stream(1).unwrap();
+            assert_eq!(stream, vec![0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x02, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+            assert!(image.validate_jpeg_blocks().is_ok());
+            assert!(matches!(image.read_jpeg_stream(2), Err(TiffError::OutOfBounds { .. })));
+        });
+    }
+
+    #[test]
+    fn test_jpeg_decoder_receives_spliced_stream() {
+        let tables = [0xFF, 0xD8, 0xFF, 0xC4, 0x00, 0x02, 0xFF, 0xD9];
+        let block = [0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9];
+        let mut reader = TiffReader::new(InMemorySource::new(jpeg_file(Some(&tables), &[&block])));
+        let header = reader.read_header().unwrap();
+        let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+
+        let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+        assert!(matches!(image.read_strip(0), Err(TiffError::UnsupportedFeature { .. })));
+        let expected = image.read_jpeg_stream(0).unwrap();
+        let image = image.with_jpeg_decoder(move |stream| {
+            assert_eq!(stream, expected);
+            Ok(vec![0x80; 64])
+        });
+        assert_eq!(image.read_strip(0).unwrap(), vec![0x80; 64]);
+    }
+
+    #[test]
+    fn test_validate_jpeg_blocks_requires_soi() {
+        let good = [0xFF, 0xD8, 0xFF, 0xD9];
+        let bad = [0x00, 0x00, 0xFF, 0xD9];
+        with_image(jpeg_file(None, &[&good, &bad]), |image| {
+            assert!(matches!(image.validate_jpeg_blocks(), Err(TiffError::MalformedFile { .. })));
+        });
+        with_image(sample_file(Endian::Little, 8, 1, 1, &[0]), |image| {
+            assert!(matches!(image.read_jpeg_stream(0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_rows_per_strip_larger_than_height() {
+        for rows_per_strip in [1_000_000, u32::MAX, 0] {
+            let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+            let strip = tiff.push_data(&[7; 2 * 50]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[2])
+                .short(tags::tags::IMAGE_LENGTH, &[50])
+                .short(tags::tags::BITS_PER_SAMPLE, &[8])
+                .long(tags::tags::ROWS_PER_STRIP, &[rows_per_strip])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[100]);
+            with_image(tiff.build(), |image| {
+                assert_eq!(image.strips_per_image(), 1);
+                assert_eq!(image.strip_count(), 1);
+                assert_eq!(image.strip_rows(0), Some((0, 50)));
+                assert_eq!(image.strip_rows(1), None);
+                let (_, height, pixels) = image.read_rgba8_downsampled(5).unwrap();
+                assert_eq!(height, 10);
+                assert!(pixels.chunks(4).all(|p| p == [7, 7, 7, 255]));
+            });
+        }
+    }
+
+    #[test]
+    fn test_strip_rows_last_strip_is_short() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[0; 5]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[1])
+            .short(tags::tags::IMAGE_LENGTH, &[5])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::tags::ROWS_PER_STRIP, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip, strip + 2, strip + 4])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[2, 2, 1]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.strips_per_image(), 3);
+            assert_eq!(image.strip_rows(2), Some((4, 1)));
+            assert!(image.read_rgba8().is_ok());
+        });
+    }
+
+    #[test]
+    fn test_read_strip_lzw() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Big);
+        // ClearCode, 7, 258, 8, 8, 258, 6, 6, EndOfInformation as 9-bit codes
+        let strip = tiff.push_data(&[0x80, 0x01, 0xE0, 0x40, 0x80, 0x44, 0x08, 0x0C, 0x06, 0x80, 0x80]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[3])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::COMPRESSION, &[5])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[11]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_strip(0).unwrap(), vec![7, 7, 7, 8, 8, 7, 7, 6, 6]);
+        });
+    }
+
+    #[test]
+    fn test_raw_data_mode_skips_decompression() {
+        let lzw = [0x80, 0x01, 0xE0, 0x40, 0x80, 0x44, 0x08, 0x0C, 0x06, 0x80, 0x80];
+        for compression in [5, 3] {
+            let mut tiff = crate::testutil::TestTiff::new(Endian::Big);
+            let strip = tiff.push_data(&lzw);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[3])
+                .short(tags::tags::IMAGE_LENGTH, &[3])
+                .short(tags::tags::BITS_PER_SAMPLE, &[8])
+                .short(tags::tags::COMPRESSION, &[compression])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[11]);
+            let mut reader = TiffReader::new(InMemorySource::new(tiff.build()));
+            let header = reader.read_header().unwrap();
+            let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+
+            let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+            // CCITT (3) can't be decoded, but its stored bytes can still be read
+            assert_eq!(image.read_strip(0).is_ok(), compression == 5);
+            let image = image.with_raw_data(true);
+            assert_eq!(image.read_strip(0).unwrap(), lzw);
+        }
+    }
+
+    #[test]
+    fn test_registered_codec() {
+        /// Stands in for an external decoder: stored bytes are inverted
+        struct InvertCodec;
+
+        impl CompressionCodec for InvertCodec {
+            fn decompress(&self, data: &[u8], expected_len: usize) -> Result<Vec<u8>> {
+                assert_eq!(expected_len, 4);
+                Ok(data.iter().map(|b| !b).collect())
+            }
+        }
+
+        for compression in [Compression::Zstd as u16, Compression::None as u16] {
+            let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+            let strip = tiff.push_data(&[0xFF, 0xFE, 0x00, 0x7F]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[2])
+                .short(tags::tags::IMAGE_LENGTH, &[2])
+                .short(tags::tags::BITS_PER_SAMPLE, &[8])
+                .short(tags::tags::COMPRESSION, &[compression])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+            let mut reader = TiffReader::new(InMemorySource::new(tiff.build()));
+            let header = reader.read_header().unwrap();
+            let ifd = reader.read_ifd(header.ifd_offset as usize, header.endianness()).unwrap();
+
+            let image = TiffImageReader::new(&reader, &ifd, header.endianness()).unwrap();
+            assert_eq!(image.read_strip(0).is_ok(), compression == 1);
+            // A registered codec wins over the built-in one
+            let image = image.with_codec(compression as u32, InvertCodec);
+            assert_eq!(image.read_strip(0).unwrap(), vec![0x00, 0x01, 0xFF, 0x80]);
+        }
+    }
+
+    #[test]
+    fn test_read_strip_deflate_and_adobe_deflate() {
+        for compression in [8, 32946] {
+            let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+            // zlib.compress(bytes(range(6)) * 4, 9) from Python
+            let strip = tiff.push_data(&[
+                0x78, 0xDA, 0x63, 0x60, 0x64, 0x62, 0x66, 0x61, 0x65, 0xC0, 0x20, 0x01, 0x02, 0xC0, 0x00, 0x3D,
+            ]);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[6])
+                .short(tags::tags::IMAGE_LENGTH, &[4])
+                .short(tags::tags::BITS_PER_SAMPLE, &[8])
+                .short(tags::tags::COMPRESSION, &[compression])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[16]);
+            with_image(tiff.build(), |image| {
+                let expected: Vec<u8> = (0..4).flat_map(|_| 0..6).collect();
+                assert_eq!(image.read_strip(0).unwrap(), expected);
+            });
+        }
+    }
+
+    #[test]
+    fn test_read_strip_horizontal_predictor_rgb() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        // Original row: (10, 20, 30), (15, 25, 35), (5, 40, 30)
+        let strip = tiff.push_data(&[10, 20, 30, 5, 5, 5, 246, 15, 251]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[3])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8, 8, 8])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[3])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[2])
+            .short(tags::tags::PREDICTOR, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[9]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_strip(0).unwrap(), vec![10, 20, 30, 15, 25, 35, 5, 40, 30]);
+        });
+    }
+
+    #[test]
+    fn test_read_tile_horizontal_predictor_16_bit() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Big);
+        // One 2x1 tile row of differences: 1000, then +24
+        let tile = tiff.push_data(&[0x03, 0xE8, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[16])
+            .short(tags::tags::PREDICTOR, &[2])
+            .short(tags::tags::TILE_WIDTH, &[2])
+            .short(tags::tags::TILE_LENGTH, &[2])
+            .long(tags::tags::TILE_OFFSETS, &[tile])
+            .long(tags::tags::TILE_BYTE_COUNTS, &[8]);
+        with_image(tiff.build(), |image| {
+            assert_eq!(image.read_tile(0, 0).unwrap(), vec![0x03, 0xE8, 0x04, 0x00, 0, 0, 0, 0]);
+        });
+    }
+
+    #[test]
+    fn test_unsupported_predictor() {
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&[1, 2]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[2])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[4, 4])
+            .short(tags::tags::SAMPLES_PER_PIXEL, &[2])
+            .short(tags::tags::PREDICTOR, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[2]);
+        with_image(tiff.build(), |image| {
+            assert!(matches!(image.read_strip(0), Err(TiffError::UnsupportedFeature { .. })));
+        });
+    }
+
+    #[test]
+    fn test_read_bilevel_packbits() {
+        // Two 10-pixel rows (2 bytes each): a literal row, then a repeated 0xFF row,
+        // stored LSB-first so FillOrder also has to be honored
+        let packed = [0x01, 0b0101_0101, 0b0000_0001, 0xFF, 0xFF];
+        let mut tiff = crate::testutil::TestTiff::new(Endian::Little);
+        let strip = tiff.push_data(&packed);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[10])
+            .short(tags::tags::IMAGE_LENGTH, &[2])
+            .short(tags::tags::BITS_PER_SAMPLE, &[1])
+            .short(tags::tags::COMPRESSION, &[32773])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[1])
+            .short(tags::tags::FILL_ORDER, &[2])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[packed.len() as u32]);
+
+        let (width, height, pixels) = read_bilevel_file(tiff.build(), true);
+        assert_eq!((width, height), (10, 2));
+        assert_eq!(pixels[..10], [255, 0, 255, 0, 255, 0, 255, 0, 255, 0]);
+        assert_eq!(pixels[10..], [255; 10]);
+    }
 }
@@ -9,11 +9,14 @@
 ///
 /// These are the official tag numbers defined in the TIFF specification.
 /// Each tag represents a specific piece of metadata about the image.
+#[allow(clippy::module_inception)]
 pub mod tags {
     // =============================================================================
     // Basic image information
     // =============================================================================
 
+    /// Bit flags describing the kind of subfile (reduced resolution, page, mask)
+    pub const NEW_SUBFILE_TYPE: u16 = 254;
     /// Width of the image in pixels
     pub const IMAGE_WIDTH: u16 = 256;
     /// Height of the image in pixels  
@@ -37,6 +40,12 @@ pub mod tags {
     pub const ROWS_PER_STRIP: u16 = 278;
     /// Byte counts for strips
     pub const STRIP_BYTE_COUNTS: u16 = 279;
+    /// How samples are laid out: interleaved (chunky) or one plane per sample
+    pub const PLANAR_CONFIGURATION: u16 = 284;
+    /// Bit order within each byte (MSB-first or LSB-first)
+    pub const FILL_ORDER: u16 = 266;
+    /// How the stored rows and columns map to the displayed image
+    pub const ORIENTATION: u16 = 274;
 
     // =============================================================================
     // Resolution and units
@@ -59,6 +68,14 @@ pub mod tags {
     pub const EXTRA_SAMPLES: u16 = 338;
     /// Sample format (unsigned, signed, float, etc.)
     pub const SAMPLE_FORMAT: u16 = 339;
+    /// Luma coefficients for converting YCbCr to RGB (3 RATIONALs)
+    pub const YCBCR_COEFFICIENTS: u16 = 529;
+    /// Chroma subsampling factors (horizontal, vertical)
+    pub const YCBCR_SUB_SAMPLING: u16 = 530;
+    /// Position of subsampled chroma relative to luma (centered or cosited)
+    pub const YCBCR_POSITIONING: u16 = 531;
+    /// Reference black and white codes for each component (6 RATIONALs)
+    pub const REFERENCE_BLACK_WHITE: u16 = 532;
 
     // =============================================================================
     // Tiled images (alternative to strips)
@@ -79,6 +96,8 @@ pub mod tags {
 
     /// Predictor for compression (used with LZW and Deflate)
     pub const PREDICTOR: u16 = 317;
+    /// Shared quantization and Huffman tables for JPEG-compressed strips/tiles
+    pub const JPEG_TABLES: u16 = 347;
 
     // =============================================================================
     // Metadata
@@ -98,6 +117,60 @@ pub mod tags {
     pub const ARTIST: u16 = 315;
     /// Copyright notice
     pub const COPYRIGHT: u16 = 33432;
+    /// Page number and total page count of a multi-page document
+    pub const PAGE_NUMBER: u16 = 297;
+
+    // =============================================================================
+    // Embedded metadata blobs
+    // =============================================================================
+
+    /// Embedded ICC color profile
+    pub const ICC_PROFILE: u16 = 34675;
+    /// XMP metadata packet (XML)
+    pub const XMP: u16 = 700;
+    /// IPTC-NAA record (IIM metadata)
+    pub const IPTC: u16 = 33723;
+
+    // =============================================================================
+    // Pointers to other IFDs
+    // =============================================================================
+
+    /// Offsets to child IFDs (reduced-resolution images, thumbnails, ...)
+    pub const SUB_IFDS: u16 = 330;
+    /// Offset to the EXIF IFD
+    pub const EXIF_IFD: u16 = 34665;
+    /// Offset to the GPS IFD
+    pub const GPS_IFD: u16 = 34853;
+    /// Offset to the EXIF Interoperability IFD
+    pub const INTEROPERABILITY_IFD: u16 = 40965;
+
+    // =============================================================================
+    // EXIF tags (stored in the EXIF IFD)
+    // =============================================================================
+
+    /// Exposure time in seconds (RATIONAL)
+    pub const EXPOSURE_TIME: u16 = 33434;
+    /// F-number of the lens (RATIONAL)
+    pub const F_NUMBER: u16 = 33437;
+    /// ISO speed (PhotographicSensitivity in EXIF 2.3)
+    pub const ISO_SPEED_RATINGS: u16 = 34855;
+    /// Date and time the original image was captured
+    pub const DATE_TIME_ORIGINAL: u16 = 36867;
+    /// Lens focal length in millimetres (RATIONAL)
+    pub const FOCAL_LENGTH: u16 = 37386;
+
+    // =============================================================================
+    // GPS tags (stored in the GPS IFD, which numbers its tags from 0)
+    // =============================================================================
+
+    /// "N" or "S"
+    pub const GPS_LATITUDE_REF: u16 = 1;
+    /// Latitude as degrees, minutes, seconds (3 RATIONALs)
+    pub const GPS_LATITUDE: u16 = 2;
+    /// "E" or "W"
+    pub const GPS_LONGITUDE_REF: u16 = 3;
+    /// Longitude as degrees, minutes, seconds (3 RATIONALs)
+    pub const GPS_LONGITUDE: u16 = 4;
 
     // =============================================================================
     // GeoTIFF tags (we'll need these later)
@@ -122,6 +195,7 @@ pub mod tags {
 /// These values appear in the Compression tag (259) and tell us
 /// how the image data is compressed.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Compression {
     /// No compression
     None = 1,
@@ -143,6 +217,12 @@ pub enum Compression {
     AdobeDeflate = 32946,
     /// PackBits compression
     PackBits = 32773,
+    /// Limited Error Raster Compression (Esri)
+    Lerc = 34887,
+    /// Zstandard compression
+    Zstd = 50000,
+    /// WebP compression
+    WebP = 50001,
 }
 
 impl Compression {
@@ -159,19 +239,23 @@ impl Compression {
             8 => Some(Compression::Deflate),
             32946 => Some(Compression::AdobeDeflate),
             32773 => Some(Compression::PackBits),
+            34887 => Some(Compression::Lerc),
+            50000 => Some(Compression::Zstd),
+            50001 => Some(Compression::WebP),
             _ => None,
         }
     }
 
     /// Check if this compression type is supported by our parser
     pub fn is_supported(self) -> bool {
-        match self {
-            Compression::None => true,
-            Compression::PackBits => true, // TODO: implement
-            Compression::Lzw => false,     // TODO: implement
-            Compression::Deflate => false, // TODO: implement
-            _ => false,
-        }
+        matches!(
+            self,
+            Compression::None
+                | Compression::PackBits
+                | Compression::Lzw
+                | Compression::Deflate
+                | Compression::AdobeDeflate
+        )
     }
 }
 
@@ -180,6 +264,7 @@ impl Compression {
 /// These values appear in the PhotometricInterpretation tag (262)
 /// and tell us how to interpret the pixel values.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PhotometricInterpretation {
     /// Min value is white (bilevel/grayscale)
     WhiteIsZero = 0,
@@ -221,6 +306,7 @@ impl PhotometricInterpretation {
 /// These values appear in the ResolutionUnit tag (296) and specify
 /// the units for X/Y resolution values.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResolutionUnit {
     /// No absolute unit (just relative)
     None = 1,
@@ -247,6 +333,7 @@ impl ResolutionUnit {
 /// These values appear in the SampleFormat tag (339) and specify
 /// how to interpret the bits in each sample.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SampleFormat {
     /// Unsigned integer
     UInt = 1,
@@ -271,6 +358,101 @@ impl SampleFormat {
     }
 }
 
+/// Planar configuration values
+///
+/// These values appear in the PlanarConfiguration tag (284) and specify
+/// how the samples of each pixel are stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanarConfiguration {
+    /// Samples are interleaved per pixel, e.g. RGBRGB... (default)
+    Chunky = 1,
+    /// Each sample is stored in its own plane, e.g. RR...GG...BB...
+    Planar = 2,
+}
+
+impl PlanarConfiguration {
+    /// Convert from u32 to PlanarConfiguration
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(PlanarConfiguration::Chunky),
+            2 => Some(PlanarConfiguration::Planar),
+            _ => None,
+        }
+    }
+}
+
+/// Fill order values
+///
+/// These values appear in the FillOrder tag (266) and specify the
+/// order of pixels within each byte of sub-byte image data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillOrder {
+    /// Lower column values are stored in the higher-order bits (default)
+    MsbToLsb = 1,
+    /// Lower column values are stored in the lower-order bits
+    LsbToMsb = 2,
+}
+
+impl FillOrder {
+    /// Convert from u32 to FillOrder
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(FillOrder::MsbToLsb),
+            2 => Some(FillOrder::LsbToMsb),
+            _ => None,
+        }
+    }
+}
+
+/// Orientation values
+///
+/// These values appear in the Orientation tag (274) and say where the
+/// stored image's first row and first column belong when displayed,
+/// e.g. `RightTop` means row 0 is the right edge and column 0 the top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    /// Row 0 at the top, column 0 at the left (default)
+    TopLeft = 1,
+    /// Row 0 at the top, column 0 at the right (mirrored horizontally)
+    TopRight = 2,
+    /// Row 0 at the bottom, column 0 at the right (rotated 180 degrees)
+    BottomRight = 3,
+    /// Row 0 at the bottom, column 0 at the left (mirrored vertically)
+    BottomLeft = 4,
+    /// Row 0 at the left, column 0 at the top (transposed)
+    LeftTop = 5,
+    /// Row 0 at the right, column 0 at the top (needs a 90 degree
+    /// clockwise turn)
+    RightTop = 6,
+    /// Row 0 at the right, column 0 at the bottom (transversed)
+    RightBottom = 7,
+    /// Row 0 at the left, column 0 at the bottom (needs a 90 degree
+    /// counter-clockwise turn)
+    LeftBottom = 8,
+}
+
+impl Orientation {
+    /// Convert from u32 to Orientation
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Orientation::TopLeft),
+            2 => Some(Orientation::TopRight),
+            3 => Some(Orientation::BottomRight),
+            4 => Some(Orientation::BottomLeft),
+            5 => Some(Orientation::LeftTop),
+            6 => Some(Orientation::RightTop),
+            7 => Some(Orientation::RightBottom),
+            8 => Some(Orientation::LeftBottom),
+            _ => None,
+        }
+    }
+
+    /// Check if displaying the image swaps its width and height
+    pub fn swaps_dimensions(self) -> bool {
+        self as u32 >= 5
+    }
+}
+
 /// Extra sample types
 ///
 /// These values appear in the ExtraSamples tag (338) and specify
@@ -297,49 +479,205 @@ impl ExtraSample {
     }
 }
 
+/// Declare `Tag`, its conversions and `TAG_NAMES` from one table
+///
+/// Each row is a variant, its tag number and its display name, so the
+/// enum, `from_u16`, `as_u16` and the name lookups can't drift apart.
+macro_rules! known_tags {
+    ($($(#[doc = $doc:literal])* $variant:ident = $code:path, $name:literal;)*) => {
+        /// A known TIFF tag
+        ///
+        /// A typed alternative to the `tags` constants, which remain available.
+        /// Numbers without a variant are kept as `Unknown`; GPS tags live in their
+        /// own numbering space inside the GPS IFD and have no variants.
+        ///
+        /// Tags compare and hash by number, so `Tag::Unknown(256)` equals
+        /// `Tag::ImageWidth`.
+        #[derive(Debug, Clone, Copy)]
+        pub enum Tag {
+            $($(#[doc = $doc])* $variant,)*
+            /// Any tag without a variant of its own
+            Unknown(u16),
+        }
+
+        impl Tag {
+            /// Convert from a tag number
+            ///
+            /// Known numbers always map to their variant, never to `Unknown`.
+            pub fn from_u16(value: u16) -> Self {
+                match value {
+                    $($code => Tag::$variant,)*
+                    other => Tag::Unknown(other),
+                }
+            }
+
+            /// Get the tag number
+            pub fn as_u16(self) -> u16 {
+                match self {
+                    $(Tag::$variant => $code,)*
+                    Tag::Unknown(value) => value,
+                }
+            }
+        }
+
+        /// Known tags and their names, shared by `tag_name` and `tag_from_name`
+        const TAG_NAMES: &[(u16, &str)] = &[$(($code, $name),)*];
+    };
+}
+
+known_tags! {
+    /// Bit flags describing the kind of subfile (reduced resolution, page, mask)
+    NewSubfileType = tags::NEW_SUBFILE_TYPE, "NewSubfileType";
+    /// Width of the image in pixels
+    ImageWidth = tags::IMAGE_WIDTH, "ImageWidth";
+    /// Height of the image in pixels
+    ImageLength = tags::IMAGE_LENGTH, "ImageLength";
+    /// Number of bits per sample (per channel)
+    BitsPerSample = tags::BITS_PER_SAMPLE, "BitsPerSample";
+    /// Compression scheme used on the image data
+    Compression = tags::COMPRESSION, "Compression";
+    /// Color space interpretation of the image data
+    PhotometricInterpretation = tags::PHOTOMETRIC_INTERPRETATION, "PhotometricInterpretation";
+    /// Offsets to strips of image data
+    StripOffsets = tags::STRIP_OFFSETS, "StripOffsets";
+    /// Number of samples (channels) per pixel
+    SamplesPerPixel = tags::SAMPLES_PER_PIXEL, "SamplesPerPixel";
+    /// Number of rows per strip
+    RowsPerStrip = tags::ROWS_PER_STRIP, "RowsPerStrip";
+    /// Byte counts for strips
+    StripByteCounts = tags::STRIP_BYTE_COUNTS, "StripByteCounts";
+    /// Bit order within each byte (MSB-first or LSB-first)
+    FillOrder = tags::FILL_ORDER, "FillOrder";
+    /// How the stored rows and columns map to the displayed image
+    Orientation = tags::ORIENTATION, "Orientation";
+    /// How samples are laid out: interleaved (chunky) or one plane per sample
+    PlanarConfiguration = tags::PLANAR_CONFIGURATION, "PlanarConfiguration";
+    /// Horizontal resolution (pixels per resolution unit)
+    XResolution = tags::X_RESOLUTION, "XResolution";
+    /// Vertical resolution (pixels per resolution unit)
+    YResolution = tags::Y_RESOLUTION, "YResolution";
+    /// Resolution unit (inches, centimeters, etc.)
+    ResolutionUnit = tags::RESOLUTION_UNIT, "ResolutionUnit";
+    /// Color map for palette images
+    ColorMap = tags::COLORMAP, "ColorMap";
+    /// Width of tiles in pixels
+    TileWidth = tags::TILE_WIDTH, "TileWidth";
+    /// Height of tiles in pixels
+    TileLength = tags::TILE_LENGTH, "TileLength";
+    /// Offsets to tiles of image data
+    TileOffsets = tags::TILE_OFFSETS, "TileOffsets";
+    /// Byte counts for tiles
+    TileByteCounts = tags::TILE_BYTE_COUNTS, "TileByteCounts";
+    /// Predictor for compression (used with LZW and Deflate)
+    Predictor = tags::PREDICTOR, "Predictor";
+    /// Shared quantization and Huffman tables for JPEG-compressed strips/tiles
+    JpegTables = tags::JPEG_TABLES, "JPEGTables";
+    /// Sample format (unsigned, signed, float, etc.)
+    SampleFormat = tags::SAMPLE_FORMAT, "SampleFormat";
+    /// Extra samples (alpha channel, etc.)
+    ExtraSamples = tags::EXTRA_SAMPLES, "ExtraSamples";
+    /// Luma coefficients for converting YCbCr to RGB (3 RATIONALs)
+    YCbCrCoefficients = tags::YCBCR_COEFFICIENTS, "YCbCrCoefficients";
+    /// Chroma subsampling factors (horizontal, vertical)
+    YCbCrSubSampling = tags::YCBCR_SUB_SAMPLING, "YCbCrSubSampling";
+    /// Position of subsampled chroma relative to luma (centered or cosited)
+    YCbCrPositioning = tags::YCBCR_POSITIONING, "YCbCrPositioning";
+    /// Reference black and white codes for each component (6 RATIONALs)
+    ReferenceBlackWhite = tags::REFERENCE_BLACK_WHITE, "ReferenceBlackWhite";
+    /// Image description/title
+    ImageDescription = tags::IMAGE_DESCRIPTION, "ImageDescription";
+    /// Make of scanner/camera
+    Make = tags::MAKE, "Make";
+    /// Model of scanner/camera
+    Model = tags::MODEL, "Model";
+    /// Software used to create the image
+    Software = tags::SOFTWARE, "Software";
+    /// Date and time of image creation
+    DateTime = tags::DATE_TIME, "DateTime";
+    /// Artist/photographer
+    Artist = tags::ARTIST, "Artist";
+    /// Copyright notice
+    Copyright = tags::COPYRIGHT, "Copyright";
+    /// Page number and total page count of a multi-page document
+    PageNumber = tags::PAGE_NUMBER, "PageNumber";
+    /// Embedded ICC color profile
+    IccProfile = tags::ICC_PROFILE, "InterColorProfile";
+    /// XMP metadata packet (XML)
+    Xmp = tags::XMP, "XMP";
+    /// IPTC-NAA record (IIM metadata)
+    Iptc = tags::IPTC, "IPTC-NAA";
+    /// Offsets to child IFDs (reduced-resolution images, thumbnails, ...)
+    SubIfds = tags::SUB_IFDS, "SubIFDs";
+    /// Offset to the EXIF IFD
+    ExifIfd = tags::EXIF_IFD, "ExifIFD";
+    /// Offset to the GPS IFD
+    GpsIfd = tags::GPS_IFD, "GPSIFD";
+    /// Offset to the EXIF Interoperability IFD
+    InteroperabilityIfd = tags::INTEROPERABILITY_IFD, "InteroperabilityIFD";
+    /// Exposure time in seconds (RATIONAL)
+    ExposureTime = tags::EXPOSURE_TIME, "ExposureTime";
+    /// F-number of the lens (RATIONAL)
+    FNumber = tags::F_NUMBER, "FNumber";
+    /// ISO speed (PhotographicSensitivity in EXIF 2.3)
+    IsoSpeedRatings = tags::ISO_SPEED_RATINGS, "ISOSpeedRatings";
+    /// Date and time the original image was captured
+    DateTimeOriginal = tags::DATE_TIME_ORIGINAL, "DateTimeOriginal";
+    /// Lens focal length in millimetres (RATIONAL)
+    FocalLength = tags::FOCAL_LENGTH, "FocalLength";
+    /// Model pixel scale (for geographic images)
+    ModelPixelScale = tags::MODEL_PIXEL_SCALE, "ModelPixelScale";
+    /// Model tie points (for geographic images)
+    ModelTiepoint = tags::MODEL_TIEPOINT, "ModelTiepoint";
+    /// Model transformation matrix
+    ModelTransformation = tags::MODEL_TRANSFORMATION, "ModelTransformation";
+    /// GeoKey directory
+    GeoKeyDirectory = tags::GEO_KEY_DIRECTORY, "GeoKeyDirectory";
+    /// GeoKey double parameters
+    GeoDoubleParams = tags::GEO_DOUBLE_PARAMS, "GeoDoubleParams";
+    /// GeoKey ASCII parameters
+    GeoAsciiParams = tags::GEO_ASCII_PARAMS, "GeoAsciiParams";
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_u16() == other.as_u16()
+    }
+}
+
+impl Eq for Tag {}
+
+impl core::hash::Hash for Tag {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_u16().hash(state);
+    }
+}
+
 /// Get a human-readable name for a tag
 ///
 /// This is useful for debugging and displaying tag information.
 pub fn tag_name(tag: u16) -> &'static str {
-    match tag {
-        tags::IMAGE_WIDTH => "ImageWidth",
-        tags::IMAGE_LENGTH => "ImageLength",
-        tags::BITS_PER_SAMPLE => "BitsPerSample",
-        tags::COMPRESSION => "Compression",
-        tags::PHOTOMETRIC_INTERPRETATION => "PhotometricInterpretation",
-        tags::STRIP_OFFSETS => "StripOffsets",
-        tags::SAMPLES_PER_PIXEL => "SamplesPerPixel",
-        tags::ROWS_PER_STRIP => "RowsPerStrip",
-        tags::STRIP_BYTE_COUNTS => "StripByteCounts",
-        tags::X_RESOLUTION => "XResolution",
-        tags::Y_RESOLUTION => "YResolution",
-        tags::RESOLUTION_UNIT => "ResolutionUnit",
-        tags::COLORMAP => "ColorMap",
-        tags::TILE_WIDTH => "TileWidth",
-        tags::TILE_LENGTH => "TileLength",
-        tags::TILE_OFFSETS => "TileOffsets",
-        tags::TILE_BYTE_COUNTS => "TileByteCounts",
-        tags::PREDICTOR => "Predictor",
-        tags::SAMPLE_FORMAT => "SampleFormat",
-        tags::EXTRA_SAMPLES => "ExtraSamples",
-        tags::IMAGE_DESCRIPTION => "ImageDescription",
-        tags::MAKE => "Make",
-        tags::MODEL => "Model",
-        tags::SOFTWARE => "Software",
-        tags::DATE_TIME => "DateTime",
-        tags::ARTIST => "Artist",
-        tags::COPYRIGHT => "Copyright",
-        tags::MODEL_PIXEL_SCALE => "ModelPixelScale",
-        tags::MODEL_TIEPOINT => "ModelTiepoint",
-        tags::MODEL_TRANSFORMATION => "ModelTransformation",
-        tags::GEO_KEY_DIRECTORY => "GeoKeyDirectory",
-        tags::GEO_DOUBLE_PARAMS => "GeoDoubleParams",
-        tags::GEO_ASCII_PARAMS => "GeoAsciiParams",
-        _ => "Unknown",
-    }
+    TAG_NAMES
+        .iter()
+        .find(|(known, _)| *known == tag)
+        .map_or("Unknown", |(_, name)| name)
+}
+
+/// Look up a tag number by its name
+///
+/// This is the inverse of `tag_name`, matching case-insensitively, so
+/// "ImageWidth" and "imagewidth" both give 256.
+///
+/// # Returns
+/// The tag number, or `None` if the name isn't a known tag
+pub fn tag_from_name(name: &str) -> Option<u16> {
+    TAG_NAMES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+        .map(|(tag, _)| *tag)
 }
 
 /// Check if a tag is required for basic TIFF compliance
+#[allow(clippy::match_like_matches_macro)]
 pub fn is_required_tag(tag: u16) -> bool {
     match tag {
         tags::IMAGE_WIDTH | tags::IMAGE_LENGTH | tags::STRIP_OFFSETS | tags::STRIP_BYTE_COUNTS => {
@@ -350,6 +688,7 @@ pub fn is_required_tag(tag: u16) -> bool {
 }
 
 /// Check if a tag contains image layout information
+#[allow(clippy::match_like_matches_macro)]
 pub fn is_layout_tag(tag: u16) -> bool {
     match tag {
         tags::IMAGE_WIDTH
@@ -364,6 +703,7 @@ pub fn is_layout_tag(tag: u16) -> bool {
 }
 
 /// Check if a tag contains image data location information
+#[allow(clippy::match_like_matches_macro)]
 pub fn is_data_location_tag(tag: u16) -> bool {
     match tag {
         tags::STRIP_OFFSETS
@@ -383,6 +723,9 @@ mod tests {
         assert_eq!(Compression::from_u32(1), Some(Compression::None));
         assert_eq!(Compression::from_u32(5), Some(Compression::Lzw));
         assert_eq!(Compression::from_u32(32773), Some(Compression::PackBits));
+        assert_eq!(Compression::from_u32(50000), Some(Compression::Zstd));
+        assert_eq!(Compression::from_u32(34887), Some(Compression::Lerc));
+        assert_eq!(Compression::from_u32(50001), Some(Compression::WebP));
         assert_eq!(Compression::from_u32(99999), None);
     }
 
@@ -390,7 +733,9 @@ mod tests {
     fn test_compression_support() {
         assert!(Compression::None.is_supported());
         assert!(Compression::PackBits.is_supported());
-        assert!(!Compression::Lzw.is_supported()); // TODO: implement
+        assert!(Compression::Lzw.is_supported());
+        assert!(Compression::Deflate.is_supported());
+        assert!(Compression::AdobeDeflate.is_supported());
         assert!(!Compression::Jpeg.is_supported());
     }
 
@@ -411,6 +756,35 @@ mod tests {
     fn test_tag_names() {
         assert_eq!(tag_name(tags::IMAGE_WIDTH), "ImageWidth");
         assert_eq!(tag_name(tags::COMPRESSION), "Compression");
+        assert_eq!(tag_name(65000), "Unknown");
+    }
+
+    #[test]
+    fn test_tag_enum_round_trip() {
+        assert_eq!(Tag::from_u16(256), Tag::ImageWidth);
+        assert_eq!(Tag::ImageWidth.as_u16(), tags::IMAGE_WIDTH);
+        assert_eq!(Tag::from_u16(65000), Tag::Unknown(65000));
+        assert_eq!(Tag::Unknown(65000).as_u16(), 65000);
+        // Unknown(n) for a known number is the same tag
+        assert_eq!(Tag::Unknown(256), Tag::ImageWidth);
+        assert_ne!(Tag::Unknown(257), Tag::ImageWidth);
+        for &(tag, _) in TAG_NAMES {
+            let typed = Tag::from_u16(tag);
+            assert!(!matches!(typed, Tag::Unknown(_)), "{tag} has no variant");
+            assert_eq!(typed.as_u16(), tag);
+        }
+    }
+
+    #[test]
+    fn test_tag_from_name() {
+        assert_eq!(tag_from_name("ImageWidth"), Some(tags::IMAGE_WIDTH));
+        assert_eq!(tag_from_name("imagewidth"), Some(tags::IMAGE_WIDTH));
+        assert_eq!(tag_from_name("IPTC-NAA"), Some(tags::IPTC));
+        assert_eq!(tag_from_name("Unknown"), None);
+        assert_eq!(tag_from_name("ImageWidthh"), None);
+        for &(tag, name) in TAG_NAMES {
+            assert_eq!(tag_from_name(name), Some(tag), "{name}");
+        }
     }
 
     #[test]
@@ -446,6 +820,32 @@ mod tests {
         assert_eq!(SampleFormat::from_u32(3), Some(SampleFormat::Float));
     }
 
+    #[test]
+    fn test_fill_order() {
+        assert_eq!(FillOrder::from_u32(1), Some(FillOrder::MsbToLsb));
+        assert_eq!(FillOrder::from_u32(2), Some(FillOrder::LsbToMsb));
+        assert_eq!(FillOrder::from_u32(3), None);
+        assert_eq!(tag_name(tags::FILL_ORDER), "FillOrder");
+    }
+
+    #[test]
+    fn test_orientation() {
+        assert_eq!(Orientation::from_u32(1), Some(Orientation::TopLeft));
+        assert_eq!(Orientation::from_u32(6), Some(Orientation::RightTop));
+        assert_eq!(Orientation::from_u32(0), None);
+        assert!(!Orientation::BottomLeft.swaps_dimensions());
+        assert!(Orientation::LeftTop.swaps_dimensions());
+        assert_eq!(tag_name(tags::ORIENTATION), "Orientation");
+    }
+
+    #[test]
+    fn test_planar_configuration() {
+        assert_eq!(PlanarConfiguration::from_u32(1), Some(PlanarConfiguration::Chunky));
+        assert_eq!(PlanarConfiguration::from_u32(2), Some(PlanarConfiguration::Planar));
+        assert_eq!(PlanarConfiguration::from_u32(0), None);
+        assert_eq!(tag_name(tags::PLANAR_CONFIGURATION), "PlanarConfiguration");
+    }
+
     #[test]
     fn test_extra_samples() {
         assert_eq!(ExtraSample::from_u32(1), Some(ExtraSample::AssociatedAlpha));
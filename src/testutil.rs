@@ -0,0 +1,220 @@
+// tiff-core/src/testutil.rs
+//! Helpers for building small TIFF files in unit tests
+//!
+//! Tests across the crate need real byte layouts to parse. `TestTiff` lays
+//! out a header, any raw data blocks (strips, tiles, blobs), and a chain of
+//! IFDs whose out-of-line values are placed directly after each directory.
+
+#![allow(dead_code)]
+
+use crate::header::Endian;
+use crate::ifd::ImageFileDirectory;
+use crate::reader::{InMemorySource, TiffReader};
+
+/// A tag entry waiting to be laid out: (tag, field type, count, value bytes)
+type PendingEntry = (u16, u16, u32, Vec<u8>);
+
+/// Minimal TIFF file builder for tests
+pub(crate) struct TestTiff {
+    endian: Endian,
+    data: Vec<u8>,
+    ifds: Vec<Vec<PendingEntry>>,
+    /// Region reserved right after the header for the IFDs, if any
+    ifd_area: Option<(usize, usize)>,
+}
+
+impl TestTiff {
+    /// Start a new file with the given byte order
+    pub(crate) fn new(endian: Endian) -> Self {
+        let mut data = match endian {
+            Endian::Little => vec![b'I', b'I'],
+            Endian::Big => vec![b'M', b'M'],
+        };
+        data.extend_from_slice(&u16_bytes(endian, 42));
+        data.extend_from_slice(&[0; 4]); // first IFD offset, patched in build()
+        Self { endian, data, ifds: Vec::new(), ifd_area: None }
+    }
+
+    /// Reserve `size` bytes for the IFDs ahead of any data blocks
+    ///
+    /// Must be called before `push_data`. Lets tests build files where the
+    /// directories precede the image data (e.g. Cloud-Optimized GeoTIFFs).
+    pub(crate) fn reserve_ifd_area(&mut self, size: usize) -> &mut Self {
+        self.ifd_area = Some((self.data.len(), size));
+        self.data.resize(self.data.len() + size, 0);
+        self
+    }
+
+    /// Append a raw data block (e.g. strip data) and return its offset
+    pub(crate) fn push_data(&mut self, bytes: &[u8]) -> u32 {
+        if self.data.len() % 2 == 1 {
+            self.data.push(0);
+        }
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(bytes);
+        offset
+    }
+
+    /// Start a new IFD; subsequent tag calls add entries to it
+    pub(crate) fn add_ifd(&mut self) -> &mut Self {
+        self.ifds.push(Vec::new());
+        self
+    }
+
+    /// Add an entry with pre-encoded value bytes (in file byte order)
+    pub(crate) fn raw(&mut self, tag: u16, field_type: u16, count: u32, bytes: Vec<u8>) -> &mut Self {
+        self.ifds
+            .last_mut()
+            .expect("call add_ifd() before adding tags")
+            .push((tag, field_type, count, bytes));
+        self
+    }
+
+    /// Add a BYTE entry
+    pub(crate) fn bytes(&mut self, tag: u16, values: &[u8]) -> &mut Self {
+        self.raw(tag, 1, values.len() as u32, values.to_vec())
+    }
+
+    /// Add an ASCII entry (a null terminator is appended)
+    pub(crate) fn ascii(&mut self, tag: u16, value: &str) -> &mut Self {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.push(0);
+        self.raw(tag, 2, bytes.len() as u32, bytes)
+    }
+
+    /// Add a SHORT entry
+    pub(crate) fn short(&mut self, tag: u16, values: &[u16]) -> &mut Self {
+        let endian = self.endian;
+        let bytes = values.iter().flat_map(|&v| u16_bytes(endian, v)).collect();
+        self.raw(tag, 3, values.len() as u32, bytes)
+    }
+
+    /// Add a LONG entry
+    pub(crate) fn long(&mut self, tag: u16, values: &[u32]) -> &mut Self {
+        let endian = self.endian;
+        let bytes = values.iter().flat_map(|&v| u32_bytes(endian, v)).collect();
+        self.raw(tag, 4, values.len() as u32, bytes)
+    }
+
+    /// Add a RATIONAL entry
+    pub(crate) fn rational(&mut self, tag: u16, values: &[(u32, u32)]) -> &mut Self {
+        let endian = self.endian;
+        let bytes = values
+            .iter()
+            .flat_map(|&(n, d)| [u32_bytes(endian, n), u32_bytes(endian, d)].concat())
+            .collect();
+        self.raw(tag, 5, values.len() as u32, bytes)
+    }
+
+    /// Add a DOUBLE entry
+    pub(crate) fn double(&mut self, tag: u16, values: &[f64]) -> &mut Self {
+        let bytes = values
+            .iter()
+            .flat_map(|&v| match self.endian {
+                Endian::Little => v.to_le_bytes(),
+                Endian::Big => v.to_be_bytes(),
+            })
+            .collect();
+        self.raw(tag, 12, values.len() as u32, bytes)
+    }
+
+    /// Lay out all IFDs and return the finished file
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let endian = self.endian;
+        let mut data = self.data.clone();
+        let base = self.ifd_area.map_or(data.len(), |(start, _)| start);
+        let mut block: Vec<u8> = Vec::new();
+        let mut link: Option<usize> = None; // position of the previous next-IFD link in `block`
+
+        for entries in &self.ifds {
+            if (base + block.len()) % 2 == 1 {
+                block.push(0);
+            }
+            let ifd_offset = (base + block.len()) as u32;
+            match link {
+                Some(position) => block[position..position + 4].copy_from_slice(&u32_bytes(endian, ifd_offset)),
+                None => data[4..8].copy_from_slice(&u32_bytes(endian, ifd_offset)),
+            }
+
+            let mut entries = entries.clone();
+            entries.sort_by_key(|entry| entry.0);
+
+            let ifd_size = 2 + entries.len() * 12 + 4;
+            let mut values_offset = ifd_offset as usize + ifd_size;
+            let mut values = Vec::new();
+
+            block.extend_from_slice(&u16_bytes(endian, entries.len() as u16));
+            for (tag, field_type, count, bytes) in &entries {
+                block.extend_from_slice(&u16_bytes(endian, *tag));
+                block.extend_from_slice(&u16_bytes(endian, *field_type));
+                block.extend_from_slice(&u32_bytes(endian, *count));
+                if bytes.len() <= 4 {
+                    let mut slot = bytes.clone();
+                    slot.resize(4, 0);
+                    block.extend_from_slice(&slot);
+                } else {
+                    block.extend_from_slice(&u32_bytes(endian, values_offset as u32));
+                    values.extend_from_slice(bytes);
+                    if bytes.len() % 2 == 1 {
+                        values.push(0);
+                    }
+                    values_offset += bytes.len().next_multiple_of(2);
+                }
+            }
+
+            link = Some(block.len());
+            block.extend_from_slice(&[0; 4]);
+            block.extend_from_slice(&values);
+        }
+
+        match self.ifd_area {
+            Some((start, size)) => {
+                assert!(block.len() <= size, "IFDs need {} bytes, only {size} reserved", block.len());
+                data[start..start + block.len()].copy_from_slice(&block);
+            }
+            None => data.extend_from_slice(&block),
+        }
+        data
+    }
+}
+
+/// Parse `data` and return a reader plus its first IFD
+pub(crate) fn first_ifd(data: Vec<u8>) -> (TiffReader<InMemorySource>, ImageFileDirectory, Endian) {
+    let mut reader = TiffReader::new(InMemorySource::new(data));
+    let header = reader.read_header().unwrap();
+    let endian = header.endianness();
+    let ifd = reader.read_ifd(header.ifd_offset as usize, endian).unwrap();
+    (reader, ifd, endian)
+}
+
+/// Encode a little-endian IFD with no next link
+///
+/// Each entry is (tag, field type, count, value field); values that don't
+/// fit inline must already be in the file at the offset given.
+pub(crate) fn raw_le_ifd(entries: &[(u16, u16, u32, u32)]) -> Vec<u8> {
+    let mut bytes = (entries.len() as u16).to_le_bytes().to_vec();
+    for &(tag, field_type, count, value) in entries {
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&field_type.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0; 4]);
+    bytes
+}
+
+/// Encode a u16 in the given byte order
+pub(crate) fn u16_bytes(endian: Endian, value: u16) -> [u8; 2] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
+
+/// Encode a u32 in the given byte order
+pub(crate) fn u32_bytes(endian: Endian, value: u32) -> [u8; 4] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
@@ -0,0 +1,1058 @@
+// tiff-core/src/writer.rs
+//! TIFF writing support
+//!
+//! This module serializes IFDs and their image data back into TIFF bytes.
+//! A `TiffWriter` collects pages (each a set of tags plus raw strip or tile
+//! blocks) and lays them out as a classic TIFF file:
+//!
+//! - header (8 bytes)
+//! - for each page: the directories it points to (SubIFDs, EXIF, GPS),
+//!   its data blocks, then its IFD followed by any values too large to fit
+//!   in the 4-byte entry slot
+//!
+//! Strip/tile offsets and byte counts are computed during serialization, so
+//! pages only carry the data blocks themselves.
+//!
+//! `TiffBuilder` creates a single-page file from raw, uncompressed pixels.
+
+use crate::{
+    error::{Result, TiffError},
+    header::{Endian, TiffHeader},
+    ifd::{FieldType, ImageFileDirectory, TagValue},
+    reader::{TiffDataSource, TiffReader},
+    tags::{self, PhotometricInterpretation, PlanarConfiguration},
+    TiffFile,
+};
+use crate::prelude::*;
+
+/// Pointer tags whose directories are copied along with the page
+///
+/// SubIFDs (330) and the EXIF (34665), GPS (34853) and Interoperability
+/// (40965) IFD pointers. The pointed-to directories are copied and the
+/// pointers rewritten when the output is serialized.
+const SUB_DIRECTORY_TAGS: [u16; 4] = [
+    tags::tags::SUB_IFDS,
+    tags::tags::EXIF_IFD,
+    tags::tags::GPS_IFD,
+    tags::tags::INTEROPERABILITY_IFD,
+];
+
+/// JPEGInterchangeFormat (513), the offset of an old-style JPEG stream
+const JPEG_INTERCHANGE_FORMAT: u16 = 513;
+
+/// JPEGInterchangeFormatLength (514), the length of that stream
+const JPEG_INTERCHANGE_FORMAT_LENGTH: u16 = 514;
+
+/// FreeOffsets/FreeByteCounts (288/289)
+///
+/// These only describe unused space in the source file, so they're dropped.
+const FREE_SPACE_TAGS: [u16; 2] = [288, 289];
+
+/// Check whether `tag` holds an offset that the writer computes
+fn is_pointer_tag(tag: u16) -> bool {
+    SUB_DIRECTORY_TAGS.contains(&tag)
+        || FREE_SPACE_TAGS.contains(&tag)
+        || matches!(tag, JPEG_INTERCHANGE_FORMAT | JPEG_INTERCHANGE_FORMAT_LENGTH)
+}
+
+/// One page (IFD) waiting to be written
+#[derive(Debug, Clone)]
+struct WriterPage {
+    /// Tag values, excluding the strip/tile offset and byte count tags
+    entries: Vec<(u16, TagValue)>,
+    /// Raw (possibly compressed) strip or tile data, in order
+    blocks: Vec<Vec<u8>>,
+    /// Whether `blocks` are tiles rather than strips
+    tiled: bool,
+    /// Directories this page points to, by pointer tag
+    sub_directories: Vec<(u16, Vec<WriterPage>)>,
+    /// Old-style JPEG stream referenced by JPEGInterchangeFormat
+    jpeg_interchange: Option<Vec<u8>>,
+}
+
+/// Writer that assembles pages into a TIFF file
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tiff_core::{Endian, TiffFile};
+/// use tiff_core::writer::TiffWriter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let first = TiffFile::from_bytes(std::fs::read("page1.tif")?)?;
+/// let second = TiffFile::from_bytes(std::fs::read("page2.tif")?)?;
+///
+/// let mut writer = TiffWriter::new(Endian::Little);
+/// writer.append_from(&first, 0)?;
+/// writer.append_from(&second, 0)?;
+/// std::fs::write("merged.tif", writer.to_bytes()?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TiffWriter {
+    endian: Endian,
+    pages: Vec<WriterPage>,
+}
+
+impl TiffWriter {
+    /// Create an empty writer producing a file with the given byte order
+    pub fn new(endian: Endian) -> Self {
+        Self {
+            endian,
+            pages: Vec::new(),
+        }
+    }
+
+    /// Get the number of pages added so far
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Copy an IFD from an existing file as a new page
+    ///
+    /// All tags are copied, and the strip or tile data is copied verbatim
+    /// (still compressed), so no pixels are re-encoded. The data offsets are
+    /// rewritten when the output is serialized. Directories the page points
+    /// to (SubIFDs, EXIF/GPS/Interoperability IFDs) and an old-style JPEG
+    /// stream are copied the same way. FreeOffsets/FreeByteCounts are
+    /// dropped, since they only describe unused space in the source file.
+    ///
+    /// When the source's byte order differs from the writer's, uncompressed
+    /// samples wider than 8 bits are byte-swapped to match. Compressed data
+    /// can't be converted without re-encoding, so it's rejected.
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if `ifd_index` doesn't exist,
+    /// `UnsupportedFeature` if the data needs a byte-order conversion this
+    /// can't do, `MalformedFile` if the directory pointers form a cycle or
+    /// nest deeper than [`TiffFile::MAX_SUB_IFD_DEPTH`], or any error from
+    /// parsing the source tags or reading its image data
+    pub fn append_from<T: TiffDataSource>(&mut self, tiff: &TiffFile<T>, ifd_index: usize) -> Result<()> {
+        let ifd = tiff.get_ifd(ifd_index).ok_or(TiffError::OutOfBounds {
+            index: ifd_index,
+            max: tiff.image_count(),
+        })?;
+        let page = self.read_page(ifd, &tiff.reader, tiff.endianness(), &mut Vec::new())?;
+        self.pages.push(page);
+        Ok(())
+    }
+
+    /// Copy `ifd`, its data and the directories it points to
+    ///
+    /// `ancestors` holds the offsets of the directories that led here, so
+    /// a pointer back to one of them is caught instead of recursing forever.
+    fn read_page<T: TiffDataSource>(
+        &self,
+        ifd: &ImageFileDirectory,
+        reader: &TiffReader<T>,
+        endian: Endian,
+        ancestors: &mut Vec<usize>,
+    ) -> Result<WriterPage> {
+        let mut entries = Vec::with_capacity(ifd.entries.len());
+        for entry in &ifd.entries {
+            if tags::is_data_location_tag(entry.tag) || is_pointer_tag(entry.tag) {
+                continue;
+            }
+            entries.push((entry.tag, reader.parse_tag_value(entry, endian)?));
+        }
+
+        let mut blocks = Vec::new();
+        for (offset, count) in ifd.image_data_ranges(reader, endian)? {
+            blocks.push(reader.read_bytes_at(offset, count)?);
+        }
+        if endian != self.endian {
+            swap_sample_bytes(ifd, reader, endian, &mut blocks)?;
+        }
+
+        let mut sub_directories = Vec::new();
+        for tag in SUB_DIRECTORY_TAGS {
+            let mut children = Vec::new();
+            for offset in ifd.ifd_pointer_offsets(tag, reader, endian)? {
+                if offset == 0 {
+                    continue;
+                }
+                if ancestors.contains(&offset) {
+                    return Err(TiffError::MalformedFile {
+                        reason: format!("directory at offset {offset} points back to itself"),
+                    });
+                }
+                if ancestors.len() >= TiffFile::<T>::MAX_SUB_IFD_DEPTH {
+                    return Err(TiffError::MalformedFile {
+                        reason: format!(
+                            "directories are nested deeper than {} pointers",
+                            TiffFile::<T>::MAX_SUB_IFD_DEPTH
+                        ),
+                    });
+                }
+                let child = reader.read_ifd_at(offset, endian)?;
+                ancestors.push(offset);
+                children.push(self.read_page(&child, reader, endian, ancestors)?);
+                ancestors.pop();
+            }
+            if !children.is_empty() {
+                sub_directories.push((tag, children));
+            }
+        }
+
+        let jpeg_interchange = match ifd.get_tag_value(JPEG_INTERCHANGE_FORMAT, reader, endian)? {
+            Some(offset) => {
+                let length = ifd
+                    .get_tag_value(JPEG_INTERCHANGE_FORMAT_LENGTH, reader, endian)?
+                    .and_then(|v| v.as_u32())
+                    .ok_or_else(|| TiffError::InvalidTag {
+                        tag: JPEG_INTERCHANGE_FORMAT,
+                        reason: "JPEGInterchangeFormatLength is missing".to_string(),
+                    })?;
+                let offset = offset.as_u32().ok_or_else(|| TiffError::InvalidTag {
+                    tag: JPEG_INTERCHANGE_FORMAT,
+                    reason: "expected a single offset".to_string(),
+                })?;
+                Some(reader.read_bytes_at(offset as usize, length as usize)?)
+            }
+            None => None,
+        };
+
+        Ok(WriterPage {
+            entries,
+            blocks,
+            tiled: ifd.is_tiled(reader, endian)?,
+            sub_directories,
+            jpeg_interchange,
+        })
+    }
+
+    /// Serialize all pages into a complete TIFF file
+    ///
+    /// # Errors
+    /// Returns `UnsupportedFeature` if the output would exceed the 4 GiB
+    /// limit of classic TIFF offsets
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let endian = self.endian;
+        let mut out = Vec::new();
+        out.extend_from_slice(match endian {
+            Endian::Little => b"II",
+            Endian::Big => b"MM",
+        });
+        out.extend_from_slice(&encode_u16(endian, TiffHeader::MAGIC_NUMBER));
+        out.extend_from_slice(&[0; 4]);
+
+        // Position of the 4-byte link that should point at the next IFD
+        let mut link_position = 4;
+
+        for page in &self.pages {
+            let (ifd_offset, next_link) = write_page(&mut out, page, endian)?;
+            out[link_position..link_position + 4].copy_from_slice(&encode_u32(endian, ifd_offset));
+            link_position = next_link;
+        }
+
+        Ok(out)
+    }
+}
+
+/// Append a page's sub-directories, data blocks and IFD to `out`
+///
+/// Returns the offset of the page's IFD and the position of its next-IFD
+/// link. Sub-directories are written first, so their offsets are known
+/// when the page's pointer tags are filled in; their own links stay 0.
+fn write_page(out: &mut Vec<u8>, page: &WriterPage, endian: Endian) -> Result<(u32, usize)> {
+    let mut entries = page.entries.clone();
+    for (tag, children) in &page.sub_directories {
+        let mut offsets = Vec::with_capacity(children.len());
+        for child in children {
+            offsets.push(write_page(out, child, endian)?.0);
+        }
+        entries.push((*tag, TagValue::Longs(offsets)));
+    }
+
+    let mut offsets = Vec::with_capacity(page.blocks.len());
+    let mut byte_counts = Vec::with_capacity(page.blocks.len());
+    for block in &page.blocks {
+        pad_to_word(out);
+        offsets.push(to_offset(out.len())?);
+        byte_counts.push(to_offset(block.len())?);
+        out.extend_from_slice(block);
+    }
+
+    if let Some(stream) = &page.jpeg_interchange {
+        pad_to_word(out);
+        entries.push((JPEG_INTERCHANGE_FORMAT, TagValue::Longs(vec![to_offset(out.len())?])));
+        entries.push((JPEG_INTERCHANGE_FORMAT_LENGTH, TagValue::Longs(vec![to_offset(stream.len())?])));
+        out.extend_from_slice(stream);
+    }
+
+    let (offsets_tag, counts_tag) = if page.tiled {
+        (tags::tags::TILE_OFFSETS, tags::tags::TILE_BYTE_COUNTS)
+    } else {
+        (tags::tags::STRIP_OFFSETS, tags::tags::STRIP_BYTE_COUNTS)
+    };
+    if !page.blocks.is_empty() {
+        entries.push((offsets_tag, TagValue::Longs(offsets)));
+        entries.push((counts_tag, TagValue::Longs(byte_counts)));
+    }
+    entries.sort_by_key(|(tag, _)| *tag);
+
+    pad_to_word(out);
+    let ifd_offset = to_offset(out.len())?;
+    let link_position = write_ifd(out, &entries, endian)?;
+    Ok((ifd_offset, link_position))
+}
+
+/// Target strip size used when `rows_per_strip` isn't set
+const DEFAULT_STRIP_BYTES: usize = 8192;
+
+/// Builder for a single-page, uncompressed TIFF file
+///
+/// The builder writes the layout tags itself (ImageWidth, ImageLength,
+/// BitsPerSample, SamplesPerPixel, Compression, PlanarConfiguration,
+/// RowsPerStrip and the strip locations); everything else is added with
+/// `tag`. Pixels are chunky (interleaved) rows, each padded to a whole
+/// byte.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tiff_core::{Endian, TagValue};
+/// use tiff_core::tags::tags;
+/// use tiff_core::writer::TiffBuilder;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let pixels = vec![0u8; 64 * 48 * 3];
+/// let bytes = TiffBuilder::new(64, 48)
+///     .endian(Endian::Big)
+///     .samples(3, 8)
+///     .tag(tags::SOFTWARE, TagValue::Ascii("my-tool".to_string()))
+///     .image_data(pixels)
+///     .build()?;
+/// std::fs::write("out.tif", bytes)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TiffBuilder {
+    endian: Endian,
+    width: u32,
+    height: u32,
+    samples_per_pixel: u16,
+    bits_per_sample: u16,
+    photometric: Option<PhotometricInterpretation>,
+    rows_per_strip: Option<u32>,
+    entries: Vec<(u16, TagValue)>,
+    data: Vec<u8>,
+}
+
+impl TiffBuilder {
+    /// Start a little-endian, 8-bit grayscale image of the given size
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            endian: Endian::Little,
+            width,
+            height,
+            samples_per_pixel: 1,
+            bits_per_sample: 8,
+            photometric: None,
+            rows_per_strip: None,
+            entries: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Set the byte order of the output
+    pub fn endian(&mut self, endian: Endian) -> &mut Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Set the number of samples per pixel and the bits in each sample
+    pub fn samples(&mut self, samples_per_pixel: u16, bits_per_sample: u16) -> &mut Self {
+        self.samples_per_pixel = samples_per_pixel;
+        self.bits_per_sample = bits_per_sample;
+        self
+    }
+
+    /// Set the photometric interpretation
+    ///
+    /// Defaults to RGB for three or more samples and BlackIsZero otherwise.
+    pub fn photometric(&mut self, photometric: PhotometricInterpretation) -> &mut Self {
+        self.photometric = Some(photometric);
+        self
+    }
+
+    /// Set the number of rows in each strip
+    ///
+    /// Defaults to as many rows as fit in 8 KiB (at least one).
+    pub fn rows_per_strip(&mut self, rows: u32) -> &mut Self {
+        self.rows_per_strip = Some(rows);
+        self
+    }
+
+    /// Add a tag, replacing any earlier value for the same tag
+    pub fn tag(&mut self, tag: u16, value: TagValue) -> &mut Self {
+        self.entries.retain(|(existing, _)| *existing != tag);
+        self.entries.push((tag, value));
+        self
+    }
+
+    /// Set the raw pixel data, row by row
+    pub fn image_data(&mut self, data: Vec<u8>) -> &mut Self {
+        self.data = data;
+        self
+    }
+
+    /// Get the number of bytes in one row of pixels
+    fn row_bytes(&self) -> usize {
+        (self.width as usize * self.samples_per_pixel as usize * self.bits_per_sample as usize).div_ceil(8)
+    }
+
+    /// Serialize the image as a complete TIFF file
+    ///
+    /// # Errors
+    /// Returns `InvalidTag` if a tag added with `tag` is one the builder
+    /// writes itself, `MalformedFile` if the image data isn't exactly
+    /// `height` rows long, or any error from `TiffWriter`
+    pub fn build(&self) -> Result<Vec<u8>> {
+        for (tag, _) in &self.entries {
+            if tags::is_layout_tag(*tag)
+                || tags::is_data_location_tag(*tag)
+                || matches!(*tag, tags::tags::COMPRESSION | tags::tags::PLANAR_CONFIGURATION)
+            {
+                return Err(TiffError::InvalidTag {
+                    tag: *tag,
+                    reason: "layout tags are written by the builder".to_string(),
+                });
+            }
+        }
+
+        let row_bytes = self.row_bytes();
+        let expected = row_bytes * self.height as usize;
+        if self.data.len() != expected {
+            return Err(TiffError::MalformedFile {
+                reason: format!(
+                    "image data is {} bytes but {}x{} pixels need {expected}",
+                    self.data.len(),
+                    self.width,
+                    self.height
+                ),
+            });
+        }
+
+        let rows_per_strip = self
+            .rows_per_strip
+            .unwrap_or((DEFAULT_STRIP_BYTES / row_bytes.max(1)) as u32)
+            .clamp(1, self.height.max(1));
+        let strip_bytes = (row_bytes * rows_per_strip as usize).max(1);
+        let blocks: Vec<Vec<u8>> = self.data.chunks(strip_bytes).map(<[u8]>::to_vec).collect();
+
+        let photometric = self.photometric.unwrap_or(if self.samples_per_pixel >= 3 {
+            PhotometricInterpretation::Rgb
+        } else {
+            PhotometricInterpretation::BlackIsZero
+        });
+        let mut entries = vec![
+            (tags::tags::IMAGE_WIDTH, TagValue::Longs(vec![self.width])),
+            (tags::tags::IMAGE_LENGTH, TagValue::Longs(vec![self.height])),
+            (
+                tags::tags::BITS_PER_SAMPLE,
+                TagValue::Shorts(vec![self.bits_per_sample; self.samples_per_pixel as usize]),
+            ),
+            (tags::tags::COMPRESSION, TagValue::Shorts(vec![1])),
+            (tags::tags::SAMPLES_PER_PIXEL, TagValue::Shorts(vec![self.samples_per_pixel])),
+            (tags::tags::ROWS_PER_STRIP, TagValue::Longs(vec![rows_per_strip])),
+            (tags::tags::PLANAR_CONFIGURATION, TagValue::Shorts(vec![1])),
+        ];
+        if !self.entries.iter().any(|(tag, _)| *tag == tags::tags::PHOTOMETRIC_INTERPRETATION) {
+            entries.push((tags::tags::PHOTOMETRIC_INTERPRETATION, TagValue::Shorts(vec![photometric as u16])));
+        }
+        entries.extend(self.entries.iter().cloned());
+
+        let writer = TiffWriter {
+            endian: self.endian,
+            pages: vec![WriterPage {
+                entries,
+                blocks,
+                tiled: false,
+                sub_directories: Vec::new(),
+                jpeg_interchange: None,
+            }],
+        };
+        writer.to_bytes()
+    }
+}
+
+/// A pending change to one tag of one IFD
+#[derive(Debug, Clone)]
+struct TagEdit {
+    ifd_index: usize,
+    tag: u16,
+    /// New value, or None to remove the tag
+    value: Option<TagValue>,
+}
+
+/// Editor that re-serializes a file with modified tags
+///
+/// Created by `TiffFile::edit`. Changes are queued and applied in order
+/// when `save` is called; the source file is never modified. Image data is
+/// copied verbatim (still compressed) and relocated, and every IFD is
+/// rewritten with fresh offsets.
+///
+/// Like `TiffWriter::append_from`, saving copies the directories each page
+/// points to (SubIFDs, EXIF/GPS IFDs, etc.) and rewrites the pointers.
+/// Removing a pointer tag drops its directory; setting one is rejected.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tiff_core::{TagValue, TiffFile};
+/// use tiff_core::tags::tags;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = TiffFile::from_bytes(std::fs::read("photo.tif")?)?;
+/// let bytes = file
+///     .edit()
+///     .set_tag(0, tags::COPYRIGHT, TagValue::Ascii("(c) 2024 Example".to_string()))
+///     .remove_tag(0, tags::SOFTWARE)
+///     .save()?;
+/// std::fs::write("photo.tif", bytes)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TiffEditor<'a, T: TiffDataSource> {
+    file: &'a TiffFile<T>,
+    edits: Vec<TagEdit>,
+}
+
+impl<'a, T: TiffDataSource> TiffEditor<'a, T> {
+    /// Start editing `file`
+    pub fn new(file: &'a TiffFile<T>) -> Self {
+        Self { file, edits: Vec::new() }
+    }
+
+    /// Add a tag to the IFD at `ifd_index`, replacing any existing value
+    pub fn set_tag(&mut self, ifd_index: usize, tag: u16, value: TagValue) -> &mut Self {
+        self.edits.push(TagEdit { ifd_index, tag, value: Some(value) });
+        self
+    }
+
+    /// Remove a tag from the IFD at `ifd_index`, if present
+    pub fn remove_tag(&mut self, ifd_index: usize, tag: u16) -> &mut Self {
+        self.edits.push(TagEdit { ifd_index, tag, value: None });
+        self
+    }
+
+    /// Serialize the edited file
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if an edit names an IFD that doesn't exist,
+    /// `InvalidTag` if an edit touches the strip/tile offset or byte count
+    /// tags or sets a pointer tag (those are computed by the writer), or
+    /// any error from `TiffWriter`
+    pub fn save(&self) -> Result<Vec<u8>> {
+        let mut writer = TiffWriter::new(self.file.endianness());
+        for index in 0..self.file.image_count() {
+            writer.append_from(self.file, index)?;
+        }
+
+        for edit in &self.edits {
+            if tags::is_data_location_tag(edit.tag) {
+                return Err(TiffError::InvalidTag {
+                    tag: edit.tag,
+                    reason: "strip and tile locations are computed when saving".to_string(),
+                });
+            }
+            if edit.value.is_some() && is_pointer_tag(edit.tag) {
+                return Err(TiffError::InvalidTag {
+                    tag: edit.tag,
+                    reason: "pointers to other structures are computed when saving".to_string(),
+                });
+            }
+            let page = writer.pages.get_mut(edit.ifd_index).ok_or(TiffError::OutOfBounds {
+                index: edit.ifd_index,
+                max: self.file.image_count(),
+            })?;
+            page.entries.retain(|(tag, _)| *tag != edit.tag);
+            page.sub_directories.retain(|(tag, _)| *tag != edit.tag);
+            if matches!(edit.tag, JPEG_INTERCHANGE_FORMAT | JPEG_INTERCHANGE_FORMAT_LENGTH) {
+                page.jpeg_interchange = None;
+            }
+            if let Some(value) = &edit.value {
+                page.entries.push((edit.tag, value.clone()));
+            }
+        }
+
+        writer.to_bytes()
+    }
+}
+
+/// Byte-swap every sample in `blocks` to convert them out of `endian`
+///
+/// Only uncompressed data can be converted, and only samples that are a
+/// whole number of bytes; 8-bit and smaller samples need no conversion.
+fn swap_sample_bytes<T: TiffDataSource>(
+    ifd: &ImageFileDirectory,
+    reader: &TiffReader<T>,
+    endian: Endian,
+    blocks: &mut [Vec<u8>],
+) -> Result<()> {
+    let samples_per_pixel = ifd.samples_per_pixel_or_default(reader, endian)?;
+    let bits = ifd
+        .bits_per_sample(reader, endian)?
+        .filter(|bits| !bits.is_empty())
+        .unwrap_or_else(|| vec![1; samples_per_pixel.max(1) as usize]);
+    if bits.iter().all(|&b| b <= 8) {
+        return Ok(());
+    }
+    let unsupported = |what: &str| TiffError::UnsupportedFeature {
+        feature: format!("changing the byte order of {what} {bits:?}-bit samples"),
+    };
+    if bits.iter().any(|&b| b % 8 != 0) {
+        return Err(unsupported("bit-packed"));
+    }
+    let compression = ifd
+        .get_tag_value(tags::tags::COMPRESSION, reader, endian)?
+        .and_then(|v| v.as_u32())
+        .unwrap_or(1);
+    if compression != 1 {
+        return Err(unsupported("compressed"));
+    }
+    // The floating point predictor stores samples byte-shuffled
+    if ifd.predictor(reader, endian)? == Some(3) {
+        return Err(unsupported("float-predicted"));
+    }
+
+    let planar = ifd.planar_configuration(reader, endian)? == Some(PlanarConfiguration::Planar);
+    let blocks_per_plane = (blocks.len() / samples_per_pixel.max(1) as usize).max(1);
+    for (index, block) in blocks.iter_mut().enumerate() {
+        let sample_bytes: Vec<usize> = if planar {
+            let plane_bits = bits.get(index / blocks_per_plane).unwrap_or(&bits[0]);
+            vec![*plane_bits as usize / 8]
+        } else {
+            bits.iter().map(|&b| b as usize / 8).collect()
+        };
+        let mut position = 0;
+        for &size in sample_bytes.iter().cycle() {
+            let Some(sample) = block.get_mut(position..position + size) else {
+                break;
+            };
+            sample.reverse();
+            position += size;
+        }
+    }
+    Ok(())
+}
+
+/// Append an IFD (and its out-of-line values) at the end of `out`
+///
+/// Returns the position of the IFD's next-IFD link, which is written as 0.
+fn write_ifd(out: &mut Vec<u8>, entries: &[(u16, TagValue)], endian: Endian) -> Result<usize> {
+    let ifd_start = out.len();
+    let ifd_size = 2 + entries.len() * 12 + 4;
+    let mut values = Vec::new();
+
+    out.extend_from_slice(&encode_u16(endian, entries.len() as u16));
+    for (tag, value) in entries {
+        let value = &to_classic_value(*tag, value)?;
+        let bytes = encode_value(value, endian);
+        out.extend_from_slice(&encode_u16(endian, *tag));
+        out.extend_from_slice(&encode_u16(endian, value_field_type(value) as u16));
+        out.extend_from_slice(&encode_u32(endian, to_offset(value_count(value))?));
+        if bytes.len() <= 4 {
+            let mut slot = bytes;
+            slot.resize(4, 0);
+            out.extend_from_slice(&slot);
+        } else {
+            let value_offset = ifd_start + ifd_size + values.len();
+            out.extend_from_slice(&encode_u32(endian, to_offset(value_offset)?));
+            values.extend_from_slice(&bytes);
+            pad_to_word(&mut values);
+        }
+    }
+
+    let link_position = out.len();
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&values);
+    Ok(link_position)
+}
+
+/// Narrow BigTIFF LONG8/SLONG8 values to LONG/SLONG
+///
+/// Classic TIFF has no 64-bit integer types, so values copied from a
+/// BigTIFF file have to fit in 32 bits to be written.
+fn to_classic_value(tag: u16, value: &TagValue) -> Result<TagValue> {
+    let too_large = || TiffError::InvalidTag {
+        tag,
+        reason: "64-bit value doesn't fit in classic TIFF".to_string(),
+    };
+    match value {
+        TagValue::Longs8(v) => v
+            .iter()
+            .map(|&x| u32::try_from(x).map_err(|_| too_large()))
+            .collect::<Result<_>>()
+            .map(TagValue::Longs),
+        TagValue::SLongs8(v) => v
+            .iter()
+            .map(|&x| i32::try_from(x).map_err(|_| too_large()))
+            .collect::<Result<_>>()
+            .map(TagValue::SLongs),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Get the TIFF field type used to store a value
+fn value_field_type(value: &TagValue) -> FieldType {
+    match value {
+        TagValue::Bytes(_) => FieldType::Byte,
+        TagValue::Ascii(_) => FieldType::Ascii,
+        TagValue::Shorts(_) => FieldType::Short,
+        TagValue::Longs(_) => FieldType::Long,
+        TagValue::Rationals(_) => FieldType::Rational,
+        TagValue::SBytes(_) => FieldType::SByte,
+        TagValue::Undefined(_) => FieldType::Undefined,
+        TagValue::SShorts(_) => FieldType::SShort,
+        TagValue::SLongs(_) => FieldType::SLong,
+        TagValue::SRationals(_) => FieldType::SRational,
+        TagValue::Floats(_) => FieldType::Float,
+        TagValue::Doubles(_) => FieldType::Double,
+        TagValue::Longs8(_) => FieldType::Long8,
+        TagValue::SLongs8(_) => FieldType::SLong8,
+    }
+}
+
+/// Get the IFD entry count for a value (ASCII includes the null terminator)
+fn value_count(value: &TagValue) -> usize {
+    match value {
+        TagValue::Bytes(v) | TagValue::Undefined(v) => v.len(),
+        TagValue::Ascii(s) => s.len() + 1,
+        TagValue::Shorts(v) => v.len(),
+        TagValue::Longs(v) => v.len(),
+        TagValue::Rationals(v) => v.len(),
+        TagValue::SBytes(v) => v.len(),
+        TagValue::SShorts(v) => v.len(),
+        TagValue::SLongs(v) => v.len(),
+        TagValue::SRationals(v) => v.len(),
+        TagValue::Floats(v) => v.len(),
+        TagValue::Doubles(v) => v.len(),
+        TagValue::Longs8(v) => v.len(),
+        TagValue::SLongs8(v) => v.len(),
+    }
+}
+
+/// Encode a value's data in the given byte order
+fn encode_value(value: &TagValue, endian: Endian) -> Vec<u8> {
+    match value {
+        TagValue::Bytes(v) | TagValue::Undefined(v) => v.clone(),
+        TagValue::Ascii(s) => {
+            let mut bytes = s.as_bytes().to_vec();
+            bytes.push(0);
+            bytes
+        }
+        TagValue::SBytes(v) => v.iter().map(|&b| b as u8).collect(),
+        TagValue::Shorts(v) => v.iter().flat_map(|&x| encode_u16(endian, x)).collect(),
+        TagValue::SShorts(v) => v.iter().flat_map(|&x| encode_u16(endian, x as u16)).collect(),
+        TagValue::Longs(v) => v.iter().flat_map(|&x| encode_u32(endian, x)).collect(),
+        TagValue::SLongs(v) => v.iter().flat_map(|&x| encode_u32(endian, x as u32)).collect(),
+        TagValue::Floats(v) => v.iter().flat_map(|&x| encode_u32(endian, x.to_bits())).collect(),
+        TagValue::Rationals(v) => v
+            .iter()
+            .flat_map(|&(n, d)| [encode_u32(endian, n), encode_u32(endian, d)])
+            .flatten()
+            .collect(),
+        TagValue::SRationals(v) => v
+            .iter()
+            .flat_map(|&(n, d)| [encode_u32(endian, n as u32), encode_u32(endian, d as u32)])
+            .flatten()
+            .collect(),
+        TagValue::Doubles(v) => v
+            .iter()
+            .flat_map(|&x| match endian {
+                Endian::Little => x.to_le_bytes(),
+                Endian::Big => x.to_be_bytes(),
+            })
+            .collect(),
+        TagValue::Longs8(v) => v.iter().flat_map(|&x| encode_u64(endian, x)).collect(),
+        TagValue::SLongs8(v) => v.iter().flat_map(|&x| encode_u64(endian, x as u64)).collect(),
+    }
+}
+
+/// Convert a position to a classic TIFF 32-bit offset
+fn to_offset(position: usize) -> Result<u32> {
+    u32::try_from(position).map_err(|_| TiffError::UnsupportedFeature {
+        feature: "output larger than 4 GiB (requires BigTIFF)".to_string(),
+    })
+}
+
+/// Pad with a zero byte so the next write starts on a word boundary
+fn pad_to_word(out: &mut Vec<u8>) {
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Encode a u16 in the given byte order
+fn encode_u16(endian: Endian, value: u16) -> [u8; 2] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
+
+/// Encode a u32 in the given byte order
+fn encode_u32(endian: Endian, value: u32) -> [u8; 4] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
+
+/// Encode a u64 in the given byte order
+fn encode_u64(endian: Endian, value: u64) -> [u8; 8] {
+    match endian {
+        Endian::Little => value.to_le_bytes(),
+        Endian::Big => value.to_be_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{raw_le_ifd, TestTiff};
+
+    /// Build a single-page grayscale file filled with `fill`
+    fn page(endian: Endian, width: u16, height: u16, fill: u8) -> Vec<u8> {
+        let mut tiff = TestTiff::new(endian);
+        let pixels = vec![fill; width as usize * height as usize];
+        let strip = tiff.push_data(&pixels);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[width])
+            .short(tags::tags::IMAGE_LENGTH, &[height])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .short(tags::tags::PHOTOMETRIC_INTERPRETATION, &[1])
+            .ascii(tags::tags::SOFTWARE, "tiff-core tests")
+            .rational(tags::tags::X_RESOLUTION, &[(300, 1)])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[pixels.len() as u32]);
+        tiff.build()
+    }
+
+    #[test]
+    fn test_merge_two_pages() {
+        let first = TiffFile::from_bytes(page(Endian::Little, 4, 3, 0x11)).unwrap();
+        let second = TiffFile::from_bytes(page(Endian::Big, 5, 2, 0x22)).unwrap();
+
+        let mut writer = TiffWriter::new(Endian::Little);
+        writer.append_from(&first, 0).unwrap();
+        writer.append_from(&second, 0).unwrap();
+        assert_eq!(writer.page_count(), 2);
+
+        let merged = TiffFile::from_bytes(writer.to_bytes().unwrap()).unwrap();
+        assert_eq!(merged.image_count(), 2);
+        assert!(merged.is_valid().unwrap());
+
+        let info = merged.all_image_info().unwrap();
+        assert_eq!((info[0].width, info[0].height), (4, 3));
+        assert_eq!((info[1].width, info[1].height), (5, 2));
+
+        let endian = merged.endianness();
+        for (index, (fill, len)) in [(0x11u8, 12usize), (0x22, 10)].into_iter().enumerate() {
+            let ifd = merged.get_ifd(index).unwrap();
+            let ranges = ifd.image_data_ranges(&merged.reader, endian).unwrap();
+            assert_eq!(ranges.len(), 1);
+            let data = merged.reader.read_bytes_at(ranges[0].0, ranges[0].1).unwrap();
+            assert_eq!(data, vec![fill; len]);
+            assert_eq!(ifd.software(&merged.reader, endian).unwrap().as_deref(), Some("tiff-core tests"));
+            assert_eq!(ifd.x_resolution(&merged.reader, endian).unwrap(), Some(300.0));
+        }
+    }
+
+    #[test]
+    fn test_append_from_converts_byte_order() {
+        /// A 2x1 16-bit grayscale image holding 0x1234, 0xABCD
+        fn wide_page(endian: Endian, compression: u16) -> Vec<u8> {
+            let mut tiff = TestTiff::new(endian);
+            let pixels: Vec<u8> = [0x1234u16, 0xABCD]
+                .iter()
+                .flat_map(|&v| crate::testutil::u16_bytes(endian, v))
+                .collect();
+            let strip = tiff.push_data(&pixels);
+            tiff.add_ifd()
+                .short(tags::tags::IMAGE_WIDTH, &[2])
+                .short(tags::tags::IMAGE_LENGTH, &[1])
+                .short(tags::tags::BITS_PER_SAMPLE, &[16])
+                .short(tags::tags::COMPRESSION, &[compression])
+                .long(tags::tags::STRIP_OFFSETS, &[strip])
+                .long(tags::tags::STRIP_BYTE_COUNTS, &[4]);
+            tiff.build()
+        }
+
+        let pairs = [
+            (Endian::Big, Endian::Little),
+            (Endian::Little, Endian::Big),
+            (Endian::Big, Endian::Big),
+        ];
+        for (source, target) in pairs {
+            let file = TiffFile::from_bytes(wide_page(source, 1)).unwrap();
+            let mut writer = TiffWriter::new(target);
+            writer.append_from(&file, 0).unwrap();
+
+            let copy = TiffFile::from_bytes(writer.to_bytes().unwrap()).unwrap();
+            let ifd = copy.main_ifd().unwrap();
+            let image =
+                crate::reader::TiffImageReader::new(&copy.reader, ifd, copy.endianness())
+                    .unwrap();
+            assert_eq!(image.read_strip_as_u16(0).unwrap(), vec![0x1234, 0xABCD]);
+        }
+
+        // Compressed wide samples can only be copied in their own byte order
+        let file = TiffFile::from_bytes(wide_page(Endian::Big, 32773)).unwrap();
+        assert!(matches!(
+            TiffWriter::new(Endian::Little).append_from(&file, 0),
+            Err(TiffError::UnsupportedFeature { .. })
+        ));
+        assert!(TiffWriter::new(Endian::Big).append_from(&file, 0).is_ok());
+    }
+
+    #[test]
+    fn test_append_from_missing_ifd() {
+        let file = TiffFile::from_bytes(page(Endian::Little, 2, 2, 0)).unwrap();
+        let mut writer = TiffWriter::new(Endian::Little);
+        assert!(matches!(writer.append_from(&file, 3), Err(TiffError::OutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_edit_round_trip() {
+        let original = TiffFile::from_bytes(page(Endian::Big, 4, 3, 0x5A)).unwrap();
+        let bytes = original
+            .edit()
+            .set_tag(0, tags::tags::IMAGE_DESCRIPTION, TagValue::Ascii("edited description".to_string()))
+            .remove_tag(0, tags::tags::SOFTWARE)
+            .save()
+            .unwrap();
+
+        let edited = TiffFile::from_bytes(bytes).unwrap();
+        assert_eq!(edited.endianness(), Endian::Big);
+        let ifd = edited.main_ifd().unwrap();
+        let (reader, endian) = (&edited.reader, edited.endianness());
+        assert_eq!(ifd.image_description(reader, endian).unwrap().as_deref(), Some("edited description"));
+        assert_eq!(ifd.software(reader, endian).unwrap(), None);
+        assert_eq!(ifd.x_resolution(reader, endian).unwrap(), Some(300.0));
+
+        let ranges = ifd.image_data_ranges(reader, endian).unwrap();
+        assert_eq!(reader.read_bytes_at(ranges[0].0, ranges[0].1).unwrap(), vec![0x5A; 12]);
+    }
+
+    #[test]
+    fn test_edit_keeps_sub_directories() {
+        let mut tiff = TestTiff::new(Endian::Little);
+        let interop = tiff.push_data(&raw_le_ifd(&[(1, 2, 4, u32::from_le_bytes(*b"R98\0"))]));
+        let exif = tiff.push_data(&raw_le_ifd(&[(tags::tags::INTEROPERABILITY_IFD, 13, 1, interop)]));
+        let reduced = tiff.push_data(&[7, 9]);
+        let sub = tiff.push_data(&raw_le_ifd(&[
+            (tags::tags::IMAGE_WIDTH, 3, 1, 2),
+            (tags::tags::IMAGE_LENGTH, 3, 1, 1),
+            (tags::tags::BITS_PER_SAMPLE, 3, 1, 8),
+            (tags::tags::STRIP_OFFSETS, 4, 1, reduced),
+            (tags::tags::STRIP_BYTE_COUNTS, 4, 1, 2),
+        ]));
+        let strip = tiff.push_data(&[1, 2, 3, 4]);
+        tiff.add_ifd()
+            .short(tags::tags::IMAGE_WIDTH, &[4])
+            .short(tags::tags::IMAGE_LENGTH, &[1])
+            .short(tags::tags::BITS_PER_SAMPLE, &[8])
+            .long(tags::tags::STRIP_OFFSETS, &[strip])
+            .long(tags::tags::STRIP_BYTE_COUNTS, &[4])
+            .long(tags::tags::SUB_IFDS, &[sub])
+            .long(tags::tags::EXIF_IFD, &[exif]);
+        let file = TiffFile::from_bytes(tiff.build()).unwrap();
+
+        let saved = TiffFile::from_bytes(file.edit().save().unwrap()).unwrap();
+        let paths = saved.walk_all_ifds().unwrap();
+        let names: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+        assert_eq!(names, vec!["[0]", "[0, SubIFD, 0]", "[0, Exif]", "[0, Exif, Interop]"]);
+        let (reader, endian) = (&saved.reader, saved.endianness());
+        let ranges = paths[1].ifd.image_data_ranges(reader, endian).unwrap();
+        assert_eq!(reader.read_bytes_at(ranges[0].0, ranges[0].1).unwrap(), vec![7, 9]);
+        let index = paths[3].ifd.get_tag_value(1, reader, endian).unwrap().unwrap();
+        assert_eq!(index.as_string(), Some("R98"));
+
+        let bytes = file.edit().remove_tag(0, tags::tags::EXIF_IFD).save().unwrap();
+        let trimmed = TiffFile::from_bytes(bytes).unwrap();
+        assert_eq!(trimmed.walk_all_ifds().unwrap().len(), 2);
+
+        let result = file.edit().set_tag(0, tags::tags::EXIF_IFD, TagValue::Longs(vec![8])).save();
+        assert!(matches!(result, Err(TiffError::InvalidTag { .. })));
+    }
+
+    #[test]
+    fn test_edit_rejects_bad_edits() {
+        let file = TiffFile::from_bytes(page(Endian::Little, 2, 2, 0)).unwrap();
+        let result = file.edit().remove_tag(1, tags::tags::SOFTWARE).save();
+        assert!(matches!(result, Err(TiffError::OutOfBounds { index: 1, max: 1 })));
+
+        let result = file.edit().set_tag(0, tags::tags::STRIP_OFFSETS, TagValue::Longs(vec![8])).save();
+        assert!(matches!(result, Err(TiffError::InvalidTag { .. })));
+    }
+
+    #[test]
+    fn test_to_classic_value_narrows_64_bit_values() {
+        match to_classic_value(1000, &TagValue::Longs8(vec![1, u32::MAX as u64])).unwrap() {
+            TagValue::Longs(v) => assert_eq!(v, vec![1, u32::MAX]),
+            other => panic!("expected Longs, got {other:?}"),
+        }
+        match to_classic_value(1000, &TagValue::SLongs8(vec![-5])).unwrap() {
+            TagValue::SLongs(v) => assert_eq!(v, vec![-5]),
+            other => panic!("expected SLongs, got {other:?}"),
+        }
+        assert!(matches!(
+            to_classic_value(1000, &TagValue::Longs8(vec![1 << 32])),
+            Err(TiffError::InvalidTag { tag: 1000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_builder_round_trip() {
+        let pixels: Vec<u8> = (0..5 * 4 * 3).map(|i| i as u8).collect();
+        for endian in [Endian::Little, Endian::Big] {
+            let bytes = TiffBuilder::new(5, 4)
+                .endian(endian)
+                .samples(3, 8)
+                .rows_per_strip(3)
+                .tag(tags::tags::SOFTWARE, TagValue::Ascii("builder".to_string()))
+                .tag(tags::tags::X_RESOLUTION, TagValue::Rationals(vec![(72, 1)]))
+                .image_data(pixels.clone())
+                .build()
+                .unwrap();
+
+            let file = TiffFile::from_bytes(bytes).unwrap();
+            assert_eq!(file.endianness(), endian);
+            assert!(file.is_valid().unwrap());
+            let ifd = file.main_ifd().unwrap();
+            let (reader, endian) = (&file.reader, file.endianness());
+            assert_eq!(ifd.image_width(reader, endian).unwrap(), Some(5));
+            assert_eq!(ifd.image_height(reader, endian).unwrap(), Some(4));
+            assert_eq!(ifd.samples_per_pixel(reader, endian).unwrap(), Some(3));
+            assert_eq!(ifd.photometric_interpretation(reader, endian).unwrap(), Some(PhotometricInterpretation::Rgb));
+            assert_eq!(ifd.strip_offsets(reader, endian).unwrap().map(|offsets| offsets.len()), Some(2));
+            assert_eq!(ifd.software(reader, endian).unwrap().as_deref(), Some("builder"));
+            assert_eq!(ifd.x_resolution(reader, endian).unwrap(), Some(72.0));
+            assert_eq!(ifd.read_image(reader, endian).unwrap(), pixels);
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_input() {
+        let result = TiffBuilder::new(4, 4).image_data(vec![0; 15]).build();
+        assert!(matches!(result, Err(TiffError::MalformedFile { .. })));
+
+        let result = TiffBuilder::new(1, 1)
+            .tag(tags::tags::IMAGE_WIDTH, TagValue::Longs(vec![2]))
+            .image_data(vec![0])
+            .build();
+        assert!(matches!(result, Err(TiffError::InvalidTag { tag: 256, .. })));
+    }
+
+    #[test]
+    fn test_empty_writer_is_header_only() {
+        let bytes = TiffWriter::new(Endian::Big).to_bytes().unwrap();
+        assert_eq!(bytes, vec![b'M', b'M', 0, 42, 0, 0, 0, 0]);
+    }
+}